@@ -0,0 +1,81 @@
+//! Embeds build metadata for `teamy-windows version --verbose`.
+
+use std::path::Path;
+use std::process::Command;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+
+    // Only the CLI reports build metadata, library users shouldn't pay for the git calls
+    if std::env::var_os("CARGO_FEATURE_CLI").is_none() {
+        return;
+    }
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
+    let manifest_dir = Path::new(&manifest_dir);
+
+    println!(
+        "cargo:rustc-env=TEAMY_GIT_HASH={}",
+        git_hash().unwrap_or_else(unknown)
+    );
+    println!(
+        "cargo:rustc-env=TEAMY_BUILD_TIMESTAMP={}",
+        build_timestamp()
+    );
+
+    // Only rebuild when the commit, the sources behind the dirty flag or dependencies change,
+    // not on every build. A path that doesn't exist would make cargo rerun every time.
+    for path in [
+        "Cargo.lock",
+        "Cargo.toml",
+        "src",
+        ".git/HEAD",
+        ".git/refs",
+        ".git/packed-refs",
+        ".git/index",
+    ] {
+        if manifest_dir.join(path).exists() {
+            println!("cargo:rerun-if-changed={path}");
+        }
+    }
+    println!("cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH");
+}
+
+fn unknown() -> String {
+    "unknown".to_string()
+}
+
+/// Short commit hash, suffixed with `-dirty` when there are uncommitted changes.
+///
+/// Tracked files outside `src`, `Cargo.toml` and `Cargo.lock` only refresh the flag once the index changes.
+fn git_hash() -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--short=12", "HEAD"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let hash = String::from_utf8(output.stdout).ok()?.trim().to_string();
+    let dirty = Command::new("git")
+        .args(["status", "--porcelain", "--untracked-files=no"])
+        .output()
+        .is_ok_and(|status| !status.stdout.is_empty());
+    Some(if dirty { format!("{hash}-dirty") } else { hash })
+}
+
+/// Seconds since the Unix epoch, honouring `SOURCE_DATE_EPOCH` for reproducible builds.
+fn build_timestamp() -> u64 {
+    if let Some(epoch) = std::env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|epoch| epoch.parse().ok())
+    {
+        return epoch;
+    }
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
+}
@@ -8,43 +8,63 @@ use std::sync::atomic::AtomicBool;
 use std::sync::atomic::Ordering;
 use std::thread;
 use std::time::Duration;
+use teamy_windows::console::ConsoleWriter;
 use teamy_windows::console::hide_default_console_or_attach_ctrl_handler;
 use teamy_windows::console::is_inheriting_console;
+use teamy_windows::console::try_enable_ansi_support;
 use teamy_windows::event_loop::run_message_loop;
+use teamy_windows::hicon::OwnedHicon;
 use teamy_windows::hicon::application_icon::get_application_icon;
-use teamy_windows::hicon::get_icon_from_current_module;
 use teamy_windows::log::LOG_BUFFER;
+use teamy_windows::singleton::activation_message;
+use teamy_windows::singleton::single_instance_or_activate;
 use teamy_windows::tray::add_tray_icon;
-use teamy_windows::window::create_window_for_tray;
+use teamy_windows::tray::get_tray_icon_from_current_module;
+use teamy_windows::window::DpiAwareness;
+use teamy_windows::window::create_window_for_single_instance_tray;
+use teamy_windows::window::set_process_dpi_aware;
 use tracing::info;
 use tracing::level_filters::LevelFilter;
 use tracing_subscriber::EnvFilter;
-use tracing_subscriber::fmt::SubscriberBuilder;
-use tracing_subscriber::fmt::writer::MakeWriterExt;
+use tracing_subscriber::prelude::*;
 use tracing_subscriber::util::SubscriberInitExt;
 use windows::core::w;
 
 static HEARTBEAT_RUNNING: AtomicBool = AtomicBool::new(false);
 
+const INSTANCE_NAME: &str = r"Local\teamy-tray-console-demo";
+
 pub fn main() -> Result<()> {
     color_eyre::install()?;
 
     init_tracing();
 
+    // So the tray icon is loaded at the scaled size instead of being stretched
+    set_process_dpi_aware(DpiAwareness::default())?;
+
+    // A second launch asks the running instance to show its logs instead
+    let Some(_instance) = single_instance_or_activate(INSTANCE_NAME)? else {
+        return Ok(());
+    };
+
     let started_with_inherited_console = is_inheriting_console();
     hide_default_console_or_attach_ctrl_handler()?;
 
     configure_tray_console(TrayConsoleConfig {
         inherited_console_available: started_with_inherited_console,
         log_buffer: LOG_BUFFER.clone(),
+        activation_message: activation_message(INSTANCE_NAME)?,
     })?;
 
-    let window = create_window_for_tray(Some(window_proc))?;
+    let (window, _) = create_window_for_single_instance_tray(Some(window_proc), INSTANCE_NAME)?;
 
-    let icon = get_icon_from_current_module(w!("aaa_my_icon")).or_else(|e1| {
-        eprintln!("Failed to load embedded icon 'aaa_my_icon': {e1}");
-        get_application_icon()
-    })?;
+    // The tray keeps drawing the icon until the process exits, so it is never destroyed
+    let icon = get_tray_icon_from_current_module(w!("aaa_my_icon"))
+        .map(OwnedHicon::into_raw)
+        .or_else(|e1| {
+            eprintln!("Failed to load embedded icon 'aaa_my_icon': {e1}");
+            get_application_icon()
+        })?;
     let tooltip = w!("Tray Console Demo");
 
     add_tray_icon(window, icon, tooltip)?;
@@ -72,7 +92,19 @@ fn init_tracing() {
         ))
     });
 
-    let subscriber = SubscriberBuilder::default()
+    let console_layer = tracing_subscriber::fmt::layer()
+        .with_file(cfg!(debug_assertions))
+        .with_line_number(cfg!(debug_assertions))
+        .with_level(true)
+        .with_target(false)
+        .with_ansi(try_enable_ansi_support())
+        .with_thread_ids(false)
+        .with_thread_names(false)
+        .with_writer(ConsoleWriter);
+
+    // Always record colors in the buffer, even when the console we started with can't render them;
+    // replaying into the "show logs" console strips them again only if that console can't either
+    let buffer_layer = tracing_subscriber::fmt::layer()
         .with_file(cfg!(debug_assertions))
         .with_line_number(cfg!(debug_assertions))
         .with_level(true)
@@ -80,9 +112,12 @@ fn init_tracing() {
         .with_ansi(true)
         .with_thread_ids(false)
         .with_thread_names(false)
-        .with_env_filter(env_filter)
-        .with_writer(std::io::stderr.and(LOG_BUFFER.clone()))
-        .finish();
+        .with_writer(LOG_BUFFER.clone());
+
+    let subscriber = tracing_subscriber::registry()
+        .with(env_filter)
+        .with(console_layer)
+        .with(buffer_layer);
 
     if let Err(error) = subscriber.try_init() {
         eprintln!("Tracing already initialized? {error}");
@@ -1,18 +1,15 @@
 use crate::stop_heartbeat_logger;
-use eyre::Context;
 use eyre::Result;
 use eyre::eyre;
-use std::io::Write;
 use std::sync::OnceLock;
-use teamy_windows::console::console_attach;
-use teamy_windows::console::console_create;
-use teamy_windows::console::console_detach;
+use teamy_windows::console::TrayConsole;
 use teamy_windows::log::BufferSink;
+use teamy_windows::tray::TrayEvent;
 use teamy_windows::tray::WM_TASKBAR_CREATED;
 use teamy_windows::tray::WM_USER_TRAY_CALLBACK;
 use teamy_windows::tray::delete_tray_icon;
+use teamy_windows::tray::notify_taskbar_created;
 use teamy_windows::tray::re_add_tray_icon;
-use tracing::debug;
 use tracing::error;
 use tracing::info;
 use windows::Win32::Foundation::HWND;
@@ -20,7 +17,6 @@ use windows::Win32::Foundation::LPARAM;
 use windows::Win32::Foundation::LRESULT;
 use windows::Win32::Foundation::POINT;
 use windows::Win32::Foundation::WPARAM;
-use windows::Win32::System::Console::ATTACH_PARENT_PROCESS;
 use windows::Win32::UI::WindowsAndMessaging::*;
 use windows::core::PCWSTR;
 use windows::core::w;
@@ -34,6 +30,7 @@ const CMD_AHOY: usize = 0x1003;
 pub struct TrayConsoleConfig {
     pub inherited_console_available: bool,
     pub log_buffer: BufferSink,
+    pub activation_message: u32,
 }
 
 static CONFIG: OnceLock<TrayConsoleConfig> = OnceLock::new();
@@ -44,87 +41,19 @@ pub fn configure_tray_console(config: TrayConsoleConfig) -> Result<()> {
         .map_err(|_| eyre!("Tray console configuration may only be set once"))
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum ConsoleMode {
-    Detached,
-    Inherited,
-    Owned,
-}
-
 struct TrayConsoleState {
-    mode: ConsoleMode,
-    inherited_console_available: bool,
-    log_buffer: BufferSink,
+    console: TrayConsole,
+    activation_message: u32,
 }
 
 impl TrayConsoleState {
     fn new(config: TrayConsoleConfig) -> Self {
-        let mode = if config.inherited_console_available {
-            ConsoleMode::Inherited
-        } else {
-            ConsoleMode::Detached
-        };
         Self {
-            mode,
-            inherited_console_available: config.inherited_console_available,
-            log_buffer: config.log_buffer,
+            console: TrayConsole::new(config.log_buffer, config.inherited_console_available),
+            activation_message: config.activation_message,
         }
     }
 
-    fn can_show_logs(&self) -> bool {
-        self.mode != ConsoleMode::Owned
-    }
-
-    fn can_hide_logs(&self) -> bool {
-        self.mode == ConsoleMode::Owned
-    }
-
-    fn show_logs(&mut self) -> Result<()> {
-        if !self.can_show_logs() {
-            debug!("Show logs requested while already owning console");
-            return Ok(());
-        }
-
-        if self.mode == ConsoleMode::Inherited {
-            console_detach().wrap_err("Failed to detach from inherited console")?;
-        }
-
-        console_create().wrap_err("Failed to allocate dedicated console")?;
-        self.replay_buffer()
-            .wrap_err("Failed to replay buffered logs into new console")?;
-        self.mode = ConsoleMode::Owned;
-        info!("Console window allocated; new logs will stream live");
-        Ok(())
-    }
-
-    fn hide_logs(&mut self) -> Result<()> {
-        if !self.can_hide_logs() {
-            debug!("Hide logs requested while console is already hidden");
-            return Ok(());
-        }
-
-        console_detach().wrap_err("Failed to detach from dedicated console")?;
-        if self.inherited_console_available {
-            console_attach(ATTACH_PARENT_PROCESS)
-                .wrap_err("Failed to reattach to parent console")?;
-            self.mode = ConsoleMode::Inherited;
-            info!("Logs routed back to parent console");
-        } else {
-            self.mode = ConsoleMode::Detached;
-            info!("Logs hidden; no console attached");
-        }
-        Ok(())
-    }
-
-    fn replay_buffer(&self) -> Result<()> {
-        let mut stdout = std::io::stdout();
-        self.log_buffer
-            .replay(&mut stdout)
-            .wrap_err("Failed to write buffered logs to stdout")?;
-        stdout.flush().ok();
-        Ok(())
-    }
-
     fn show_context_menu(&mut self, hwnd: HWND) {
         unsafe {
             let _ = SetForegroundWindow(hwnd);
@@ -155,10 +84,10 @@ impl TrayConsoleState {
                 error!("Failed to populate context menu: {error}");
             }
 
-            if !self.can_show_logs() {
+            if !self.console.can_show_logs() {
                 let _ = EnableMenuItem(menu, CMD_SHOW_LOGS as u32, MF_BYCOMMAND | MF_GRAYED);
             }
-            if !self.can_hide_logs() {
+            if !self.console.can_hide_logs() {
                 let _ = EnableMenuItem(menu, CMD_HIDE_LOGS as u32, MF_BYCOMMAND | MF_GRAYED);
             }
         }
@@ -184,12 +113,12 @@ impl TrayConsoleState {
 
         match selection {
             CMD_SHOW_LOGS => {
-                if let Err(error) = self.show_logs() {
+                if let Err(error) = self.console.show_logs() {
                     error!("Failed to show logs: {error}");
                 }
             }
             CMD_HIDE_LOGS => {
-                if let Err(error) = self.hide_logs() {
+                if let Err(error) = self.console.hide_logs() {
                     error!("Failed to hide logs: {error}");
                 }
             }
@@ -227,7 +156,9 @@ fn drop_state(hwnd: HWND) {
     }
 }
 
-/// Safety: This function is an extern "system" callback.
+/// # Safety
+///
+/// This function is an extern "system" callback.
 pub unsafe extern "system" fn window_proc(
     hwnd: HWND,
     message: u32,
@@ -246,13 +177,13 @@ pub unsafe extern "system" fn window_proc(
             }
         },
         WM_USER_TRAY_CALLBACK => {
-            match lparam.0 as u32 {
-                WM_RBUTTONUP | WM_CONTEXTMENU => {
+            match TrayEvent::from_callback(wparam, lparam) {
+                TrayEvent::RightButtonUp | TrayEvent::ContextMenu => {
                     with_state(hwnd, |state| state.show_context_menu(hwnd));
                 }
-                WM_LBUTTONDBLCLK => {
+                TrayEvent::LeftDoubleClick => {
                     with_state(hwnd, |state| {
-                        if let Err(error) = state.show_logs() {
+                        if let Err(error) = state.console.show_logs() {
                             error!("Failed to show logs via double-click: {error}");
                         }
                     });
@@ -261,10 +192,20 @@ pub unsafe extern "system" fn window_proc(
             }
             LRESULT(0)
         }
+        m if with_state(hwnd, |state| state.activation_message == m).unwrap_or(false) => {
+            info!("Another instance was launched, showing logs");
+            with_state(hwnd, |state| {
+                if let Err(error) = state.console.show_and_focus_logs() {
+                    error!("Failed to show logs for another launch: {error}");
+                }
+            });
+            LRESULT(0)
+        }
         m if m == *WM_TASKBAR_CREATED => {
             if let Err(error) = re_add_tray_icon() {
                 error!("Failed to re-add tray icon after Explorer restart: {error}");
             }
+            notify_taskbar_created();
             LRESULT(0)
         }
         WM_CLOSE => {
@@ -1,11 +1,15 @@
 pub mod window_proc;
 use crate::window_proc::window_proc;
-use teamy_windows::console::attach_ctrl_c_handler;
+use teamy_windows::console::try_enable_ansi_support;
+use teamy_windows::event_loop::close_window_on_console_ctrl;
 use teamy_windows::event_loop::run_message_loop;
+use teamy_windows::hicon::OwnedHicon;
 use teamy_windows::hicon::application_icon::get_application_icon;
-use teamy_windows::hicon::get_icon_from_current_module;
-use teamy_windows::tray::add_tray_icon;
+use teamy_windows::tray::add_tray_icon_persistent;
+use teamy_windows::tray::get_tray_icon_from_current_module;
+use teamy_windows::window::DpiAwareness;
 use teamy_windows::window::create_window_for_tray;
+use teamy_windows::window::set_process_dpi_aware;
 use tracing::info;
 use tracing::level_filters::LevelFilter;
 use tracing_subscriber::EnvFilter;
@@ -21,7 +25,7 @@ pub fn main() -> eyre::Result<()> {
         .with_line_number(cfg!(debug_assertions))
         .with_level(true)
         .with_target(false)
-        .with_ansi(true)
+        .with_ansi(try_enable_ansi_support())
         .with_thread_ids(false)
         .with_thread_names(false)
         // .with_span_events(FmtSpan::NONE)
@@ -42,17 +46,24 @@ pub fn main() -> eyre::Result<()> {
 
     info!("Hello, world!");
 
+    // So the tray icon is loaded at the scaled size instead of being stretched
+    set_process_dpi_aware(DpiAwareness::default())?;
+
     let window = create_window_for_tray(Some(window_proc))?;
 
-    attach_ctrl_c_handler()?;
+    // Ctrl-C or closing the console goes through WM_CLOSE so the tray icon is removed
+    close_window_on_console_ctrl(window)?;
 
-    let icon = get_icon_from_current_module(w!("aaa_my_icon")).or_else(|e1| {
-        eprintln!("Failed to load embedded icon 'aaa_my_icon': {e1}");
-        get_application_icon()
-    })?;
+    // The tray keeps drawing the icon until the process exits, so it is never destroyed
+    let icon = get_tray_icon_from_current_module(w!("aaa_my_icon"))
+        .map(OwnedHicon::into_raw)
+        .or_else(|e1| {
+            eprintln!("Failed to load embedded icon 'aaa_my_icon': {e1}");
+            get_application_icon()
+        })?;
     let tooltip = w!("Demo Tray");
 
-    add_tray_icon(window, icon, tooltip)?;
+    add_tray_icon_persistent(window, icon, tooltip)?;
 
     run_message_loop(Some(window))?;
 
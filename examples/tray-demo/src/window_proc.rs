@@ -1,7 +1,6 @@
-use teamy_windows::tray::WM_TASKBAR_CREATED;
+use teamy_windows::tray::TrayEvent;
 use teamy_windows::tray::WM_USER_TRAY_CALLBACK;
 use teamy_windows::tray::delete_tray_icon;
-use teamy_windows::tray::re_add_tray_icon;
 use tracing::error;
 use tracing::info;
 use tracing::instrument;
@@ -15,7 +14,9 @@ use windows::Win32::UI::WindowsAndMessaging::WM_CLOSE;
 use windows::Win32::UI::WindowsAndMessaging::WM_DESTROY;
 use windows::Win32::UI::WindowsAndMessaging::*;
 
-/// Safety: This function is an extern "system" callback.
+/// # Safety
+///
+/// This function is an extern "system" callback.
 #[instrument]
 pub unsafe extern "system" fn window_proc(
     hwnd: HWND,
@@ -23,28 +24,13 @@ pub unsafe extern "system" fn window_proc(
     wparam: WPARAM,
     lparam: LPARAM,
 ) -> LRESULT {
-    // Cache the broadcast message atom once per process; cheap on subsequent calls.
-    // If you prefer, this can be hoisted into a static in this module too.
     match message {
         // Tray icon callback message (set via NOTIFYICONDATAW.uCallbackMessage = WM_USER + 1)
         WM_USER_TRAY_CALLBACK => {
-            match lparam.0 as u32 {
-                WM_LBUTTONDOWN => info!("Tray icon left button down"),
-                WM_LBUTTONUP => info!("Tray icon left button up"),
-                WM_LBUTTONDBLCLK => info!("Tray icon left button double click"),
-                WM_RBUTTONDOWN => info!("Tray icon right button down"),
-                WM_RBUTTONUP => info!("Tray icon right button up"),
-                WM_RBUTTONDBLCLK => info!("Tray icon right button double click"),
-                WM_CONTEXTMENU => info!("Tray icon context menu"),
-                WM_MOUSEMOVE => { /* ignore mouse move */ }
-                x => info!("Tray icon unknown event: {x}"),
-            }
-            LRESULT(0)
-        }
-        m if m == *WM_TASKBAR_CREATED => {
-            // Explorer/taskbar restarted; re-add our tray icon
-            if let Err(e) = re_add_tray_icon() {
-                error!("Failed to re-add tray icon after TaskbarCreated: {}", e);
+            match TrayEvent::from_callback(wparam, lparam) {
+                TrayEvent::MouseMove => { /* ignore mouse move */ }
+                TrayEvent::Other(x) => info!("Tray icon unknown event: {x}"),
+                event => info!(?event, "Tray icon event"),
             }
             LRESULT(0)
         }
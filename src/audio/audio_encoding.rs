@@ -0,0 +1,241 @@
+use crate::audio::AudioFormat;
+use crate::audio::RecordingInfo;
+use crate::audio::RecordingOptions;
+use crate::audio::create_wav_file;
+use crate::audio::parse_wav;
+use crate::audio::record_audio_with_options;
+use crate::audio::record_to_file_with_options;
+use crate::storage::write_atomic;
+use eyre::Context;
+use eyre::bail;
+use flacenc::component::BitRepr;
+use flacenc::error::Verify;
+use mp3lame_encoder::FlushNoGap;
+use mp3lame_encoder::InterleavedPcm;
+use mp3lame_encoder::MonoPcm;
+use std::path::Path;
+use std::time::Duration;
+use tracing::info;
+
+/// Container written for a recording.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum, arbitrary::Arbitrary))]
+pub enum AudioEncoding {
+    /// Uncompressed, in the captured sample format.
+    #[default]
+    Wav,
+    /// Lossless, as 16 or 24-bit integer samples.
+    Flac,
+    /// Lossy 128 kbps, mono or stereo only.
+    Mp3,
+}
+
+impl AudioEncoding {
+    /// Guesses the encoding from a `.wav`, `.flac` or `.mp3` extension.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "wav" => Some(Self::Wav),
+            "flac" => Some(Self::Flac),
+            "mp3" => Some(Self::Mp3),
+            _ => None,
+        }
+    }
+
+    /// Encodes interleaved PCM frames in `format`.
+    pub fn encode(self, audio_data: &[u8], format: AudioFormat) -> eyre::Result<Vec<u8>> {
+        match self {
+            Self::Wav => create_wav_file(audio_data, format),
+            Self::Flac => encode_flac(audio_data, format),
+            Self::Mp3 => encode_mp3(audio_data, format),
+        }
+    }
+}
+
+/// Like [`record_to_file_with_options`](crate::audio::record_to_file_with_options), writing `encoding` instead of WAV.
+///
+/// WAV is written as captured; other encodings convert the samples first.
+pub fn record_to_file_with_encoding(
+    device_id: &str,
+    duration: Duration,
+    path: &Path,
+    options: &RecordingOptions,
+    encoding: AudioEncoding,
+) -> eyre::Result<RecordingInfo> {
+    if encoding == AudioEncoding::Wav {
+        return record_to_file_with_options(device_id, duration, path, options);
+    }
+    let duration_ms = u64::try_from(duration.as_millis()).unwrap_or(u64::MAX);
+    let (wav_bytes, info) = record_audio_with_options(device_id, duration_ms, options)?;
+    let (format, audio_data) = parse_wav(&wav_bytes)?;
+    let bytes = encoding.encode(audio_data, format.into())?;
+    write_atomic(path, &bytes).wrap_err_with(|| format!("Failed to write {}", path.display()))?;
+    info!("Wrote {} bytes to {}", bytes.len(), path.display());
+    Ok(info)
+}
+
+/// Encodes PCM frames as FLAC. 32-bit and float samples are reduced to 24 bits, the most FLAC encoders accept.
+pub fn encode_flac(audio_data: &[u8], format: AudioFormat) -> eyre::Result<Vec<u8>> {
+    let bits_per_sample = if format.bits_per_sample == 16 && !format.is_float {
+        16
+    } else {
+        24
+    };
+    let samples = samples_at_depth(audio_data, format, bits_per_sample)?;
+
+    let config = flacenc::config::Encoder::default()
+        .into_verified()
+        .map_err(|(_, e)| eyre::eyre!("Invalid FLAC encoder config: {e:?}"))?;
+    let source = flacenc::source::MemSource::from_samples(
+        &samples,
+        format.channels as usize,
+        bits_per_sample as usize,
+        format.sample_rate as usize,
+    );
+    let stream = flacenc::encode_with_fixed_block_size(&config, source, config.block_size)
+        .map_err(|e| eyre::eyre!("Failed to encode FLAC: {e:?}"))?;
+
+    let mut sink = flacenc::bitsink::ByteSink::new();
+    stream
+        .write(&mut sink)
+        .map_err(|e| eyre::eyre!("Failed to write FLAC stream: {e:?}"))?;
+    Ok(sink.as_slice().to_vec())
+}
+
+/// Encodes PCM frames as 128 kbps MP3, converting samples to 16 bits.
+pub fn encode_mp3(audio_data: &[u8], format: AudioFormat) -> eyre::Result<Vec<u8>> {
+    let samples: Vec<i16> = samples_at_depth(audio_data, format, 16)?
+        .into_iter()
+        .map(|sample| sample as i16)
+        .collect();
+
+    let mut builder = mp3lame_encoder::Builder::new()
+        .ok_or_else(|| eyre::eyre!("Failed to create LAME encoder"))?;
+    builder
+        .set_num_channels(format.channels as u8)
+        .map_err(|e| eyre::eyre!("MP3 does not support {} channels: {e:?}", format.channels))?;
+    builder
+        .set_sample_rate(format.sample_rate)
+        .map_err(|e| eyre::eyre!("MP3 does not support {} Hz: {e:?}", format.sample_rate))?;
+    builder
+        .set_brate(mp3lame_encoder::Bitrate::Kbps128)
+        .map_err(|e| eyre::eyre!("Failed to set MP3 bitrate: {e:?}"))?;
+    builder
+        .set_quality(mp3lame_encoder::Quality::Best)
+        .map_err(|e| eyre::eyre!("Failed to set MP3 quality: {e:?}"))?;
+    let mut encoder = builder
+        .build()
+        .map_err(|e| eyre::eyre!("Failed to initialize LAME encoder: {e:?}"))?;
+
+    let mut output = Vec::with_capacity(mp3lame_encoder::max_required_buffer_size(samples.len()));
+    let encoded = match format.channels {
+        1 => encoder.encode(MonoPcm(&samples), output.spare_capacity_mut()),
+        2 => encoder.encode(InterleavedPcm(&samples), output.spare_capacity_mut()),
+        channels => bail!("MP3 only supports mono or stereo, not {channels} channels"),
+    }
+    .map_err(|e| eyre::eyre!("Failed to encode MP3: {e:?}"))?;
+    // SAFETY: the encoder initialized this many bytes of the spare capacity
+    unsafe { output.set_len(output.len() + encoded) };
+
+    output.reserve(7200);
+    let flushed = encoder
+        .flush::<FlushNoGap>(output.spare_capacity_mut())
+        .map_err(|e| eyre::eyre!("Failed to flush MP3 encoder: {e:?}"))?;
+    // SAFETY: as above
+    unsafe { output.set_len(output.len() + flushed) };
+    Ok(output)
+}
+
+/// Reads interleaved samples of `format` as integers of `bits_per_sample` (16 or 24) bits.
+fn samples_at_depth(
+    audio_data: &[u8],
+    format: AudioFormat,
+    bits_per_sample: u16,
+) -> eyre::Result<Vec<i32>> {
+    let max = ((1i64 << (bits_per_sample - 1)) - 1) as f32;
+    let samples = match (format.bits_per_sample, format.is_float) {
+        (16, false) => audio_data
+            .chunks_exact(2)
+            .map(|c| (i16::from_le_bytes([c[0], c[1]]) as i32) << (bits_per_sample - 16))
+            .collect(),
+        (24, false) => audio_data
+            .chunks_exact(3)
+            .map(|c| (i32::from_le_bytes([0, c[0], c[1], c[2]]) >> 8) >> (24 - bits_per_sample))
+            .collect(),
+        (32, false) => audio_data
+            .chunks_exact(4)
+            .map(|c| i32::from_le_bytes([c[0], c[1], c[2], c[3]]) >> (32 - bits_per_sample))
+            .collect(),
+        (32, true) => audio_data
+            .chunks_exact(4)
+            .map(|c| {
+                let sample = f32::from_le_bytes([c[0], c[1], c[2], c[3]]);
+                (sample.clamp(-1.0, 1.0) * max) as i32
+            })
+            .collect(),
+        (bits, is_float) => bail!(
+            "Unsupported sample format: {} bits {}",
+            bits,
+            if is_float { "float" } else { "int" }
+        ),
+    };
+    Ok(samples)
+}
+
+#[cfg(test)]
+mod test {
+    use super::AudioEncoding;
+    use super::samples_at_depth;
+    use crate::audio::AudioFormat;
+    use std::path::Path;
+
+    #[test]
+    fn converts_sample_depths() -> eyre::Result<()> {
+        let int_16: Vec<u8> = [0x1234i16, -2]
+            .iter()
+            .flat_map(|s| s.to_le_bytes())
+            .collect();
+        assert_eq!(
+            samples_at_depth(&int_16, AudioFormat::for_test(1, 16_000, 16, false), 24)?,
+            vec![0x12_3400, -0x200]
+        );
+
+        let int_24 = [0x56, 0x34, 0x12, 0xFF, 0xFF, 0xFF];
+        assert_eq!(
+            samples_at_depth(&int_24, AudioFormat::for_test(1, 16_000, 24, false), 16)?,
+            vec![0x1234, -1]
+        );
+
+        let float: Vec<u8> = [1.0f32, -2.0]
+            .iter()
+            .flat_map(|s| s.to_le_bytes())
+            .collect();
+        assert_eq!(
+            samples_at_depth(&float, AudioFormat::for_test(1, 16_000, 32, true), 16)?,
+            vec![32767, -32767]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn encodes_flac_and_mp3() -> eyre::Result<()> {
+        let samples: Vec<u8> = (0..16_000)
+            .map(|i| ((i as f32 / 16.0).sin() * 8000.0) as i16)
+            .flat_map(|s| s.to_le_bytes())
+            .collect();
+        let flac =
+            AudioEncoding::Flac.encode(&samples, AudioFormat::for_test(1, 16_000, 16, false))?;
+        assert!(flac.starts_with(b"fLaC"));
+        let mp3 =
+            AudioEncoding::Mp3.encode(&samples, AudioFormat::for_test(1, 16_000, 16, false))?;
+        assert!(!mp3.is_empty());
+        assert!(mp3.len() < samples.len());
+
+        assert_eq!(
+            AudioEncoding::from_path(Path::new("out.FLAC")),
+            Some(AudioEncoding::Flac)
+        );
+        assert_eq!(AudioEncoding::from_path(Path::new("out")), None);
+        Ok(())
+    }
+}
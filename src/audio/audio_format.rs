@@ -0,0 +1,222 @@
+use crate::audio::WavFormat;
+use crate::audio::WavSampleFormat;
+use eyre::bail;
+use eyre::ensure;
+use std::time::Duration;
+use windows::Win32::Media::Audio::WAVE_FORMAT_PCM;
+use windows::Win32::Media::Audio::WAVEFORMATEX;
+use windows::Win32::Media::Audio::WAVEFORMATEXTENSIBLE;
+use windows::Win32::Media::KernelStreaming::KSDATAFORMAT_SUBTYPE_PCM;
+use windows::Win32::Media::KernelStreaming::WAVE_FORMAT_EXTENSIBLE;
+use windows::Win32::Media::Multimedia::KSDATAFORMAT_SUBTYPE_IEEE_FLOAT;
+use windows::Win32::Media::Multimedia::WAVE_FORMAT_IEEE_FLOAT;
+
+/// Layout of interleaved audio frames, shared by capture and WAV writing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AudioFormat {
+    pub channels: u16,
+    pub sample_rate: u32,
+    /// Container size of each sample, e.g. 24 for packed 3-byte samples.
+    pub bits_per_sample: u16,
+    /// IEEE float samples rather than signed integers.
+    pub is_float: bool,
+}
+
+impl AudioFormat {
+    /// Reads the format of a `WAVEFORMATEX`, such as the one returned by `IAudioClient::GetMixFormat`.
+    ///
+    /// Float vs integer samples is decided by `wFormatTag`, or by the `SubFormat` GUID of a
+    /// `WAVEFORMATEXTENSIBLE`, since the bit depth alone can't tell 32-bit float from 32-bit int.
+    /// Errors for encodings other than PCM and IEEE float.
+    ///
+    /// # Safety
+    ///
+    /// `format` must point to a valid `WAVEFORMATEX`, followed by the rest of a `WAVEFORMATEXTENSIBLE`
+    /// when `wFormatTag` is `WAVE_FORMAT_EXTENSIBLE`.
+    pub unsafe fn from_waveformatex(format: *const WAVEFORMATEX) -> eyre::Result<Self> {
+        // Copy the fields we need to avoid unaligned reference issues (WAVEFORMATEX is packed)
+        let fmt = unsafe { *format };
+        let is_float = match fmt.wFormatTag as u32 {
+            WAVE_FORMAT_PCM => false,
+            WAVE_FORMAT_IEEE_FLOAT => true,
+            WAVE_FORMAT_EXTENSIBLE => {
+                ensure!(
+                    fmt.cbSize >= 22,
+                    "WAVEFORMATEXTENSIBLE cbSize is {}, expected at least 22",
+                    fmt.cbSize
+                );
+                let extensible = unsafe { *(format as *const WAVEFORMATEXTENSIBLE) };
+                match extensible.SubFormat {
+                    KSDATAFORMAT_SUBTYPE_PCM => false,
+                    KSDATAFORMAT_SUBTYPE_IEEE_FLOAT => true,
+                    sub_format => bail!("Unsupported sub-format {:?}", sub_format),
+                }
+            }
+            tag => bail!("Unsupported format tag {:#06X}", tag),
+        };
+        Ok(Self {
+            channels: fmt.nChannels,
+            sample_rate: fmt.nSamplesPerSec,
+            bits_per_sample: fmt.wBitsPerSample,
+            is_float,
+        })
+    }
+
+    /// A plain `WAVEFORMATEX` describing this format, for `IsFormatSupported` and `Initialize`.
+    pub fn to_waveformatex(&self) -> WAVEFORMATEX {
+        WAVEFORMATEX {
+            wFormatTag: if self.is_float {
+                WAVE_FORMAT_IEEE_FLOAT as u16
+            } else {
+                WAVE_FORMAT_PCM as u16
+            },
+            nChannels: self.channels,
+            nSamplesPerSec: self.sample_rate,
+            nAvgBytesPerSec: self.bytes_per_second() as u32,
+            nBlockAlign: self.bytes_per_frame() as u16,
+            wBitsPerSample: self.bits_per_sample,
+            cbSize: 0,
+        }
+    }
+
+    pub fn bytes_per_sample(&self) -> usize {
+        self.bits_per_sample.div_ceil(8) as usize
+    }
+
+    /// Size of one frame, one sample for every channel (`nBlockAlign`).
+    pub fn bytes_per_frame(&self) -> usize {
+        self.channels as usize * self.bytes_per_sample()
+    }
+
+    /// Bytes per second of audio (`nAvgBytesPerSec`).
+    pub fn bytes_per_second(&self) -> usize {
+        self.sample_rate as usize * self.bytes_per_frame()
+    }
+
+    /// Number of whole frames in `byte_len` bytes, or 0 for a degenerate format without channels or samples.
+    pub fn frames_in(&self, byte_len: usize) -> usize {
+        byte_len.checked_div(self.bytes_per_frame()).unwrap_or(0)
+    }
+
+    /// Playback duration of `byte_len` bytes of audio in this format, or zero for a degenerate format.
+    pub fn duration_of(&self, byte_len: usize) -> Duration {
+        if self.sample_rate == 0 {
+            return Duration::ZERO;
+        }
+        Duration::from_secs_f64(self.frames_in(byte_len) as f64 / self.sample_rate as f64)
+    }
+}
+
+impl From<WavFormat> for AudioFormat {
+    fn from(format: WavFormat) -> Self {
+        Self {
+            channels: format.channels,
+            sample_rate: format.sample_rate,
+            bits_per_sample: format.bits_per_sample,
+            is_float: format.sample_format == WavSampleFormat::Float,
+        }
+    }
+}
+
+#[cfg(test)]
+impl AudioFormat {
+    /// Shorthand for building formats in tests.
+    pub(crate) fn for_test(
+        channels: u16,
+        sample_rate: u32,
+        bits_per_sample: u16,
+        is_float: bool,
+    ) -> Self {
+        Self {
+            channels,
+            sample_rate,
+            bits_per_sample,
+            is_float,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::AudioFormat;
+    use std::time::Duration;
+    use windows::Win32::Media::Audio::WAVEFORMATEXTENSIBLE;
+    use windows::Win32::Media::Audio::WAVEFORMATEXTENSIBLE_0;
+    use windows::Win32::Media::KernelStreaming::KSDATAFORMAT_SUBTYPE_PCM;
+    use windows::Win32::Media::KernelStreaming::WAVE_FORMAT_EXTENSIBLE;
+    use windows::Win32::Media::Multimedia::KSDATAFORMAT_SUBTYPE_IEEE_FLOAT;
+
+    fn extensible(format: AudioFormat, sub_format: windows::core::GUID) -> WAVEFORMATEXTENSIBLE {
+        let mut header = format.to_waveformatex();
+        header.wFormatTag = WAVE_FORMAT_EXTENSIBLE as u16;
+        header.cbSize = 22;
+        WAVEFORMATEXTENSIBLE {
+            Format: header,
+            Samples: WAVEFORMATEXTENSIBLE_0 {
+                wValidBitsPerSample: format.bits_per_sample,
+            },
+            dwChannelMask: 0x3,
+            SubFormat: sub_format,
+        }
+    }
+
+    #[test]
+    fn detects_float_from_format_tag_and_sub_format() -> eyre::Result<()> {
+        let float = AudioFormat {
+            channels: 2,
+            sample_rate: 48_000,
+            bits_per_sample: 32,
+            is_float: true,
+        };
+        let int = AudioFormat {
+            is_float: false,
+            ..float
+        };
+
+        for format in [float, int] {
+            let plain = format.to_waveformatex();
+            assert_eq!(unsafe { AudioFormat::from_waveformatex(&plain) }?, format);
+        }
+
+        // 32-bit integer WAVEFORMATEXTENSIBLE must not be mistaken for float
+        let int_extensible = extensible(int, KSDATAFORMAT_SUBTYPE_PCM);
+        let parsed = unsafe { AudioFormat::from_waveformatex(&int_extensible.Format) }?;
+        assert_eq!(parsed, int);
+
+        let float_extensible = extensible(float, KSDATAFORMAT_SUBTYPE_IEEE_FLOAT);
+        let parsed = unsafe { AudioFormat::from_waveformatex(&float_extensible.Format) }?;
+        assert_eq!(parsed, float);
+        Ok(())
+    }
+
+    #[test]
+    fn frame_arithmetic() {
+        let format = AudioFormat {
+            channels: 2,
+            sample_rate: 48_000,
+            bits_per_sample: 24,
+            is_float: false,
+        };
+        assert_eq!(format.bytes_per_frame(), 6);
+        assert_eq!(format.bytes_per_second(), 288_000);
+        assert_eq!(format.frames_in(13), 2);
+        assert_eq!(format.duration_of(144_000), Duration::from_millis(500));
+    }
+
+    #[test]
+    fn degenerate_formats_have_no_frames() {
+        let format = AudioFormat {
+            channels: 0,
+            sample_rate: 0,
+            bits_per_sample: 16,
+            is_float: false,
+        };
+        assert_eq!(format.frames_in(1024), 0);
+        assert_eq!(format.duration_of(1024), Duration::ZERO);
+        let no_rate = AudioFormat {
+            channels: 2,
+            ..format
+        };
+        assert_eq!(no_rate.duration_of(1024), Duration::ZERO);
+    }
+}
@@ -1,20 +1,22 @@
+use crate::audio::DeviceFlow;
 use crate::audio::TeamyImmDeviceIconPath;
+use crate::audio::cached_device_enumerator;
 use crate::audio::imm_device::TeamyImmDevice;
 use crate::audio::imm_device_id::TeamyImmDeviceId;
 use crate::com::com_guard::ComGuard;
+use crate::com::property_store::PropertyStore;
+use eyre::Context;
+use std::sync::mpsc;
+use std::time::Duration;
 use windows::Win32::Devices::Properties::DEVPKEY_Device_FriendlyName;
-use windows::Win32::Foundation::PROPERTYKEY;
 use windows::Win32::Media::Audio::DEVICE_STATE_ACTIVE;
 use windows::Win32::Media::Audio::IMMDevice;
 use windows::Win32::Media::Audio::IMMDeviceCollection;
 use windows::Win32::Media::Audio::IMMDeviceEnumerator;
 use windows::Win32::Media::Audio::MMDeviceEnumerator;
-use windows::Win32::Media::Audio::eCapture;
 use windows::Win32::Media::Audio::eMultimedia;
 use windows::Win32::System::Com::CLSCTX_ALL;
 use windows::Win32::System::Com::CoCreateInstance;
-use windows::Win32::System::Com::STGM_READ;
-use windows::Win32::UI::Shell::PropertiesSystem::IPropertyStore;
 
 pub fn list_audio_input_devices() -> eyre::Result<Vec<TeamyImmDevice>> {
     let _com_guard = ComGuard::new()?;
@@ -22,11 +24,89 @@ pub fn list_audio_input_devices() -> eyre::Result<Vec<TeamyImmDevice>> {
     let enumerator: IMMDeviceEnumerator =
         unsafe { CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL) }?;
 
-    let default_device = unsafe { enumerator.GetDefaultAudioEndpoint(eCapture, eMultimedia) }?;
+    list_audio_input_devices_with(&enumerator)
+}
+
+/// Like [`list_audio_input_devices`], but gives up after `timeout`.
+///
+/// Some audio drivers can block `CoCreateInstance` or `EnumAudioEndpoints` for a long time,
+/// so the enumeration runs on a worker thread and this returns an error once the budget is spent.
+/// A timed-out worker can't be interrupted; it finishes in the background and its result is discarded.
+pub fn list_audio_input_devices_timeout(timeout: Duration) -> eyre::Result<Vec<TeamyImmDevice>> {
+    list_with_timeout(timeout, list_audio_input_devices)
+}
+
+/// Runs `list` on a worker thread, erroring if it hasn't finished within `timeout`.
+fn list_with_timeout(
+    timeout: Duration,
+    list: impl FnOnce() -> eyre::Result<Vec<TeamyImmDevice>> + Send + 'static,
+) -> eyre::Result<Vec<TeamyImmDevice>> {
+    let (tx, rx) = mpsc::sync_channel(1);
+    std::thread::Builder::new()
+        .name("list-audio-input-devices".to_string())
+        .spawn(move || {
+            _ = tx.send(list());
+        })
+        .wrap_err("Failed to spawn audio device enumeration thread")?;
+
+    match rx.recv_timeout(timeout) {
+        Ok(devices) => devices,
+        Err(mpsc::RecvTimeoutError::Timeout) => {
+            eyre::bail!("Listing audio input devices timed out after {timeout:?}")
+        }
+        Err(mpsc::RecvTimeoutError::Disconnected) => {
+            eyre::bail!("Audio device enumeration thread exited without a result")
+        }
+    }
+}
+
+/// Lists active output devices, which can be recorded in loopback mode to capture system audio.
+pub fn list_audio_output_devices() -> eyre::Result<Vec<TeamyImmDevice>> {
+    let _com_guard = ComGuard::new()?;
+
+    let enumerator: IMMDeviceEnumerator =
+        unsafe { CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL) }?;
+
+    list_audio_devices_with(&enumerator, DeviceFlow::Render)
+}
+
+/// Lists input devices, followed by output devices when `include_render` is set.
+pub fn list_audio_devices(include_render: bool) -> eyre::Result<Vec<TeamyImmDevice>> {
+    let _com_guard = ComGuard::new()?;
+
+    let enumerator: IMMDeviceEnumerator =
+        unsafe { CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL) }?;
+
+    let mut devices = list_audio_devices_with(&enumerator, DeviceFlow::Capture)?;
+    if include_render {
+        devices.extend(list_audio_devices_with(&enumerator, DeviceFlow::Render)?);
+    }
+    Ok(devices)
+}
+
+/// Like [`list_audio_input_devices`], but reuses this thread's [`cached_device_enumerator`].
+/// Prefer this when re-listing often, e.g. every time a settings screen opens.
+pub fn list_audio_input_devices_cached() -> eyre::Result<Vec<TeamyImmDevice>> {
+    let enumerator = cached_device_enumerator()?;
+    list_audio_input_devices_with(&enumerator)
+}
+
+fn list_audio_input_devices_with(
+    enumerator: &IMMDeviceEnumerator,
+) -> eyre::Result<Vec<TeamyImmDevice>> {
+    list_audio_devices_with(enumerator, DeviceFlow::Capture)
+}
+
+fn list_audio_devices_with(
+    enumerator: &IMMDeviceEnumerator,
+    flow: DeviceFlow,
+) -> eyre::Result<Vec<TeamyImmDevice>> {
+    let default_device =
+        unsafe { enumerator.GetDefaultAudioEndpoint(flow.as_edataflow(), eMultimedia) }?;
     let default_device_id = TeamyImmDeviceId::new(unsafe { default_device.GetId()? })?;
 
     let collection: IMMDeviceCollection =
-        unsafe { enumerator.EnumAudioEndpoints(eCapture, DEVICE_STATE_ACTIVE) }?;
+        unsafe { enumerator.EnumAudioEndpoints(flow.as_edataflow(), DEVICE_STATE_ACTIVE) }?;
     let count = unsafe { collection.GetCount() }?;
 
     let mut rtn = Vec::new();
@@ -42,13 +122,12 @@ pub fn list_audio_input_devices() -> eyre::Result<Vec<TeamyImmDevice>> {
         let is_default = default_device_id == device_id;
 
         // Get the device friendly name
-        let device_property_store: IPropertyStore = unsafe { device.OpenPropertyStore(STGM_READ)? };
-        let name = unsafe {
-            device_property_store
-                .GetValue(&DEVPKEY_Device_FriendlyName as *const _ as *const PROPERTYKEY)
-        }
-        .map(|prop_variant| prop_variant.to_string())
-        .unwrap_or_else(|_| "(Unknown Device)".to_string());
+        let device_property_store = PropertyStore::from_device(&device)?;
+        let name = device_property_store
+            .get_string(DEVPKEY_Device_FriendlyName)
+            .ok()
+            .flatten()
+            .unwrap_or_else(|| "(Unknown Device)".to_string());
 
         // Get the device icon path
         let device_icon = TeamyImmDeviceIconPath::from_property_store(&device_property_store)
@@ -62,6 +141,7 @@ pub fn list_audio_input_devices() -> eyre::Result<Vec<TeamyImmDevice>> {
             name,
             is_default,
             icon: device_icon,
+            flow,
         });
     }
     Ok(rtn)
@@ -70,6 +150,7 @@ pub fn list_audio_input_devices() -> eyre::Result<Vec<TeamyImmDevice>> {
 #[cfg(test)]
 mod test {
     use std::path::PathBuf;
+    use std::time::Duration;
 
     #[test]
     fn it_works() -> eyre::Result<()> {
@@ -97,4 +178,33 @@ mod test {
         }
         Ok(())
     }
+
+    #[test]
+    fn timeout_lists_same_devices() -> eyre::Result<()> {
+        let devices = crate::audio::list_audio_input_devices()?;
+        let within_timeout =
+            crate::audio::list_audio_input_devices_timeout(Duration::from_secs(30))?;
+        assert_eq!(
+            devices.iter().map(|d| &d.id).collect::<Vec<_>>(),
+            within_timeout.iter().map(|d| &d.id).collect::<Vec<_>>()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn slow_enumeration_times_out() {
+        let result = super::list_with_timeout(Duration::from_millis(10), || {
+            std::thread::sleep(Duration::from_secs(1));
+            Ok(Vec::new())
+        });
+        let error = result.unwrap_err();
+        assert!(error.to_string().contains("timed out"));
+    }
+
+    #[test]
+    fn fast_enumeration_returns_its_result() -> eyre::Result<()> {
+        let devices = super::list_with_timeout(Duration::from_secs(30), || Ok(Vec::new()))?;
+        assert!(devices.is_empty());
+        Ok(())
+    }
 }
@@ -1,32 +1,239 @@
 //! Audio recording using Windows WASAPI (Windows Audio Session API).
 //!
 //! This module provides functionality to record audio from a specific microphone
-//! device using the low-level WASAPI interface.
-
+//! device using the low-level WASAPI interface, or to record what an output device
+//! is playing in loopback mode.
+
+use crate::audio::AudioFormat;
+use crate::audio::PauseSignal;
+use crate::audio::RecordingBuffer;
+use crate::audio::RecordingLevel;
+use crate::audio::RecordingStorage;
+use crate::audio::StopSignal;
+use crate::audio::peak_amplitude;
+use crate::com::co_task_mem::CoTaskMem;
 use crate::com::com_guard::ComGuard;
+use crate::error::last_error_context;
 use eyre::Context;
 use eyre::Result;
 use eyre::bail;
 use std::io::Cursor;
+use std::io::Read;
+use std::io::Seek;
+use std::io::Write;
 use std::ptr;
 use std::slice;
 use std::time::Duration;
 use std::time::Instant;
 use widestring::U16CString;
+use windows::Win32::Foundation::S_OK;
+use windows::Win32::Foundation::WAIT_FAILED;
+use windows::Win32::Media::Audio::AUDCLNT_E_BUFFER_SIZE_NOT_ALIGNED;
+use windows::Win32::Media::Audio::AUDCLNT_SHAREMODE_EXCLUSIVE;
 use windows::Win32::Media::Audio::AUDCLNT_SHAREMODE_SHARED;
+use windows::Win32::Media::Audio::AUDCLNT_STREAMFLAGS_EVENTCALLBACK;
+use windows::Win32::Media::Audio::AUDCLNT_STREAMFLAGS_LOOPBACK;
 use windows::Win32::Media::Audio::IAudioCaptureClient;
 use windows::Win32::Media::Audio::IAudioClient;
 use windows::Win32::Media::Audio::IMMDevice;
 use windows::Win32::Media::Audio::IMMDeviceEnumerator;
 use windows::Win32::Media::Audio::MMDeviceEnumerator;
+use windows::Win32::Media::Audio::WAVEFORMATEX;
 use windows::Win32::System::Com::CLSCTX_ALL;
 use windows::Win32::System::Com::CoCreateInstance;
+use windows::Win32::System::Threading::CreateEventW;
+use windows::Win32::System::Threading::WaitForMultipleObjects;
+use windows::core::Owned;
 use windows::core::PCWSTR;
 
+/// Timing details negotiated with the capture device, useful for aligning audio with other streams.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecordingInfo {
+    /// Default scheduling period of the device (`IAudioClient::GetDevicePeriod`).
+    pub default_device_period: Duration,
+    /// Minimum scheduling period of the device (`IAudioClient::GetDevicePeriod`).
+    pub minimum_device_period: Duration,
+    /// Maximum latency of the stream (`IAudioClient::GetStreamLatency`).
+    pub stream_latency: Duration,
+    /// Size of the endpoint buffer in frames (`IAudioClient::GetBufferSize`).
+    pub buffer_frame_count: u32,
+    pub sample_rate: u32,
+}
+
+impl RecordingInfo {
+    /// Duration covered by the endpoint buffer.
+    pub fn buffer_duration(&self) -> Duration {
+        Duration::from_secs_f64(self.buffer_frame_count as f64 / self.sample_rate as f64)
+    }
+}
+
+/// Options for [`record_audio_with_options`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RecordingOptions {
+    /// Where captured audio is held until the recording finishes.
+    pub storage: RecordingStorage,
+    /// Whether the device is shared with other applications or opened exclusively.
+    pub share_mode: RecordingShareMode,
+    /// Ends the recording before the requested duration when triggered; what was captured so far is kept.
+    pub stop: StopSignal,
+    /// Discards captured audio while paused, so the result only covers unpaused time.
+    ///
+    /// The requested duration is wall-clock time and keeps running while paused.
+    pub pause: PauseSignal,
+    /// Capture what an output (render) device is playing instead of recording an input device.
+    ///
+    /// Only supported with [`RecordingShareMode::Shared`].
+    pub loopback: bool,
+    /// Updated with the peak amplitude of every captured packet, for showing a live input level.
+    pub level: RecordingLevel,
+    /// Sample format to capture in.
+    pub format: RecordingFormat,
+}
+
+/// Sample format requested for capture.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RecordingFormat {
+    /// The format the device prefers: the shared-mode mix format (often 48 kHz float),
+    /// or in exclusive mode the first of the mix format, 24-bit and 16-bit PCM the device accepts.
+    #[default]
+    MixDefault,
+    /// Exactly this format, checked with `IAudioClient::IsFormatSupported`.
+    /// Recording fails if the device does not accept it.
+    Exact(AudioFormat),
+}
+
+impl RecordingFormat {
+    pub fn mix_default() -> Self {
+        Self::MixDefault
+    }
+
+    /// Integer PCM, e.g. `RecordingFormat::pcm(16_000, 1, 16)` for speech recognition.
+    pub fn pcm(sample_rate: u32, channels: u16, bits_per_sample: u16) -> Self {
+        Self::Exact(AudioFormat {
+            channels,
+            sample_rate,
+            bits_per_sample,
+            is_float: false,
+        })
+    }
+}
+
+/// WASAPI share mode used for capture.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RecordingShareMode {
+    /// Capture through the audio engine using its mix format.
+    #[default]
+    Shared,
+    /// Take exclusive control of the device for lower latency.
+    ///
+    /// The audio engine is bypassed, so capture uses a format the device natively supports
+    /// (the mix format if accepted, otherwise 24-bit or 16-bit PCM at the mix rate and channel count).
+    Exclusive,
+}
+
 /// Records audio from a specific device for the given duration.
 ///
 /// Returns the recorded audio as WAV file bytes.
 pub fn record_audio(device_id: &str, duration_ms: u64) -> Result<Vec<u8>> {
+    let (wav_bytes, _info) = record_audio_with_info(device_id, duration_ms)?;
+    Ok(wav_bytes)
+}
+
+/// Records what the output device `device_id` is playing for the given duration.
+///
+/// Use [`list_audio_output_devices`](crate::audio::list_audio_output_devices) to find render endpoints.
+/// WASAPI delivers no packets while nothing is being played; those stretches are filled with silence
+/// so the recording lasts as long as the wall-clock time it covers.
+///
+/// Returns the recorded audio as WAV file bytes.
+pub fn record_loopback(device_id: &str, duration_ms: u64) -> Result<Vec<u8>> {
+    let options = RecordingOptions {
+        loopback: true,
+        ..Default::default()
+    };
+    let (wav_bytes, _info) = record_audio_with_options(device_id, duration_ms, &options)?;
+    Ok(wav_bytes)
+}
+
+/// Records audio from a specific device for the given duration.
+///
+/// Returns the recorded audio as WAV file bytes along with the negotiated device timing.
+pub fn record_audio_with_info(
+    device_id: &str,
+    duration_ms: u64,
+) -> Result<(Vec<u8>, RecordingInfo)> {
+    record_audio_with_options(device_id, duration_ms, &RecordingOptions::default())
+}
+
+/// Records audio from a specific device for the given duration using the given options.
+///
+/// Returns the recorded audio as WAV file bytes along with the negotiated device timing.
+pub fn record_audio_with_options(
+    device_id: &str,
+    duration_ms: u64,
+    options: &RecordingOptions,
+) -> Result<(Vec<u8>, RecordingInfo)> {
+    let (audio_data, format, info) = record_to_buffer(device_id, duration_ms, options)?;
+
+    // Convert to WAV format
+    let wav_bytes = audio_data.into_wav_bytes(format)?;
+
+    Ok((wav_bytes, info))
+}
+
+/// Records into a [`RecordingBuffer`] per [`RecordingOptions::storage`], leaving the WAV conversion to the caller.
+pub(crate) fn record_to_buffer(
+    device_id: &str,
+    duration_ms: u64,
+    options: &RecordingOptions,
+) -> Result<(RecordingBuffer, AudioFormat, RecordingInfo)> {
+    let mut audio_data = RecordingBuffer::new(options.storage)?;
+    let (format, info) = capture_audio(
+        device_id,
+        duration_ms,
+        options,
+        |packet, format| {
+            match packet {
+                CapturedPacket::Audio(data) => audio_data.extend_from_slice(data)?,
+                CapturedPacket::Silence(byte_len) => audio_data.extend_silence(byte_len)?,
+            }
+            audio_data.enforce_limit(format);
+            Ok(true)
+        },
+        || false,
+    )?;
+
+    tracing::info!(
+        "Captured {} bytes of audio data ({:.2} seconds)",
+        audio_data.len(),
+        format.duration_of(audio_data.len()).as_secs_f64()
+    );
+
+    Ok((audio_data, format, info))
+}
+
+/// Frames read from the capture client, handed to the callback of [`capture_audio`].
+pub(crate) enum CapturedPacket<'a> {
+    Audio(&'a [u8]),
+    /// The device flagged this many bytes as silence.
+    Silence(usize),
+}
+
+/// Runs the capture loop, passing each unpaused packet to `on_packet` along with the negotiated format.
+///
+/// Ends early when `on_packet` returns `false`, or when `is_closed` returns `true`.
+/// `is_closed` is checked on every wake-up, including while paused when no packet reaches `on_packet`.
+pub(crate) fn capture_audio(
+    device_id: &str,
+    duration_ms: u64,
+    options: &RecordingOptions,
+    mut on_packet: impl FnMut(CapturedPacket<'_>, AudioFormat) -> Result<bool>,
+    is_closed: impl Fn() -> bool,
+) -> Result<(AudioFormat, RecordingInfo)> {
+    if options.loopback && options.share_mode == RecordingShareMode::Exclusive {
+        bail!("Loopback recording is only supported in shared mode");
+    }
+
     let _com_guard = ComGuard::new()?;
 
     // Get the device by ID
@@ -36,39 +243,47 @@ pub fn record_audio(device_id: &str, duration_ms: u64) -> Result<Vec<u8>> {
     let audio_client: IAudioClient =
         unsafe { device.Activate(CLSCTX_ALL, None) }.wrap_err("Failed to activate audio client")?;
 
-    // Get the mix format (the format the device will capture in)
-    let mix_format_ptr =
-        unsafe { audio_client.GetMixFormat() }.wrap_err("Failed to get mix format")?;
+    // Get the mix format (the format the device will capture in), freed on every exit path
+    let mix_format = get_mix_format(&audio_client)?;
 
-    // SAFETY: GetMixFormat returns a valid pointer that we must free with CoTaskMemFree
-    // Copy the fields we need to avoid unaligned reference issues (WAVEFORMATEX is packed)
-    let (n_channels, n_samples_per_sec, n_block_align, w_bits_per_sample) = unsafe {
-        let fmt = &*mix_format_ptr;
-        (
-            fmt.nChannels,
-            fmt.nSamplesPerSec,
-            fmt.nBlockAlign,
-            fmt.wBitsPerSample,
-        )
+    // SAFETY: GetMixFormat returns a valid WAVEFORMATEX
+    let mix_audio_format = unsafe { AudioFormat::from_waveformatex(mix_format.as_ptr()) }?;
+    let (audio_client, format) = match options.share_mode {
+        RecordingShareMode::Shared => {
+            let stream_flags = if options.loopback {
+                AUDCLNT_STREAMFLAGS_EVENTCALLBACK | AUDCLNT_STREAMFLAGS_LOOPBACK
+            } else {
+                AUDCLNT_STREAMFLAGS_EVENTCALLBACK
+            };
+            match options.format {
+                RecordingFormat::MixDefault => {
+                    initialize_shared(&audio_client, mix_format.as_ptr(), stream_flags)?;
+                    (audio_client, mix_audio_format)
+                }
+                RecordingFormat::Exact(requested) => {
+                    let requested_format = requested.to_waveformatex();
+                    ensure_shared_format_supported(&audio_client, requested)?;
+                    initialize_shared(&audio_client, &requested_format, stream_flags)?;
+                    (audio_client, requested)
+                }
+            }
+        }
+        RecordingShareMode::Exclusive => {
+            let requested = match options.format {
+                RecordingFormat::MixDefault => None,
+                RecordingFormat::Exact(requested) => Some(requested),
+            };
+            initialize_exclusive(
+                &device,
+                audio_client,
+                mix_format.as_ptr(),
+                mix_audio_format,
+                requested,
+            )?
+        }
     };
 
-    // Initialize the audio client for capture
-    // Using 100-nanosecond units for buffer duration (1 second = 10_000_000)
-    let buffer_duration = 10_000_000i64; // 1 second buffer
-
-    unsafe {
-        audio_client.Initialize(
-            AUDCLNT_SHAREMODE_SHARED,
-            0, // No flags for normal capture (not loopback)
-            buffer_duration,
-            0, // periodicity (0 = use default)
-            mix_format_ptr,
-            None, // audio session GUID
-        )
-    }
-    .wrap_err("Failed to initialize audio client")?;
-
-    // Get the capture client interface
+    // Get the capture client interface, which loopback streams use as well
     let capture_client: IAudioCaptureClient =
         unsafe { audio_client.GetService() }.wrap_err("Failed to get capture client")?;
 
@@ -76,17 +291,46 @@ pub fn record_audio(device_id: &str, duration_ms: u64) -> Result<Vec<u8>> {
     let buffer_frame_count =
         unsafe { audio_client.GetBufferSize() }.wrap_err("Failed to get buffer size")?;
 
+    let mut default_device_period = 0i64;
+    let mut minimum_device_period = 0i64;
+    unsafe {
+        audio_client.GetDevicePeriod(
+            Some(&mut default_device_period),
+            Some(&mut minimum_device_period),
+        )
+    }
+    .wrap_err("Failed to get device period")?;
+
+    let stream_latency =
+        unsafe { audio_client.GetStreamLatency() }.wrap_err("Failed to get stream latency")?;
+
+    let info = RecordingInfo {
+        default_device_period: hns_to_duration(default_device_period),
+        minimum_device_period: hns_to_duration(minimum_device_period),
+        stream_latency: hns_to_duration(stream_latency),
+        buffer_frame_count,
+        sample_rate: format.sample_rate,
+    };
+
     tracing::debug!(
-        "Audio capture initialized: {} channels, {} Hz, {} bits, buffer frames: {}",
-        n_channels,
-        n_samples_per_sec,
-        w_bits_per_sample,
-        buffer_frame_count
+        "Audio capture initialized: {} channels, {} Hz, {} bits, buffer frames: {}, info: {:?}",
+        format.channels,
+        format.sample_rate,
+        format.bits_per_sample,
+        buffer_frame_count,
+        info
     );
 
-    // Prepare to collect audio data
-    let bytes_per_frame = n_block_align as usize;
-    let mut audio_data: Vec<u8> = Vec::new();
+    let bytes_per_frame = format.bytes_per_frame();
+
+    // The device signals this event whenever a packet is ready
+    let audio_event = unsafe { CreateEventW(None, false, false, None) }
+        .wrap_err("Failed to create audio event")?;
+    let audio_event = unsafe { Owned::new(audio_event) };
+    unsafe { audio_client.SetEventHandle(*audio_event) }.wrap_err("Failed to set event handle")?;
+    let wait_handles = [*audio_event, options.stop.event()?];
+    // Loopback streams on older Windows versions never signal the event, so never wait long enough to overflow the buffer
+    let max_wait = info.buffer_duration() / 2;
 
     // Start capturing
     unsafe { audio_client.Start() }.wrap_err("Failed to start audio capture")?;
@@ -94,15 +338,42 @@ pub fn record_audio(device_id: &str, duration_ms: u64) -> Result<Vec<u8>> {
     let start_time = Instant::now();
     let target_duration = Duration::from_millis(duration_ms);
 
+    // Loopback streams go quiet while nothing is playing, so frames are counted against the clock
+    // to fill those gaps with silence. Falling a few device periods behind is normal scheduling jitter.
+    let mut frames_drained = 0u64;
+    let gap_tolerance = info.default_device_period * 3;
+
     // Capture loop
-    while start_time.elapsed() < target_duration {
+    let mut keep_going = true;
+    while keep_going
+        && start_time.elapsed() < target_duration
+        && !options.stop.is_stopped()
+        && !is_closed()
+    {
         // Get the next packet size
         let packet_length = unsafe { capture_client.GetNextPacketSize() }
             .wrap_err("Failed to get next packet size")?;
 
         if packet_length == 0 {
-            // No data available, sleep briefly
-            std::thread::sleep(Duration::from_millis(10));
+            if options.loopback {
+                let gap = loopback_gap_frames(
+                    start_time.elapsed(),
+                    format.sample_rate,
+                    frames_drained,
+                    gap_tolerance,
+                );
+                if gap > 0 {
+                    frames_drained += gap;
+                    keep_going = pad_silence(gap, format, options, &mut on_packet)?;
+                    continue;
+                }
+            }
+            // No data available, wait for the next packet, the stop signal or the end of the recording
+            let remaining = target_duration.saturating_sub(start_time.elapsed());
+            let timeout = remaining.min(max_wait).as_millis() as u32;
+            if unsafe { WaitForMultipleObjects(&wait_handles, false, timeout) } == WAIT_FAILED {
+                bail!("Failed to wait for audio packet: {}", last_error_context());
+            }
             continue;
         }
 
@@ -122,6 +393,7 @@ pub fn record_audio(device_id: &str, duration_ms: u64) -> Result<Vec<u8>> {
         }
         .wrap_err("Failed to get capture buffer")?;
 
+        frames_drained += u64::from(num_frames_available);
         if num_frames_available > 0 && !data_ptr.is_null() {
             let data_size = num_frames_available as usize * bytes_per_frame;
 
@@ -130,11 +402,16 @@ pub fn record_audio(device_id: &str, duration_ms: u64) -> Result<Vec<u8>> {
 
             // Check for silence flag
             const AUDCLNT_BUFFERFLAGS_SILENT: u32 = 0x2;
-            if flags & AUDCLNT_BUFFERFLAGS_SILENT != 0 {
+            if options.pause.is_paused() {
+                // Still drained so the device buffer doesn't overflow, but not kept
+                options.level.set(0.0);
+            } else if flags & AUDCLNT_BUFFERFLAGS_SILENT != 0 {
                 // Device is reporting silence, write zeros
-                audio_data.extend(std::iter::repeat_n(0u8, data_size));
+                options.level.set(0.0);
+                keep_going = on_packet(CapturedPacket::Silence(data_size), format)?;
             } else {
-                audio_data.extend_from_slice(captured_data);
+                options.level.set(peak_amplitude(captured_data, format));
+                keep_going = on_packet(CapturedPacket::Audio(captured_data), format)?;
             }
         }
 
@@ -146,30 +423,218 @@ pub fn record_audio(device_id: &str, duration_ms: u64) -> Result<Vec<u8>> {
     // Stop capturing
     unsafe { audio_client.Stop() }.wrap_err("Failed to stop audio capture")?;
 
-    // Free the mix format
+    // Trailing silence never produces a wake-up with an empty packet queue after the deadline, so pad it here
+    if options.loopback && keep_going && !is_closed() {
+        let elapsed = start_time.elapsed().min(target_duration);
+        let gap = loopback_gap_frames(elapsed, format.sample_rate, frames_drained, gap_tolerance);
+        if gap > 0 {
+            pad_silence(gap, format, options, &mut on_packet)?;
+        }
+    }
+
+    Ok((format, info))
+}
+
+/// Frames a loopback stream is behind the clock after `elapsed`, or zero when within `tolerance`.
+fn loopback_gap_frames(
+    elapsed: Duration,
+    sample_rate: u32,
+    frames_drained: u64,
+    tolerance: Duration,
+) -> u64 {
+    let frames_at = |duration: Duration| (duration.as_secs_f64() * sample_rate as f64) as u64;
+    let behind = frames_at(elapsed).saturating_sub(frames_drained);
+    if behind > frames_at(tolerance) {
+        behind
+    } else {
+        0
+    }
+}
+
+/// Hands `frames` of silence to `on_packet`, or drops them while paused like captured audio.
+fn pad_silence(
+    frames: u64,
+    format: AudioFormat,
+    options: &RecordingOptions,
+    on_packet: &mut impl FnMut(CapturedPacket<'_>, AudioFormat) -> Result<bool>,
+) -> Result<bool> {
+    options.level.set(0.0);
+    if options.pause.is_paused() {
+        return Ok(true);
+    }
+    let byte_len = usize::try_from(frames)
+        .ok()
+        .and_then(|frames| frames.checked_mul(format.bytes_per_frame()))
+        .ok_or_else(|| eyre::eyre!("Loopback gap of {frames} frames is too large to pad"))?;
+    on_packet(CapturedPacket::Silence(byte_len), format)
+}
+
+/// Gets the shared-mode mix format of `audio_client`, owned so it is freed however the caller exits.
+pub(crate) fn get_mix_format(audio_client: &IAudioClient) -> Result<CoTaskMem<WAVEFORMATEX>> {
+    let mix_format_ptr =
+        unsafe { audio_client.GetMixFormat() }.wrap_err("Failed to get mix format")?;
+    unsafe { CoTaskMem::from_raw(mix_format_ptr) }
+        .ok_or_else(|| eyre::eyre!("GetMixFormat returned a null format"))
+}
+
+/// Initializes `audio_client` for shared-mode capture in `format` with a 1 second buffer.
+///
+/// Pass `AUDCLNT_STREAMFLAGS_EVENTCALLBACK` in `stream_flags` for event-driven capture,
+/// and `AUDCLNT_STREAMFLAGS_LOOPBACK` to capture from a render endpoint.
+pub(crate) fn initialize_shared(
+    audio_client: &IAudioClient,
+    format: *const WAVEFORMATEX,
+    stream_flags: u32,
+) -> Result<()> {
+    // Using 100-nanosecond units for buffer duration (1 second = 10_000_000)
+    let buffer_duration = 10_000_000i64;
+
     unsafe {
-        windows::Win32::System::Com::CoTaskMemFree(Some(mix_format_ptr as *const _));
+        audio_client.Initialize(
+            AUDCLNT_SHAREMODE_SHARED,
+            stream_flags,
+            buffer_duration,
+            0, // periodicity (0 = use default)
+            format,
+            None, // audio session GUID
+        )
     }
+    .wrap_err("Failed to initialize audio client")
+}
 
-    tracing::info!(
-        "Captured {} bytes of audio data ({:.2} seconds)",
-        audio_data.len(),
-        duration_ms as f64 / 1000.0
-    );
+/// Checks that `audio_client` accepts `format` in shared mode as is, naming the closest format it suggests otherwise.
+fn ensure_shared_format_supported(audio_client: &IAudioClient, format: AudioFormat) -> Result<()> {
+    let waveformat = format.to_waveformatex();
+    let mut closest_ptr: *mut WAVEFORMATEX = ptr::null_mut();
+    let result = unsafe {
+        audio_client.IsFormatSupported(
+            AUDCLNT_SHAREMODE_SHARED,
+            &waveformat,
+            Some(&mut closest_ptr),
+        )
+    };
+    // Freed on every exit path; only set when the result is S_FALSE
+    let closest = unsafe { CoTaskMem::from_raw(closest_ptr) };
+    if result == S_OK {
+        return Ok(());
+    }
+    let closest =
+        closest.map(|closest| unsafe { AudioFormat::from_waveformatex(closest.as_ptr()) });
+    match closest {
+        Some(Ok(closest)) => bail!(
+            "Device does not support {:?} in shared mode, the closest supported format is {:?}",
+            format,
+            closest
+        ),
+        _ => Err(windows::core::Error::from_hresult(result))
+            .wrap_err_with(|| format!("Device does not support {:?} in shared mode", format)),
+    }
+}
 
-    // Convert to WAV format
-    let wav_bytes = create_wav_file(
-        &audio_data,
-        n_channels,
-        n_samples_per_sec,
-        w_bits_per_sample,
-    )?;
+/// Initializes `audio_client` for event-driven exclusive-mode capture with the first format the device accepts.
+///
+/// When `requested` is set only that format is tried.
+///
+/// Exclusive streams use the device period as buffer size. If the driver reports
+/// `AUDCLNT_E_BUFFER_SIZE_NOT_ALIGNED`, the period is recomputed from the aligned frame count
+/// and a fresh client is activated and initialized again, as described in the `IAudioClient::Initialize` docs.
+/// <https://learn.microsoft.com/en-us/windows/win32/api/audioclient/nf-audioclient-iaudioclient-initialize>
+fn initialize_exclusive(
+    device: &IMMDevice,
+    audio_client: IAudioClient,
+    mix_format_ptr: *const WAVEFORMATEX,
+    mix_format: AudioFormat,
+    requested: Option<AudioFormat>,
+) -> Result<(IAudioClient, AudioFormat)> {
+    let pcm_24 = AudioFormat {
+        bits_per_sample: 24,
+        is_float: false,
+        ..mix_format
+    }
+    .to_waveformatex();
+    let pcm_16 = AudioFormat {
+        bits_per_sample: 16,
+        is_float: false,
+        ..mix_format
+    }
+    .to_waveformatex();
+    let requested_format = requested.map(|requested| requested.to_waveformatex());
+    let candidates: Vec<*const WAVEFORMATEX> = match &requested_format {
+        Some(requested_format) => vec![requested_format],
+        None => vec![mix_format_ptr, &pcm_24, &pcm_16],
+    };
 
-    Ok(wav_bytes)
+    let Some(format_ptr) = candidates.into_iter().find(|format| {
+        unsafe { audio_client.IsFormatSupported(AUDCLNT_SHAREMODE_EXCLUSIVE, *format, None) }
+            .is_ok()
+    }) else {
+        if let Some(requested) = requested {
+            bail!("Device does not support {:?} in exclusive mode", requested);
+        }
+        bail!(
+            "Device does not support the mix format or 16/24-bit PCM at {} Hz with {} channels in exclusive mode",
+            mix_format.sample_rate,
+            mix_format.channels
+        );
+    };
+    let format = unsafe { AudioFormat::from_waveformatex(format_ptr) }?;
+
+    let mut device_period = 0i64;
+    unsafe { audio_client.GetDevicePeriod(Some(&mut device_period), None) }
+        .wrap_err("Failed to get device period")?;
+
+    let result = unsafe {
+        audio_client.Initialize(
+            AUDCLNT_SHAREMODE_EXCLUSIVE,
+            AUDCLNT_STREAMFLAGS_EVENTCALLBACK,
+            device_period,
+            device_period,
+            format_ptr,
+            None,
+        )
+    };
+    match result {
+        Ok(()) => Ok((audio_client, format)),
+        Err(e) if e.code() == AUDCLNT_E_BUFFER_SIZE_NOT_ALIGNED => {
+            // The failed client reports the next aligned buffer size, but must be discarded
+            let aligned_frames = unsafe { audio_client.GetBufferSize() }
+                .wrap_err("Failed to get aligned buffer size")?;
+            drop(audio_client);
+            let aligned_period =
+                (10_000_000.0 * aligned_frames as f64 / format.sample_rate as f64 + 0.5) as i64;
+            tracing::debug!(
+                aligned_frames,
+                aligned_period,
+                "Buffer size not aligned, retrying exclusive initialization"
+            );
+
+            let audio_client: IAudioClient = unsafe { device.Activate(CLSCTX_ALL, None) }
+                .wrap_err("Failed to re-activate audio client")?;
+            unsafe {
+                audio_client.Initialize(
+                    AUDCLNT_SHAREMODE_EXCLUSIVE,
+                    AUDCLNT_STREAMFLAGS_EVENTCALLBACK,
+                    aligned_period,
+                    aligned_period,
+                    format_ptr,
+                    None,
+                )
+            }
+            .wrap_err("Failed to initialize audio client with aligned buffer size")?;
+            Ok((audio_client, format))
+        }
+        Err(e) => Err(e).wrap_err("Failed to initialize audio client in exclusive mode"),
+    }
+}
+
+/// Converts a WASAPI `REFERENCE_TIME` (100-nanosecond units) into a `Duration`.
+fn hns_to_duration(hns: i64) -> Duration {
+    Duration::from_nanos(hns.max(0) as u64 * 100)
 }
 
 /// Gets an IMMDevice by its device ID string.
-fn get_device_by_id(device_id: &str) -> Result<IMMDevice> {
+pub(crate) fn get_device_by_id(device_id: &str) -> Result<IMMDevice> {
+    ComGuard::assert_initialized();
     let enumerator: IMMDeviceEnumerator =
         unsafe { CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL) }
             .wrap_err("Failed to create device enumerator")?;
@@ -185,31 +650,76 @@ fn get_device_by_id(device_id: &str) -> Result<IMMDevice> {
 }
 
 /// Creates a WAV file from raw audio data.
-fn create_wav_file(
-    audio_data: &[u8],
-    n_channels: u16,
-    n_samples_per_sec: u32,
-    w_bits_per_sample: u16,
-) -> Result<Vec<u8>> {
+///
+/// Supports 16-bit, packed 24-bit and 32-bit integer samples, and 32-bit float samples.
+pub(crate) fn create_wav_file(audio_data: &[u8], format: AudioFormat) -> Result<Vec<u8>> {
     let mut output = Cursor::new(Vec::new());
+    write_wav_file(&mut output, audio_data, format)?;
+    Ok(output.into_inner())
+}
+
+/// Writes a WAV file to `output`, streaming the raw audio data from `audio_data` in chunks.
+pub(crate) fn write_wav_file<W: Write + Seek>(
+    output: W,
+    mut audio_data: impl Read,
+    format: AudioFormat,
+) -> Result<()> {
+    let bytes_per_sample = match format.bits_per_sample {
+        16 | 24 | 32 => format.bytes_per_sample(),
+        bits => bail!("Unsupported bit depth: {}", bits),
+    };
 
     let spec = hound::WavSpec {
-        channels: n_channels,
-        sample_rate: n_samples_per_sec,
-        bits_per_sample: w_bits_per_sample,
-        sample_format: if w_bits_per_sample == 32 {
+        channels: format.channels,
+        sample_rate: format.sample_rate,
+        bits_per_sample: format.bits_per_sample,
+        sample_format: if format.is_float {
             hound::SampleFormat::Float
         } else {
             hound::SampleFormat::Int
         },
     };
 
-    let mut writer =
-        hound::WavWriter::new(&mut output, spec).wrap_err("Failed to create WAV writer")?;
+    let mut writer = hound::WavWriter::new(output, spec).wrap_err("Failed to create WAV writer")?;
+
+    // Read whole samples at a time so a chunk never splits a sample
+    let mut chunk = vec![0u8; bytes_per_sample * 16 * 1024];
+    loop {
+        let filled =
+            read_up_to(&mut audio_data, &mut chunk).wrap_err("Failed to read audio data")?;
+        write_samples(&mut writer, &chunk[..filled], format)?;
+        if filled < chunk.len() {
+            break;
+        }
+    }
+
+    writer.finalize().wrap_err("Failed to finalize WAV file")?;
+
+    Ok(())
+}
 
+/// Fills `buf` from `reader`, returning fewer bytes only at end of input.
+fn read_up_to(reader: &mut impl Read, buf: &mut [u8]) -> std::io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(filled)
+}
+
+fn write_samples<W: Write + Seek>(
+    writer: &mut hound::WavWriter<W>,
+    audio_data: &[u8],
+    format: AudioFormat,
+) -> Result<()> {
     // Write samples based on bit depth
-    match w_bits_per_sample {
-        16 => {
+    match (format.bits_per_sample, format.is_float) {
+        (16, false) => {
             // 16-bit samples
             for chunk in audio_data.chunks_exact(2) {
                 let sample = i16::from_le_bytes([chunk[0], chunk[1]]);
@@ -218,7 +728,25 @@ fn create_wav_file(
                     .wrap_err("Failed to write sample")?;
             }
         }
-        32 => {
+        (24, false) => {
+            // 24-bit packed (3-byte) integer samples, sign-extended into i32
+            for chunk in audio_data.chunks_exact(3) {
+                let sample = i32::from_le_bytes([0, chunk[0], chunk[1], chunk[2]]) >> 8;
+                writer
+                    .write_sample(sample)
+                    .wrap_err("Failed to write sample")?;
+            }
+        }
+        (32, false) => {
+            // 32-bit integer samples
+            for chunk in audio_data.chunks_exact(4) {
+                let sample = i32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+                writer
+                    .write_sample(sample)
+                    .wrap_err("Failed to write sample")?;
+            }
+        }
+        (32, true) => {
             // 32-bit float samples
             for chunk in audio_data.chunks_exact(4) {
                 let sample = f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
@@ -227,12 +755,153 @@ fn create_wav_file(
                     .wrap_err("Failed to write sample")?;
             }
         }
-        bits => {
-            bail!("Unsupported bit depth: {}", bits);
+        (bits, is_float) => {
+            bail!(
+                "Unsupported sample format: {} bits {}",
+                bits,
+                if is_float { "float" } else { "int" }
+            );
         }
     }
+    Ok(())
+}
 
-    writer.finalize().wrap_err("Failed to finalize WAV file")?;
+#[cfg(test)]
+mod test {
+    use super::RecordingFormat;
+    use super::RecordingOptions;
+    use super::capture_audio;
+    use super::create_wav_file;
+    use super::get_device_by_id;
+    use super::get_mix_format;
+    use super::initialize_shared;
+    use super::loopback_gap_frames;
+    use super::record_audio_with_options;
+    use super::record_loopback;
+    use crate::audio::AudioFormat;
+    use crate::audio::PauseSignal;
+    use crate::audio::list_audio_input_devices;
+    use crate::audio::list_audio_output_devices;
+    use crate::com::com_guard::ComGuard;
+    use std::io::Cursor;
+    use std::time::Duration;
+    use std::time::Instant;
+    use windows::Win32::Media::Audio::IAudioClient;
+    use windows::Win32::System::Com::CLSCTX_ALL;
+
+    #[test]
+    fn bad_format_fails_initialize_and_frees_mix_format() -> eyre::Result<()> {
+        let _com_guard = ComGuard::new()?;
+        let Some(device) = list_audio_input_devices()?.into_iter().next() else {
+            return Ok(());
+        };
+        let device = get_device_by_id(&device.id)?;
+        let audio_client: IAudioClient = unsafe { device.Activate(CLSCTX_ALL, None) }?;
+
+        let mix_format = get_mix_format(&audio_client)?;
+        unsafe { (*mix_format.as_ptr()).nChannels = 0 };
+        assert!(initialize_shared(&audio_client, mix_format.as_ptr(), 0).is_err());
+        // mix_format is freed when it drops here, even though initialization failed
+        Ok(())
+    }
 
-    Ok(output.into_inner())
+    #[test]
+    fn records_loopback_from_output_device() -> eyre::Result<()> {
+        let Some(device) = list_audio_output_devices()?.into_iter().next() else {
+            return Ok(());
+        };
+        let wav_bytes = record_loopback(&device.id, 100)?;
+        let (format, data) = parse_wav(&wav_bytes)?;
+        assert!(format.channels > 0);
+        assert_eq!(data.len() % format.block_align()? as usize, 0);
+        // Silence is padded, so even an idle output device yields most of the 100 ms
+        let frames = data.len() / format.block_align()? as usize;
+        assert!(frames >= format.sample_rate as usize / 20);
+        Ok(())
+    }
+
+    #[test]
+    fn loopback_gaps_beyond_tolerance_are_padded() {
+        let tolerance = Duration::from_millis(30);
+        // 100 ms at 48 kHz is 4800 frames
+        let elapsed = Duration::from_millis(100);
+        assert_eq!(loopback_gap_frames(elapsed, 48_000, 0, tolerance), 4800);
+        assert_eq!(loopback_gap_frames(elapsed, 48_000, 3600, tolerance), 1200);
+        // Within a few device periods of the clock is jitter, not a gap
+        assert_eq!(loopback_gap_frames(elapsed, 48_000, 4000, tolerance), 0);
+        // Ahead of the clock never pads
+        assert_eq!(loopback_gap_frames(elapsed, 48_000, 9000, tolerance), 0);
+    }
+
+    #[test]
+    fn pause_discards_packets_until_resumed() -> eyre::Result<()> {
+        let Some(device) = list_audio_input_devices()?.into_iter().next() else {
+            return Ok(());
+        };
+        let pause = PauseSignal::new();
+        pause.pause();
+        let options = RecordingOptions {
+            pause: pause.clone(),
+            ..Default::default()
+        };
+        let started = Instant::now();
+        let resume_after = Duration::from_millis(150);
+        let resumer = std::thread::spawn(move || {
+            std::thread::sleep(resume_after);
+            pause.resume();
+        });
+
+        let mut first_packet_at = None;
+        let mut packets = 0;
+        capture_audio(
+            &device.id,
+            500,
+            &options,
+            |_packet, _format| {
+                first_packet_at.get_or_insert_with(|| started.elapsed());
+                packets += 1;
+                Ok(true)
+            },
+            || false,
+        )?;
+        resumer
+            .join()
+            .map_err(|_| eyre::eyre!("Resume thread panicked"))?;
+
+        // Input devices deliver packets continuously, so only the pause explains the wait
+        assert!(packets > 0);
+        assert!(first_packet_at.is_some_and(|at| at >= resume_after));
+        Ok(())
+    }
+
+    #[test]
+    fn unsupported_exact_format_errors() -> eyre::Result<()> {
+        let Some(device) = list_audio_input_devices()?.into_iter().next() else {
+            return Ok(());
+        };
+        let options = RecordingOptions {
+            format: RecordingFormat::pcm(1, 1, 16),
+            ..Default::default()
+        };
+        let error = record_audio_with_options(&device.id, 100, &options).unwrap_err();
+        assert!(format!("{error:?}").contains("does not support"));
+        Ok(())
+    }
+
+    #[test]
+    fn writes_24_bit_pcm() -> eyre::Result<()> {
+        // 1, -1, max, min as packed little-endian 24-bit samples
+        let audio_data = [
+            0x01, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x7F, 0x00, 0x00, 0x80,
+        ];
+        let wav_bytes = create_wav_file(&audio_data, AudioFormat::for_test(2, 48_000, 24, false))?;
+
+        let mut reader = hound::WavReader::new(Cursor::new(wav_bytes))?;
+        let spec = reader.spec();
+        assert_eq!(spec.bits_per_sample, 24);
+        assert_eq!(spec.sample_format, hound::SampleFormat::Int);
+        let samples = reader.samples::<i32>().collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(samples, vec![1, -1, 0x7F_FFFF, -0x80_0000]);
+        Ok(())
+    }
 }
@@ -0,0 +1,153 @@
+use crate::audio::AudioFormat;
+use crate::audio::CapturedPacket;
+use crate::audio::RecordingInfo;
+use crate::audio::RecordingOptions;
+use crate::audio::capture_audio;
+use crossbeam_channel::Receiver;
+use crossbeam_channel::unbounded;
+use eyre::Context;
+use std::ops::Deref;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::thread;
+use std::thread::JoinHandle;
+use std::time::Duration;
+use std::time::Instant;
+
+/// A run of captured frames delivered by [`stream_audio`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AudioChunk {
+    pub format: AudioFormat,
+    /// Position of the first frame within the stream, counting only delivered audio.
+    pub position: Duration,
+    /// When the chunk was read from the device.
+    pub captured_at: Instant,
+    /// Interleaved frames in `format`; zeros when the device reported silence.
+    pub data: Vec<u8>,
+}
+
+/// The receiving end of [`stream_audio`], dereferencing to the chunk [`Receiver`].
+///
+/// Dropping it ends the recording at the capture thread's next wake-up, even while paused.
+#[derive(Debug)]
+pub struct AudioStream {
+    receiver: Receiver<AudioChunk>,
+    closed: Arc<AtomicBool>,
+}
+
+impl Deref for AudioStream {
+    type Target = Receiver<AudioChunk>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.receiver
+    }
+}
+
+impl Drop for AudioStream {
+    fn drop(&mut self) {
+        self.closed.store(true, Ordering::SeqCst);
+    }
+}
+
+/// Records like [`record_audio_with_options`](crate::audio::record_audio_with_options) on a background thread,
+/// delivering chunks as they arrive instead of buffering the whole recording.
+///
+/// [`RecordingOptions::storage`] is ignored since nothing is buffered.
+/// The channel is unbounded so a slow consumer never stalls capture and overflows the device buffer;
+/// chunks it hasn't received yet accumulate in memory instead.
+/// Recording ends early once the [`AudioStream`] is dropped. The thread returns the negotiated device timing.
+pub fn stream_audio(
+    device_id: &str,
+    duration_ms: u64,
+    options: RecordingOptions,
+) -> eyre::Result<(AudioStream, JoinHandle<eyre::Result<RecordingInfo>>)> {
+    let (tx, rx) = unbounded();
+    let closed = Arc::new(AtomicBool::new(false));
+    let stream = AudioStream {
+        receiver: rx,
+        closed: closed.clone(),
+    };
+    let device_id = device_id.to_string();
+    let handle = thread::Builder::new()
+        .name("audio-stream".into())
+        .spawn(move || {
+            let mut position = Duration::ZERO;
+            let (_format, info) = capture_audio(
+                &device_id,
+                duration_ms,
+                &options,
+                |packet, format| {
+                    let data = match packet {
+                        CapturedPacket::Audio(data) => data.to_vec(),
+                        CapturedPacket::Silence(byte_len) => vec![0; byte_len],
+                    };
+                    let chunk = AudioChunk {
+                        format,
+                        position,
+                        captured_at: Instant::now(),
+                        data,
+                    };
+                    position += format.duration_of(chunk.data.len());
+                    Ok(tx.send(chunk).is_ok())
+                },
+                || closed.load(Ordering::SeqCst),
+            )?;
+            Ok(info)
+        })
+        .wrap_err("Failed to spawn audio-stream thread")?;
+    Ok((stream, handle))
+}
+
+#[cfg(test)]
+mod test {
+    use super::stream_audio;
+    use crate::audio::PauseSignal;
+    use crate::audio::RecordingOptions;
+    use crate::audio::list_audio_input_devices;
+    use std::time::Duration;
+    use std::time::Instant;
+
+    #[test]
+    fn chunks_are_contiguous() -> eyre::Result<()> {
+        let Some(device) = list_audio_input_devices()?.into_iter().next() else {
+            return Ok(());
+        };
+        let (chunks, handle) = stream_audio(&device.id, 200, RecordingOptions::default())?;
+        let chunks: Vec<_> = chunks.iter().collect();
+        handle
+            .join()
+            .map_err(|_| eyre::eyre!("Audio stream thread panicked"))??;
+
+        for pair in chunks.windows(2) {
+            assert_eq!(pair[0].format, pair[1].format);
+            assert_eq!(
+                pair[1].position,
+                pair[0].position + pair[0].format.duration_of(pair[0].data.len())
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn dropping_stream_ends_paused_recording() -> eyre::Result<()> {
+        let Some(device) = list_audio_input_devices()?.into_iter().next() else {
+            return Ok(());
+        };
+        let pause = PauseSignal::new();
+        pause.pause();
+        let options = RecordingOptions {
+            pause,
+            ..Default::default()
+        };
+        let started = Instant::now();
+        let (chunks, handle) = stream_audio(&device.id, 30_000, options)?;
+        // Nothing is ever sent while paused, so only the closed flag can end the recording
+        drop(chunks);
+        handle
+            .join()
+            .map_err(|_| eyre::eyre!("Audio stream thread panicked"))??;
+        assert!(started.elapsed() < Duration::from_secs(5));
+        Ok(())
+    }
+}
@@ -0,0 +1,204 @@
+use crate::com::com_guard::ComGuard;
+use crossbeam_channel::Receiver;
+use crossbeam_channel::Sender;
+use crossbeam_channel::unbounded;
+use eyre::Context;
+use tracing::warn;
+use windows::Win32::Foundation::PROPERTYKEY;
+use windows::Win32::Media::Audio::DEVICE_STATE;
+use windows::Win32::Media::Audio::EDataFlow;
+use windows::Win32::Media::Audio::ERole;
+use windows::Win32::Media::Audio::IMMDeviceEnumerator;
+use windows::Win32::Media::Audio::IMMNotificationClient;
+use windows::Win32::Media::Audio::IMMNotificationClient_Impl;
+use windows::Win32::Media::Audio::MMDeviceEnumerator;
+use windows::Win32::System::Com::CLSCTX_ALL;
+use windows::Win32::System::Com::CoCreateInstance;
+use windows::core::PCWSTR;
+use windows::core::implement;
+
+/// An audio endpoint change reported by [`DeviceChangeMonitor`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeviceEvent {
+    Added {
+        device_id: String,
+    },
+    Removed {
+        device_id: String,
+    },
+    /// The default device for `flow` and `role` changed; `device_id` is `None` when no device is left for that role.
+    DefaultChanged {
+        flow: EDataFlow,
+        role: ERole,
+        device_id: Option<String>,
+    },
+    StateChanged {
+        device_id: String,
+        state: DEVICE_STATE,
+    },
+}
+
+/// Delivers [`DeviceEvent`]s for audio endpoints being plugged in, unplugged, enabled or made default.
+///
+/// Re-list devices (e.g. with [`list_audio_input_devices`](crate::audio::list_audio_input_devices)) when an event arrives.
+/// The callback is unregistered when the monitor is dropped.
+pub struct DeviceChangeMonitor {
+    enumerator: IMMDeviceEnumerator,
+    notification_client: IMMNotificationClient,
+    receiver: Receiver<DeviceEvent>,
+    // Dropped last so COM stays initialized while the enumerator is released
+    _com_guard: ComGuard,
+}
+
+impl DeviceChangeMonitor {
+    pub fn new() -> eyre::Result<Self> {
+        let com_guard = ComGuard::new()?;
+        let enumerator: IMMDeviceEnumerator =
+            unsafe { CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL) }
+                .wrap_err("Failed to create device enumerator")?;
+
+        let (sender, receiver) = unbounded();
+        let notification_client: IMMNotificationClient = ForwardDeviceChanges { sender }.into();
+        unsafe { enumerator.RegisterEndpointNotificationCallback(&notification_client) }
+            .wrap_err("Failed to register endpoint notification callback")?;
+
+        Ok(Self {
+            enumerator,
+            notification_client,
+            receiver,
+            _com_guard: com_guard,
+        })
+    }
+
+    /// Receives events as they arrive; clone it to wait on events from another thread.
+    pub fn receiver(&self) -> &Receiver<DeviceEvent> {
+        &self.receiver
+    }
+}
+
+impl Drop for DeviceChangeMonitor {
+    fn drop(&mut self) {
+        if let Err(e) = unsafe {
+            self.enumerator
+                .UnregisterEndpointNotificationCallback(&self.notification_client)
+        } {
+            warn!("Failed to unregister endpoint notification callback: {}", e);
+        }
+    }
+}
+
+/// Notifications arrive on a system thread that must not block, so events go through an unbounded channel.
+/// Sending never waits, and fails harmlessly once every receiver is gone.
+#[implement(IMMNotificationClient)]
+struct ForwardDeviceChanges {
+    sender: Sender<DeviceEvent>,
+}
+
+impl ForwardDeviceChanges_Impl {
+    fn send(&self, event: DeviceEvent) -> windows::core::Result<()> {
+        let _ = self.sender.send(event);
+        Ok(())
+    }
+}
+
+fn device_id_string(device_id: &PCWSTR) -> Option<String> {
+    if device_id.is_null() {
+        return None;
+    }
+    let wide = unsafe { device_id.as_wide() };
+    Some(String::from_utf16_lossy(wide))
+}
+
+impl IMMNotificationClient_Impl for ForwardDeviceChanges_Impl {
+    fn OnDeviceStateChanged(
+        &self,
+        device_id: &PCWSTR,
+        new_state: DEVICE_STATE,
+    ) -> windows::core::Result<()> {
+        self.send(DeviceEvent::StateChanged {
+            device_id: device_id_string(device_id).unwrap_or_default(),
+            state: new_state,
+        })
+    }
+
+    fn OnDeviceAdded(&self, device_id: &PCWSTR) -> windows::core::Result<()> {
+        self.send(DeviceEvent::Added {
+            device_id: device_id_string(device_id).unwrap_or_default(),
+        })
+    }
+
+    fn OnDeviceRemoved(&self, device_id: &PCWSTR) -> windows::core::Result<()> {
+        self.send(DeviceEvent::Removed {
+            device_id: device_id_string(device_id).unwrap_or_default(),
+        })
+    }
+
+    fn OnDefaultDeviceChanged(
+        &self,
+        flow: EDataFlow,
+        role: ERole,
+        default_device_id: &PCWSTR,
+    ) -> windows::core::Result<()> {
+        self.send(DeviceEvent::DefaultChanged {
+            flow,
+            role,
+            device_id: device_id_string(default_device_id),
+        })
+    }
+
+    fn OnPropertyValueChanged(
+        &self,
+        _device_id: &PCWSTR,
+        _key: &PROPERTYKEY,
+    ) -> windows::core::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::DeviceChangeMonitor;
+    use super::DeviceEvent;
+    use super::ForwardDeviceChanges;
+    use crossbeam_channel::unbounded;
+    use windows::Win32::Media::Audio::IMMNotificationClient;
+    use windows::Win32::Media::Audio::eCapture;
+    use windows::Win32::Media::Audio::eConsole;
+    use windows::core::PCWSTR;
+    use windows::core::w;
+
+    #[test]
+    fn forwards_events_and_ignores_dropped_receiver() -> eyre::Result<()> {
+        let (sender, receiver) = unbounded();
+        let client: IMMNotificationClient = ForwardDeviceChanges { sender }.into();
+        let device_id = w!("{0.0.1.00000000}.{test}");
+
+        unsafe { client.OnDeviceAdded(device_id) }?;
+        unsafe { client.OnDefaultDeviceChanged(eCapture, eConsole, PCWSTR::null()) }?;
+        assert_eq!(
+            receiver.try_iter().collect::<Vec<_>>(),
+            vec![
+                DeviceEvent::Added {
+                    device_id: "{0.0.1.00000000}.{test}".to_string(),
+                },
+                DeviceEvent::DefaultChanged {
+                    flow: eCapture,
+                    role: eConsole,
+                    device_id: None,
+                },
+            ]
+        );
+
+        drop(receiver);
+        unsafe { client.OnDeviceRemoved(device_id) }?;
+        Ok(())
+    }
+
+    #[test]
+    fn registers_and_unregisters() -> eyre::Result<()> {
+        let monitor = DeviceChangeMonitor::new()?;
+        let _ = monitor.receiver().try_recv();
+        drop(monitor);
+        Ok(())
+    }
+}
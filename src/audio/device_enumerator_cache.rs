@@ -0,0 +1,198 @@
+use crate::com::com_guard::ComGuard;
+use eyre::Context;
+use std::cell::RefCell;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use tracing::debug;
+use tracing::warn;
+use windows::Win32::Foundation::PROPERTYKEY;
+use windows::Win32::Media::Audio::DEVICE_STATE;
+use windows::Win32::Media::Audio::EDataFlow;
+use windows::Win32::Media::Audio::ERole;
+use windows::Win32::Media::Audio::IMMDeviceEnumerator;
+use windows::Win32::Media::Audio::IMMNotificationClient;
+use windows::Win32::Media::Audio::IMMNotificationClient_Impl;
+use windows::Win32::Media::Audio::MMDeviceEnumerator;
+use windows::Win32::System::Com::CLSCTX_ALL;
+use windows::Win32::System::Com::CoCreateInstance;
+use windows::core::PCWSTR;
+use windows::core::implement;
+
+thread_local! {
+    static CACHED_ENUMERATOR: RefCell<Option<CachedEnumerator>> = const { RefCell::new(None) };
+}
+
+/// Returns a device enumerator cached for the current thread, creating it on first use.
+///
+/// Creating an `MMDeviceEnumerator` goes through `CoCreateInstance` and the audio endpoint service on every call,
+/// which adds noticeable latency when a UI re-lists devices frequently.
+/// The cache is per-thread because COM objects created in an apartment must not be used from another one.
+/// It is dropped and rebuilt when a device is added or removed, changes state, or becomes a default.
+/// Property changes don't invalidate it: endpoints report those constantly (volume, levels)
+/// and the enumerator itself is unaffected by them.
+///
+/// The `cached_enumerator_beats_fresh_enumerator` test checks that a cache hit is faster than creating an enumerator,
+/// and prints both per-call times for the machine it runs on.
+pub fn cached_device_enumerator() -> eyre::Result<IMMDeviceEnumerator> {
+    CACHED_ENUMERATOR.with(|cell| {
+        let mut cached = cell.borrow_mut();
+        if let Some(existing) = cached.as_ref()
+            && !existing.invalidated.load(Ordering::Acquire)
+        {
+            return Ok(existing.enumerator.clone());
+        }
+        if cached.take().is_some() {
+            debug!("Audio endpoints changed, recreating cached device enumerator");
+        }
+        let fresh = CachedEnumerator::new()?;
+        let enumerator = fresh.enumerator.clone();
+        *cached = Some(fresh);
+        Ok(enumerator)
+    })
+}
+
+/// Drops the device enumerator cached for the current thread, if any.
+pub fn clear_cached_device_enumerator() {
+    CACHED_ENUMERATOR.with(|cell| cell.borrow_mut().take());
+}
+
+struct CachedEnumerator {
+    enumerator: IMMDeviceEnumerator,
+    notification_client: IMMNotificationClient,
+    invalidated: Arc<AtomicBool>,
+    // Dropped last so COM stays initialized while the enumerator is released
+    _com_guard: ComGuard,
+}
+
+impl CachedEnumerator {
+    fn new() -> eyre::Result<Self> {
+        let com_guard = ComGuard::new()?;
+        let enumerator: IMMDeviceEnumerator =
+            unsafe { CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL) }
+                .wrap_err("Failed to create device enumerator")?;
+
+        let invalidated = Arc::new(AtomicBool::new(false));
+        let notification_client: IMMNotificationClient = InvalidateOnDeviceChange {
+            invalidated: invalidated.clone(),
+        }
+        .into();
+        unsafe { enumerator.RegisterEndpointNotificationCallback(&notification_client) }
+            .wrap_err("Failed to register endpoint notification callback")?;
+
+        Ok(Self {
+            enumerator,
+            notification_client,
+            invalidated,
+            _com_guard: com_guard,
+        })
+    }
+}
+
+impl Drop for CachedEnumerator {
+    fn drop(&mut self) {
+        if let Err(e) = unsafe {
+            self.enumerator
+                .UnregisterEndpointNotificationCallback(&self.notification_client)
+        } {
+            warn!("Failed to unregister endpoint notification callback: {}", e);
+        }
+    }
+}
+
+/// Notifications arrive on a system thread, so this only flips a flag checked by the owning thread.
+#[implement(IMMNotificationClient)]
+struct InvalidateOnDeviceChange {
+    invalidated: Arc<AtomicBool>,
+}
+
+impl InvalidateOnDeviceChange_Impl {
+    fn invalidate(&self) -> windows::core::Result<()> {
+        self.invalidated.store(true, Ordering::Release);
+        Ok(())
+    }
+}
+
+impl IMMNotificationClient_Impl for InvalidateOnDeviceChange_Impl {
+    fn OnDeviceStateChanged(
+        &self,
+        _device_id: &PCWSTR,
+        _new_state: DEVICE_STATE,
+    ) -> windows::core::Result<()> {
+        self.invalidate()
+    }
+
+    fn OnDeviceAdded(&self, _device_id: &PCWSTR) -> windows::core::Result<()> {
+        self.invalidate()
+    }
+
+    fn OnDeviceRemoved(&self, _device_id: &PCWSTR) -> windows::core::Result<()> {
+        self.invalidate()
+    }
+
+    fn OnDefaultDeviceChanged(
+        &self,
+        _flow: EDataFlow,
+        _role: ERole,
+        _default_device_id: &PCWSTR,
+    ) -> windows::core::Result<()> {
+        self.invalidate()
+    }
+
+    fn OnPropertyValueChanged(
+        &self,
+        _device_id: &PCWSTR,
+        _key: &PROPERTYKEY,
+    ) -> windows::core::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::cached_device_enumerator;
+    use crate::com::com_guard::ComGuard;
+    use std::time::Duration;
+    use std::time::Instant;
+    use windows::Win32::Media::Audio::IMMDeviceEnumerator;
+    use windows::Win32::Media::Audio::MMDeviceEnumerator;
+    use windows::Win32::System::Com::CLSCTX_ALL;
+    use windows::Win32::System::Com::CoCreateInstance;
+
+    const ITERATIONS: u32 = 200;
+
+    fn time(mut f: impl FnMut() -> eyre::Result<()>) -> eyre::Result<Duration> {
+        let start = Instant::now();
+        for _ in 0..ITERATIONS {
+            f()?;
+        }
+        Ok(start.elapsed())
+    }
+
+    #[test]
+    fn cached_enumerator_beats_fresh_enumerator() -> eyre::Result<()> {
+        let _com_guard = ComGuard::new()?;
+        // Fill the cache up front so its one CoCreateInstance isn't counted against it
+        cached_device_enumerator()?;
+
+        let fresh = time(|| {
+            let _enumerator: IMMDeviceEnumerator =
+                unsafe { CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL) }?;
+            Ok(())
+        })?;
+        let cached = time(|| {
+            cached_device_enumerator()?;
+            Ok(())
+        })?;
+
+        println!(
+            "{ITERATIONS} enumerators: fresh {:?} ({:?} each), cached {:?} ({:?} each)",
+            fresh,
+            fresh / ITERATIONS,
+            cached,
+            cached / ITERATIONS
+        );
+        assert!(cached < fresh);
+        Ok(())
+    }
+}
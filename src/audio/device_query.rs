@@ -0,0 +1,103 @@
+use crate::audio::TeamyImmDevice;
+use crate::audio::get_device_by_id;
+use crate::audio::list_audio_input_devices;
+use crate::com::com_guard::ComGuard;
+use eyre::bail;
+
+/// Resolves a user-supplied input device selector to a full IMM device ID.
+///
+/// Accepts, in order of precedence:
+/// - `default`, the default capture device
+/// - a full device ID such as `{0.0.1.00000000}.{guid}`, as shown by `mic list`
+/// - a case-insensitive substring of the device's friendly name, which must match exactly one device
+/// - any other exact device ID the system knows, such as a render endpoint for loopback capture
+pub fn resolve_audio_input_device_id(query: &str) -> eyre::Result<String> {
+    let devices = list_audio_input_devices()?;
+    match select_audio_device(&devices, query) {
+        Ok(device) => Ok(device.id.to_string()),
+        Err(e) if !query.eq_ignore_ascii_case("default") => {
+            let _com_guard = ComGuard::new()?;
+            match get_device_by_id(query) {
+                Ok(_) => Ok(query.to_string()),
+                Err(_) => Err(e),
+            }
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Picks the device matching `query` from `devices`, see [`resolve_audio_input_device_id`].
+pub fn select_audio_device<'a>(
+    devices: &'a [TeamyImmDevice],
+    query: &str,
+) -> eyre::Result<&'a TeamyImmDevice> {
+    if query.eq_ignore_ascii_case("default") {
+        return match devices.iter().find(|device| device.is_default) {
+            Some(device) => Ok(device),
+            None => bail!("There is no default audio device"),
+        };
+    }
+    if let Some(device) = devices.iter().find(|device| device.id.as_str() == query) {
+        return Ok(device);
+    }
+
+    let needle = query.to_lowercase();
+    let matches: Vec<&TeamyImmDevice> = devices
+        .iter()
+        .filter(|device| device.name.to_lowercase().contains(&needle))
+        .collect();
+    match matches.as_slice() {
+        [device] => Ok(device),
+        [] => bail!("No audio device has the ID or a name containing {query:?}"),
+        many => bail!(
+            "{query:?} matches {} audio devices, be more specific: {}",
+            many.len(),
+            many.iter()
+                .map(|device| format!("{:?}", device.name))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::select_audio_device;
+    use crate::audio::DeviceFlow;
+    use crate::audio::TeamyImmDevice;
+    use crate::audio::TeamyImmDeviceId;
+
+    fn device(id: &str, name: &str, is_default: bool) -> TeamyImmDevice {
+        TeamyImmDevice {
+            id: TeamyImmDeviceId(id.to_string()),
+            name: name.to_string(),
+            is_default,
+            icon: None,
+            flow: DeviceFlow::Capture,
+        }
+    }
+
+    #[test]
+    fn selects_by_default_id_or_name() -> eyre::Result<()> {
+        let devices = [
+            device("{0.0.1.00000000}.{a}", "Microphone (USB Audio)", false),
+            device("{0.0.1.00000000}.{b}", "Headset Microphone", true),
+            device("{0.0.1.00000000}.{c}", "Line In", false),
+        ];
+        assert_eq!(
+            *select_audio_device(&devices, "DEFAULT")?.id,
+            "{0.0.1.00000000}.{b}"
+        );
+        assert_eq!(
+            *select_audio_device(&devices, "{0.0.1.00000000}.{c}")?.id,
+            "{0.0.1.00000000}.{c}"
+        );
+        assert_eq!(
+            *select_audio_device(&devices, "usb")?.id,
+            "{0.0.1.00000000}.{a}"
+        );
+        assert!(select_audio_device(&devices, "microphone").is_err());
+        assert!(select_audio_device(&devices, "webcam").is_err());
+        Ok(())
+    }
+}
@@ -1,10 +1,80 @@
+use crate::audio::HasIcon;
 use crate::audio::TeamyImmDeviceIcon;
 use crate::audio::imm_device_id::TeamyImmDeviceId;
+use facet::Facet;
+use windows::Win32::Media::Audio::EDataFlow;
+use windows::Win32::Media::Audio::eCapture;
+use windows::Win32::Media::Audio::eRender;
 
 /// Interface MultiMedia Device
+///
+/// The icon is serialized as a `has_icon` bool rather than its pixels.
+#[derive(Facet)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct TeamyImmDevice {
     pub id: TeamyImmDeviceId,
     pub name: String,
     pub is_default: bool,
+    #[facet(rename = "has_icon", proxy = HasIcon)]
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "has_icon", serialize_with = "serialize_has_icon")
+    )]
     pub icon: Option<TeamyImmDeviceIcon>,
+    pub flow: DeviceFlow,
+}
+
+#[cfg(feature = "serde")]
+fn serialize_has_icon<S>(
+    icon: &Option<TeamyImmDeviceIcon>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serializer.serialize_bool(icon.is_some())
+}
+
+/// Direction audio travels through an endpoint.
+#[derive(Facet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[repr(u8)]
+pub enum DeviceFlow {
+    /// Input devices such as microphones.
+    Capture,
+    /// Output devices such as speakers, which can be recorded in loopback mode.
+    Render,
+}
+
+impl DeviceFlow {
+    pub fn as_edataflow(&self) -> EDataFlow {
+        match self {
+            DeviceFlow::Capture => eCapture,
+            DeviceFlow::Render => eRender,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::audio::DeviceFlow;
+    use crate::audio::TeamyImmDevice;
+    use crate::audio::TeamyImmDeviceIcon;
+    use crate::audio::TeamyImmDeviceId;
+    use image::RgbaImage;
+
+    #[test]
+    fn icon_serializes_as_presence() -> eyre::Result<()> {
+        let device = TeamyImmDevice {
+            id: TeamyImmDeviceId("{0.0.1.00000000}.{abc}".to_string()),
+            name: "Microphone".to_string(),
+            is_default: true,
+            icon: Some(TeamyImmDeviceIcon::new(RgbaImage::new(1, 1))),
+            flow: DeviceFlow::Capture,
+        };
+        let json = facet_json::to_string(&device)?;
+        assert!(json.contains(r#""has_icon":true"#), "{json}");
+        assert!(json.contains(r#""id":"{0.0.1.00000000}.{abc}""#), "{json}");
+        Ok(())
+    }
 }
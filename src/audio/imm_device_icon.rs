@@ -1,3 +1,4 @@
+use facet::Facet;
 use image::RgbaImage;
 use std::ops::Deref;
 pub struct TeamyImmDeviceIcon(pub RgbaImage);
@@ -20,3 +21,21 @@ impl Deref for TeamyImmDeviceIcon {
         &self.0
     }
 }
+
+/// Serialized stand-in for a device's icon, recording only whether one was loaded.
+#[derive(Facet, Debug, Clone, Copy, PartialEq, Eq)]
+#[facet(transparent)]
+pub struct HasIcon(pub bool);
+
+impl From<&Option<TeamyImmDeviceIcon>> for HasIcon {
+    fn from(icon: &Option<TeamyImmDeviceIcon>) -> Self {
+        Self(icon.is_some())
+    }
+}
+
+/// The pixels aren't serialized, so a deserialized device never has an icon.
+impl From<HasIcon> for Option<TeamyImmDeviceIcon> {
+    fn from(_: HasIcon) -> Self {
+        None
+    }
+}
@@ -1,9 +1,10 @@
 use crate::audio::TeamyImmDeviceIcon;
-use crate::shell::property_store::PropVariantExt;
+use crate::audio::get_device_by_id;
+use crate::com::com_guard::ComGuard;
+use crate::com::property_store::PropertyStore;
 use eyre::Context;
 use std::ops::Deref;
 use windows::Win32::Foundation::PROPERTYKEY;
-use windows::Win32::UI::Shell::PropertiesSystem::IPropertyStore;
 use windows::core::GUID;
 
 // DEVPKEY_Device_IconPath
@@ -24,13 +25,25 @@ impl TeamyImmDeviceIconPath {
     pub fn new(path: String) -> Self {
         Self(path)
     }
-    pub fn from_property_store(property_store: &IPropertyStore) -> eyre::Result<Self> {
-        let property = unsafe { property_store.GetValue(&PKEY_DEVICE_ICON) }
-            .or_else(|_| unsafe { property_store.GetValue(&PKEY_DEVICE_CLASS_ICON) })
-            .wrap_err_with(|| "Failed getting either PKEY_DEVICE_ICON PKEY_DEVICE_CLASS_ICON")?;
-        let property = property.interpret_string_value()?;
+    pub fn from_property_store(property_store: &PropertyStore) -> eyre::Result<Self> {
+        let property = match property_store.get_string(PKEY_DEVICE_ICON)? {
+            Some(property) => property,
+            None => property_store
+                .get_string(PKEY_DEVICE_CLASS_ICON)?
+                .ok_or_else(|| {
+                    eyre::eyre!("Neither PKEY_DEVICE_ICON nor PKEY_DEVICE_CLASS_ICON is set")
+                })?,
+        };
         Ok(TeamyImmDeviceIconPath::new(property))
     }
+    /// Looks up the icon path of the endpoint with `device_id`, falling back to the generic microphone icon.
+    pub fn for_device_id(device_id: &str) -> eyre::Result<Self> {
+        let _com_guard = ComGuard::new()?;
+        let device = get_device_by_id(device_id)?;
+        let property_store = PropertyStore::from_device(&device)
+            .wrap_err_with(|| format!("Failed to open property store for device {device_id}"))?;
+        Ok(Self::from_property_store(&property_store).unwrap_or_default())
+    }
     pub fn load_device_icon(&self) -> eyre::Result<TeamyImmDeviceIcon> {
         let icon = crate::hicon::load_icon_from_path(&self.0)?;
         Ok(icon)
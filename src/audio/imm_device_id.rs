@@ -1,7 +1,11 @@
 use crate::string::EasyPCWSTR;
+use facet::Facet;
 use std::ops::Deref;
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Facet, Debug, Eq, PartialEq)]
+#[facet(transparent)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct TeamyImmDeviceId(pub String);
 impl Deref for TeamyImmDeviceId {
     type Target = String;
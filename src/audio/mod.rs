@@ -1,13 +1,41 @@
+#[cfg(feature = "encoding")]
+mod audio_encoding;
+mod audio_format;
 mod audio_input_device_list_request;
 mod audio_recording;
+mod audio_stream;
+mod device_change_monitor;
+mod device_enumerator_cache;
+mod device_query;
 mod imm_device;
 mod imm_device_icon;
 mod imm_device_icon_path;
 mod imm_device_id;
+mod pause_signal;
+mod peak_meter;
+mod record_to_file;
+mod recording_buffer;
+mod recording_level;
+mod stop_signal;
+mod wav;
 
+#[cfg(feature = "encoding")]
+pub use audio_encoding::*;
+pub use audio_format::*;
 pub use audio_input_device_list_request::*;
 pub use audio_recording::*;
+pub use audio_stream::*;
+pub use device_change_monitor::*;
+pub use device_enumerator_cache::*;
+pub use device_query::*;
 pub use imm_device::*;
 pub use imm_device_icon::*;
 pub use imm_device_icon_path::*;
 pub use imm_device_id::*;
+pub use pause_signal::*;
+pub use peak_meter::*;
+pub use record_to_file::*;
+pub use recording_buffer::*;
+pub use recording_level::*;
+pub use stop_signal::*;
+pub use wav::*;
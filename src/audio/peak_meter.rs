@@ -0,0 +1,64 @@
+use crate::audio::audio_recording::get_mix_format;
+use crate::audio::audio_recording::initialize_shared;
+use crate::audio::get_device_by_id;
+use crate::com::com_guard::ComGuard;
+use eyre::Context;
+use eyre::Result;
+use windows::Win32::Media::Audio::Endpoints::IAudioMeterInformation;
+use windows::Win32::Media::Audio::IAudioClient;
+use windows::Win32::System::Com::CLSCTX_ALL;
+
+/// Live peak level of an audio endpoint, as shown by the Windows sound settings.
+///
+/// Capture endpoints only report levels while a stream is open on them,
+/// so this keeps an idle shared-mode stream running for its lifetime.
+pub struct PeakMeter {
+    meter: IAudioMeterInformation,
+    audio_client: IAudioClient,
+    _com_guard: ComGuard,
+}
+
+impl PeakMeter {
+    /// Peak sample value across all channels since the previous call, from 0.0 to 1.0.
+    pub fn peak(&self) -> Result<f32> {
+        unsafe { self.meter.GetPeakValue() }.wrap_err("Failed to get peak value")
+    }
+
+    /// Peak sample value of each channel, from 0.0 to 1.0.
+    pub fn channel_peaks(&self) -> Result<Vec<f32>> {
+        let count = unsafe { self.meter.GetMeteringChannelCount() }
+            .wrap_err("Failed to get metering channel count")?;
+        let mut peaks = vec![0.0; count as usize];
+        unsafe { self.meter.GetChannelsPeakValues(&mut peaks) }
+            .wrap_err("Failed to get channel peak values")?;
+        Ok(peaks)
+    }
+}
+
+impl Drop for PeakMeter {
+    fn drop(&mut self) {
+        _ = unsafe { self.audio_client.Stop() };
+    }
+}
+
+/// Opens a [`PeakMeter`] on the device with the given ID.
+pub fn get_peak_meter(device_id: &str) -> Result<PeakMeter> {
+    let com_guard = ComGuard::new()?;
+    let device = get_device_by_id(device_id)?;
+
+    let meter: IAudioMeterInformation =
+        unsafe { device.Activate(CLSCTX_ALL, None) }.wrap_err("Failed to activate audio meter")?;
+
+    let audio_client: IAudioClient =
+        unsafe { device.Activate(CLSCTX_ALL, None) }.wrap_err("Failed to activate audio client")?;
+    let mix_format = get_mix_format(&audio_client)?;
+    // The buffer is never read; the stream only exists so the meter reports levels
+    initialize_shared(&audio_client, mix_format.as_ptr(), 0)?;
+    unsafe { audio_client.Start() }.wrap_err("Failed to start audio client")?;
+
+    Ok(PeakMeter {
+        meter,
+        audio_client,
+        _com_guard: com_guard,
+    })
+}
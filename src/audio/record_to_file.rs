@@ -0,0 +1,34 @@
+use crate::audio::RecordingInfo;
+use crate::audio::RecordingOptions;
+use crate::audio::record_to_buffer;
+use eyre::Context;
+use std::path::Path;
+use std::time::Duration;
+use tracing::info;
+
+/// Records from a device for `duration` and writes the result to `path` as a WAV file.
+///
+/// A blocking one-shot: no background thread or async runtime is involved.
+/// The file is written atomically, so `path` never holds a partial recording.
+pub fn record_to_file(device_id: &str, duration: Duration, path: &Path) -> eyre::Result<()> {
+    record_to_file_with_options(device_id, duration, path, &RecordingOptions::default())?;
+    Ok(())
+}
+
+/// Like [`record_to_file`], using the given options and returning the negotiated device timing.
+///
+/// When [`RecordingOptions::stop`] is triggered early, what was captured so far is still written.
+pub fn record_to_file_with_options(
+    device_id: &str,
+    duration: Duration,
+    path: &Path,
+    options: &RecordingOptions,
+) -> eyre::Result<RecordingInfo> {
+    let duration_ms = u64::try_from(duration.as_millis()).unwrap_or(u64::MAX);
+    let (audio_data, format, info) = record_to_buffer(device_id, duration_ms, options)?;
+    audio_data
+        .write_wav_file(path, format)
+        .wrap_err_with(|| format!("Failed to write {}", path.display()))?;
+    info!("Wrote {}", path.display());
+    Ok(info)
+}
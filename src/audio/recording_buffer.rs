@@ -0,0 +1,298 @@
+use crate::audio::AudioFormat;
+use crate::audio::create_wav_file;
+use crate::audio::write_wav_file;
+use crate::storage::write_atomic_with;
+use eyre::Context;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::BufReader;
+use std::io::BufWriter;
+use std::io::Cursor;
+use std::io::Read;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+use tracing::debug;
+use tracing::warn;
+
+/// Where captured audio is held while a recording is in progress.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RecordingStorage {
+    /// Keep the raw capture in memory. Simple, but the WAV conversion briefly needs twice the recording size.
+    #[default]
+    Memory,
+    /// Spill the raw capture to a temporary file as it arrives, so memory stays bounded during long recordings.
+    /// The file is removed once the WAV has been produced.
+    TempFile,
+    /// Keep only the most recent `max_duration` of audio in memory, dropping the oldest whole frames,
+    /// e.g. for an always-on "save the last 30 seconds" buffer.
+    Ring { max_duration: Duration },
+}
+
+/// Accumulates raw PCM frames according to a [`RecordingStorage`].
+pub(crate) enum RecordingBuffer {
+    Memory(Vec<u8>),
+    TempFile {
+        writer: BufWriter<File>,
+        path: TempFilePath,
+        len: usize,
+    },
+    Ring {
+        data: VecDeque<u8>,
+        max_duration: Duration,
+    },
+}
+
+impl RecordingBuffer {
+    pub fn new(storage: RecordingStorage) -> eyre::Result<Self> {
+        match storage {
+            RecordingStorage::Memory => Ok(Self::Memory(Vec::new())),
+            RecordingStorage::TempFile => {
+                let path = TempFilePath::new()?;
+                let file = File::options()
+                    .create_new(true)
+                    .read(true)
+                    .write(true)
+                    .open(&path.0)
+                    .wrap_err_with(|| {
+                        format!("Failed to create recording spill file {}", path.0.display())
+                    })?;
+                debug!(path = %path.0.display(), "Spilling recording to temp file");
+                Ok(Self::TempFile {
+                    writer: BufWriter::new(file),
+                    path,
+                    len: 0,
+                })
+            }
+            RecordingStorage::Ring { max_duration } => Ok(Self::Ring {
+                data: VecDeque::new(),
+                max_duration,
+            }),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Memory(data) => data.len(),
+            Self::TempFile { len, .. } => *len,
+            Self::Ring { data, .. } => data.len(),
+        }
+    }
+
+    pub fn extend_from_slice(&mut self, data: &[u8]) -> eyre::Result<()> {
+        match self {
+            Self::Memory(buffer) => buffer.extend_from_slice(data),
+            Self::TempFile { writer, len, .. } => {
+                writer
+                    .write_all(data)
+                    .wrap_err("Failed to write audio to spill file")?;
+                *len += data.len();
+            }
+            Self::Ring { data: buffer, .. } => buffer.extend(data),
+        }
+        Ok(())
+    }
+
+    pub fn extend_silence(&mut self, byte_len: usize) -> eyre::Result<()> {
+        match self {
+            Self::Memory(buffer) => buffer.extend(std::iter::repeat_n(0u8, byte_len)),
+            Self::TempFile { writer, len, .. } => {
+                std::io::copy(&mut std::io::repeat(0).take(byte_len as u64), writer)
+                    .wrap_err("Failed to write silence to spill file")?;
+                *len += byte_len;
+            }
+            Self::Ring { data, .. } => data.extend(std::iter::repeat_n(0u8, byte_len)),
+        }
+        Ok(())
+    }
+
+    /// Drops the oldest frames of a [`RecordingStorage::Ring`] beyond its duration; other storage is unbounded.
+    ///
+    /// Call after each extend. Whole frames of `format` are dropped, so samples are never split.
+    pub fn enforce_limit(&mut self, format: AudioFormat) {
+        if let Self::Ring { data, max_duration } = self {
+            let max_frames = (max_duration.as_secs_f64() * format.sample_rate as f64) as usize;
+            let max_bytes = max_frames.saturating_mul(format.bytes_per_frame());
+            if data.len() > max_bytes {
+                let excess = data.len() - max_bytes;
+                // Packets hold whole frames, but round up in case an earlier format left a partial one
+                let excess = excess.next_multiple_of(format.bytes_per_frame().max(1));
+                data.drain(..excess.min(data.len()));
+            }
+        }
+    }
+
+    /// Converts the captured audio into WAV file bytes, consuming the buffer.
+    pub fn into_wav_bytes(self, format: AudioFormat) -> eyre::Result<Vec<u8>> {
+        match self {
+            Self::Memory(data) => create_wav_file(&data, format),
+            Self::TempFile { writer, path, len } => {
+                let mut file = writer
+                    .into_inner()
+                    .map_err(|e| e.into_error())
+                    .wrap_err("Failed to flush spill file")?;
+                file.seek(SeekFrom::Start(0))
+                    .wrap_err("Failed to rewind spill file")?;
+
+                // Stream from disk so only the WAV output is held in memory
+                let mut output = Cursor::new(Vec::with_capacity(len + 44));
+                write_wav_file(&mut output, BufReader::new(file), format)?;
+                drop(path);
+                Ok(output.into_inner())
+            }
+            Self::Ring { mut data, .. } => create_wav_file(data.make_contiguous(), format),
+        }
+    }
+
+    /// Writes the captured audio to `path` as a WAV file, consuming the buffer.
+    ///
+    /// A spilled recording is streamed from disk to disk, so it is never held in memory.
+    /// The file is written atomically, so `path` never holds a partial recording.
+    pub fn write_wav_file(self, path: &Path, format: AudioFormat) -> eyre::Result<()> {
+        match self {
+            Self::Memory(data) => write_atomic_with(path, |file| {
+                write_wav_file(BufWriter::new(file), data.as_slice(), format)
+            }),
+            Self::TempFile {
+                writer,
+                path: spill_path,
+                ..
+            } => {
+                let mut spill = writer
+                    .into_inner()
+                    .map_err(|e| e.into_error())
+                    .wrap_err("Failed to flush spill file")?;
+                spill
+                    .seek(SeekFrom::Start(0))
+                    .wrap_err("Failed to rewind spill file")?;
+                write_atomic_with(path, |file| {
+                    write_wav_file(BufWriter::new(file), BufReader::new(spill), format)
+                })?;
+                drop(spill_path);
+                Ok(())
+            }
+            Self::Ring { mut data, .. } => write_atomic_with(path, |file| {
+                write_wav_file(BufWriter::new(file), &*data.make_contiguous(), format)
+            }),
+        }
+    }
+}
+
+/// Removes the spill file when dropped.
+pub(crate) struct TempFilePath(PathBuf);
+
+impl TempFilePath {
+    fn new() -> eyre::Result<Self> {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .wrap_err("System clock is before the Unix epoch")?
+            .as_nanos();
+        Ok(Self(std::env::temp_dir().join(format!(
+            "teamy-recording-{}-{nanos}.pcm",
+            std::process::id()
+        ))))
+    }
+}
+
+impl Drop for TempFilePath {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_file(&self.0) {
+            warn!(path = %self.0.display(), "Failed to remove recording spill file: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::RecordingBuffer;
+    use super::RecordingStorage;
+    use crate::audio::AudioFormat;
+    use std::time::Duration;
+
+    #[test]
+    fn ring_keeps_latest_whole_frames() -> eyre::Result<()> {
+        // 10 frames per second of 16-bit stereo, 4 bytes per frame
+        let format = AudioFormat {
+            channels: 2,
+            sample_rate: 10,
+            bits_per_sample: 16,
+            is_float: false,
+        };
+        let mut ring = RecordingBuffer::new(RecordingStorage::Ring {
+            max_duration: Duration::from_millis(500),
+        })?;
+        for frame in 0..12u8 {
+            ring.extend_from_slice(&[frame; 4])?;
+            ring.enforce_limit(format);
+        }
+        assert_eq!(ring.len(), 5 * 4);
+
+        let RecordingBuffer::Ring { data, .. } = &ring else {
+            eyre::bail!("Expected a ring buffer");
+        };
+        let frames: Vec<u8> = data.iter().step_by(4).copied().collect();
+        assert_eq!(frames, vec![7, 8, 9, 10, 11]);
+        Ok(())
+    }
+
+    #[test]
+    fn temp_file_matches_memory() -> eyre::Result<()> {
+        let samples: Vec<u8> = (0..4096u16).flat_map(|x| x.to_le_bytes()).collect();
+
+        let mut memory = RecordingBuffer::new(RecordingStorage::Memory)?;
+        let mut spilled = RecordingBuffer::new(RecordingStorage::TempFile)?;
+        let RecordingBuffer::TempFile { path, .. } = &spilled else {
+            eyre::bail!("Expected a temp file buffer");
+        };
+        let spill_path = path.0.clone();
+
+        for buffer in [&mut memory, &mut spilled] {
+            buffer.extend_from_slice(&samples)?;
+            buffer.extend_silence(64)?;
+        }
+        assert_eq!(memory.len(), spilled.len());
+
+        let format = AudioFormat {
+            channels: 1,
+            sample_rate: 16_000,
+            bits_per_sample: 16,
+            is_float: false,
+        };
+        let from_memory = memory.into_wav_bytes(format)?;
+        let from_file = spilled.into_wav_bytes(format)?;
+        assert_eq!(from_memory, from_file);
+        assert!(!spill_path.exists());
+        Ok(())
+    }
+
+    #[test]
+    fn temp_file_streams_to_wav_file() -> eyre::Result<()> {
+        let samples: Vec<u8> = (0..4096u16).flat_map(|x| x.to_le_bytes()).collect();
+        let format = AudioFormat {
+            channels: 1,
+            sample_rate: 16_000,
+            bits_per_sample: 16,
+            is_float: false,
+        };
+
+        let mut memory = RecordingBuffer::new(RecordingStorage::Memory)?;
+        memory.extend_from_slice(&samples)?;
+        let mut spilled = RecordingBuffer::new(RecordingStorage::TempFile)?;
+        spilled.extend_from_slice(&samples)?;
+
+        let output = std::env::temp_dir().join(format!(
+            "teamy-recording-buffer-test-{}.wav",
+            std::process::id()
+        ));
+        spilled.write_wav_file(&output, format)?;
+        let written = std::fs::read(&output)?;
+        std::fs::remove_file(&output)?;
+        assert_eq!(written, memory.into_wav_bytes(format)?);
+        Ok(())
+    }
+}
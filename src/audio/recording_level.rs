@@ -0,0 +1,118 @@
+use crate::audio::AudioFormat;
+use std::sync::Arc;
+use std::sync::atomic::AtomicU32;
+use std::sync::atomic::Ordering;
+
+/// Shared peak amplitude of the most recent packet captured by a recording, for live level meters.
+///
+/// The capture loop stores the level as `f32` bits so a UI thread can read it without locking.
+/// Clones observe the same level. Two levels are equal only if they share it.
+#[derive(Debug, Clone, Default)]
+pub struct RecordingLevel(Arc<AtomicU32>);
+
+impl RecordingLevel {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Peak amplitude from 0.0 to 1.0; stays 0.0 until the first packet arrives.
+    pub fn peak(&self) -> f32 {
+        f32::from_bits(self.0.load(Ordering::Relaxed))
+    }
+
+    pub(crate) fn set(&self, peak: f32) {
+        self.0.store(peak.to_bits(), Ordering::Relaxed);
+    }
+}
+
+impl PartialEq for RecordingLevel {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for RecordingLevel {}
+
+/// Largest absolute sample in `audio_data` scaled to 0.0..=1.0, or 0.0 for unsupported formats.
+pub fn peak_amplitude(audio_data: &[u8], format: AudioFormat) -> f32 {
+    let peak = match (format.bits_per_sample, format.is_float) {
+        (16, false) => audio_data
+            .chunks_exact(2)
+            .map(|c| i16::from_le_bytes([c[0], c[1]]) as f32 / -(i16::MIN as f32))
+            .fold(0.0, |peak, sample| peak.max(sample.abs())),
+        (24, false) => audio_data
+            .chunks_exact(3)
+            .map(|c| (i32::from_le_bytes([0, c[0], c[1], c[2]]) >> 8) as f32 / 8_388_608.0)
+            .fold(0.0, |peak, sample| peak.max(sample.abs())),
+        (32, false) => audio_data
+            .chunks_exact(4)
+            .map(|c| i32::from_le_bytes([c[0], c[1], c[2], c[3]]) as f32 / -(i32::MIN as f32))
+            .fold(0.0, |peak, sample| peak.max(sample.abs())),
+        (32, true) => audio_data
+            .chunks_exact(4)
+            .map(|c| f32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+            .filter(|sample| sample.is_finite())
+            .fold(0.0, |peak, sample| peak.max(sample.abs())),
+        _ => 0.0,
+    };
+    peak.min(1.0)
+}
+
+#[cfg(test)]
+mod test {
+    use super::RecordingLevel;
+    use super::peak_amplitude;
+    use crate::audio::AudioFormat;
+
+    #[test]
+    fn peaks_per_sample_format() {
+        let int_16: Vec<u8> = [0i16, 8192, i16::MIN]
+            .iter()
+            .flat_map(|s| s.to_le_bytes())
+            .collect();
+        assert_eq!(
+            peak_amplitude(&int_16, AudioFormat::for_test(2, 48_000, 16, false)),
+            1.0
+        );
+        assert_eq!(
+            peak_amplitude(&int_16[..4], AudioFormat::for_test(2, 48_000, 16, false)),
+            0.25
+        );
+
+        let float: Vec<u8> = [0.1f32, -0.5, 2.0]
+            .iter()
+            .flat_map(|s| s.to_le_bytes())
+            .collect();
+        assert_eq!(
+            peak_amplitude(&float[..8], AudioFormat::for_test(2, 48_000, 32, true)),
+            0.5
+        );
+        assert_eq!(
+            peak_amplitude(&float, AudioFormat::for_test(2, 48_000, 32, true)),
+            1.0
+        );
+
+        // -0.5 as packed 24-bit
+        assert_eq!(
+            peak_amplitude(
+                &[0x00, 0x00, 0xC0],
+                AudioFormat::for_test(2, 48_000, 24, false)
+            ),
+            0.5
+        );
+        assert_eq!(
+            peak_amplitude(&[], AudioFormat::for_test(2, 48_000, 16, false)),
+            0.0
+        );
+    }
+
+    #[test]
+    fn clones_share_level() {
+        let level = RecordingLevel::new();
+        let clone = level.clone();
+        clone.set(0.75);
+        assert_eq!(level.peak(), 0.75);
+        assert_eq!(level, clone);
+        assert_ne!(level, RecordingLevel::new());
+    }
+}
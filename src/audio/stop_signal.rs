@@ -0,0 +1,95 @@
+use eyre::Context;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use windows::Win32::Foundation::HANDLE;
+use windows::Win32::System::Threading::CreateEventW;
+use windows::Win32::System::Threading::SetEvent;
+use windows::core::Owned;
+
+/// Shared flag that ends a recording early, e.g. from a Ctrl-C handler.
+///
+/// Clones observe the same flag. Two signals are equal only if they share it.
+#[derive(Debug, Clone, Default)]
+pub struct StopSignal(Arc<StopSignalInner>);
+
+#[derive(Debug, Default)]
+struct StopSignalInner {
+    stopped: AtomicBool,
+    /// Created on first use by a waiter, and set together with `stopped`.
+    event: Mutex<Option<StopEvent>>,
+}
+
+#[derive(Debug)]
+struct StopEvent(Owned<HANDLE>);
+
+// SAFETY: event handles may be waited on and set from any thread
+unsafe impl Send for StopEvent {}
+// SAFETY: see above; the handle is only closed when the last owner drops it
+unsafe impl Sync for StopEvent {}
+
+impl StopSignal {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn stop(&self) {
+        self.0.stopped.store(true, Ordering::SeqCst);
+        let event = self.0.event.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(event) = event.as_ref() {
+            _ = unsafe { SetEvent(*event.0) };
+        }
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        self.0.stopped.load(Ordering::SeqCst)
+    }
+
+    /// Manual-reset event that becomes signaled once [`stop`](Self::stop) is called, for use with `WaitForMultipleObjects`.
+    ///
+    /// The handle stays valid as long as this signal or any of its clones is alive.
+    pub(crate) fn event(&self) -> eyre::Result<HANDLE> {
+        let mut event = self.0.event.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(event) = event.as_ref() {
+            return Ok(*event.0);
+        }
+        let handle = unsafe { CreateEventW(None, true, self.is_stopped(), None) }
+            .wrap_err("Failed to create stop event")?;
+        *event = Some(StopEvent(unsafe { Owned::new(handle) }));
+        Ok(handle)
+    }
+}
+
+impl PartialEq for StopSignal {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for StopSignal {}
+
+#[cfg(test)]
+mod test {
+    use super::StopSignal;
+    use windows::Win32::Foundation::WAIT_OBJECT_0;
+    use windows::Win32::Foundation::WAIT_TIMEOUT;
+    use windows::Win32::System::Threading::WaitForSingleObject;
+
+    #[test]
+    fn event_follows_stop() -> eyre::Result<()> {
+        let signal = StopSignal::new();
+        let event = signal.event()?;
+        assert_eq!(unsafe { WaitForSingleObject(event, 0) }, WAIT_TIMEOUT);
+
+        signal.clone().stop();
+        assert_eq!(unsafe { WaitForSingleObject(event, 0) }, WAIT_OBJECT_0);
+
+        // Created after stopping, so it starts signaled
+        let stopped = StopSignal::new();
+        stopped.stop();
+        let event = stopped.event()?;
+        assert_eq!(unsafe { WaitForSingleObject(event, 0) }, WAIT_OBJECT_0);
+        Ok(())
+    }
+}
@@ -0,0 +1,320 @@
+use eyre::Result;
+use eyre::bail;
+use eyre::ensure;
+
+const WAVE_FORMAT_PCM: u16 = 0x0001;
+const WAVE_FORMAT_IEEE_FLOAT: u16 = 0x0003;
+const WAVE_FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+
+/// Bytes 2..16 shared by every `KSDATAFORMAT_SUBTYPE_*` GUID, whose first two bytes are the format tag.
+const SUBFORMAT_GUID_TAIL: [u8; 14] = [
+    0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x80, 0x00, 0x00, 0xAA, 0x00, 0x38, 0x9B, 0x71,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WavSampleFormat {
+    Int,
+    Float,
+}
+
+/// The contents of a WAV `fmt ` chunk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WavFormat {
+    pub channels: u16,
+    pub sample_rate: u32,
+    /// Container size of each sample, e.g. 24 for packed 3-byte samples.
+    pub bits_per_sample: u16,
+    pub sample_format: WavSampleFormat,
+    /// Speaker positions from a `WAVEFORMATEXTENSIBLE` header, `None` for plain `PCMWAVEFORMAT`.
+    pub channel_mask: Option<u32>,
+}
+
+impl WavFormat {
+    /// Size of one frame, failing when a malformed header claims more than fits in `nBlockAlign`.
+    pub fn block_align(&self) -> Result<u16> {
+        self.channels
+            .checked_mul(self.bits_per_sample.div_ceil(8))
+            .ok_or_else(|| {
+                eyre::eyre!(
+                    "Block alignment of {} channels at {} bits per sample overflows",
+                    self.channels,
+                    self.bits_per_sample
+                )
+            })
+    }
+}
+
+/// Validates the RIFF/WAVE structure of `bytes` and returns the format along with the raw sample data.
+///
+/// Accepts both `PCMWAVEFORMAT` and `WAVEFORMATEXTENSIBLE` `fmt ` chunks. Unknown chunks are skipped.
+pub fn parse_wav(bytes: &[u8]) -> Result<(WavFormat, &[u8])> {
+    ensure!(bytes.len() >= 12, "WAV is too short for a RIFF header");
+    ensure!(&bytes[0..4] == b"RIFF", "Missing RIFF magic");
+    ensure!(&bytes[8..12] == b"WAVE", "Missing WAVE form type");
+    // Header lengths are untrusted, so the arithmetic on them is checked for 32-bit targets
+    let riff_len = read_u32(bytes, 4) as usize;
+    ensure!(
+        riff_len >= 4,
+        "RIFF chunk is too short to hold the WAVE form type"
+    );
+    let riff_end = riff_len
+        .checked_add(8)
+        .filter(|&end| end <= bytes.len())
+        .ok_or_else(|| {
+            eyre::eyre!(
+                "RIFF chunk claims {} bytes but only {} follow its header",
+                riff_len,
+                bytes.len() - 8
+            )
+        })?;
+
+    let mut format = None;
+    let mut data = None;
+    let mut rest = &bytes[12..riff_end];
+    while rest.len() >= 8 {
+        let id = &rest[0..4];
+        let len = read_u32(rest, 4) as usize;
+        let overrun = || eyre::eyre!("Chunk {:?} overruns the RIFF chunk", id.escape_ascii());
+        let body_end = len.checked_add(8).ok_or_else(overrun)?;
+        let body = rest.get(8..body_end).ok_or_else(overrun)?;
+        match id {
+            b"fmt " => format = Some(parse_fmt(body)?),
+            b"data" => data = Some(body),
+            _ => {}
+        }
+        // Chunks are padded to an even length
+        let next = body_end
+            .checked_add(len & 1)
+            .ok_or_else(overrun)?
+            .min(rest.len());
+        rest = &rest[next..];
+    }
+
+    let Some(format) = format else {
+        bail!("Missing fmt chunk");
+    };
+    let Some(data) = data else {
+        bail!("Missing data chunk");
+    };
+    let block_align = format.block_align()?;
+    ensure!(
+        data.len() % block_align as usize == 0,
+        "Data length {} is not a multiple of the block alignment {}",
+        data.len(),
+        block_align
+    );
+    Ok((format, data))
+}
+
+fn parse_fmt(body: &[u8]) -> Result<WavFormat> {
+    ensure!(body.len() >= 16, "fmt chunk is too short");
+    let mut format_tag = read_u16(body, 0);
+    let channels = read_u16(body, 2);
+    let sample_rate = read_u32(body, 4);
+    let byte_rate = read_u32(body, 8);
+    let block_align = read_u16(body, 12);
+    let bits_per_sample = read_u16(body, 14);
+
+    let mut channel_mask = None;
+    if format_tag == WAVE_FORMAT_EXTENSIBLE {
+        ensure!(
+            body.len() >= 40,
+            "WAVEFORMATEXTENSIBLE fmt chunk is too short"
+        );
+        let extension_len = read_u16(body, 16);
+        ensure!(
+            extension_len >= 22,
+            "WAVEFORMATEXTENSIBLE cbSize is {extension_len}, expected at least 22"
+        );
+        let valid_bits = read_u16(body, 18);
+        ensure!(
+            valid_bits <= bits_per_sample,
+            "Valid bits {valid_bits} exceed container size {bits_per_sample}"
+        );
+        channel_mask = Some(read_u32(body, 20));
+        let subformat = &body[24..40];
+        ensure!(
+            subformat[2..] == SUBFORMAT_GUID_TAIL,
+            "Unrecognized sub-format GUID {:02X?}",
+            subformat
+        );
+        format_tag = read_u16(subformat, 0);
+    }
+
+    let sample_format = match (format_tag, bits_per_sample) {
+        (WAVE_FORMAT_PCM, 8 | 16 | 24 | 32) => WavSampleFormat::Int,
+        (WAVE_FORMAT_IEEE_FLOAT, 32 | 64) => WavSampleFormat::Float,
+        (tag, bits) => bail!("Unsupported format tag {tag:#06X} with {bits} bits per sample"),
+    };
+    ensure!(channels > 0, "WAV has no channels");
+
+    let format = WavFormat {
+        channels,
+        sample_rate,
+        bits_per_sample,
+        sample_format,
+        channel_mask,
+    };
+    let expected_block_align = format.block_align()?;
+    ensure!(
+        block_align == expected_block_align,
+        "Block alignment is {block_align}, expected {expected_block_align}"
+    );
+    let Some(expected_byte_rate) = sample_rate.checked_mul(u32::from(block_align)) else {
+        bail!("Byte rate of {sample_rate} Hz with block alignment {block_align} overflows");
+    };
+    ensure!(
+        byte_rate == expected_byte_rate,
+        "Byte rate is {byte_rate}, expected {expected_byte_rate}"
+    );
+    Ok(format)
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([bytes[offset], bytes[offset + 1]])
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([
+        bytes[offset],
+        bytes[offset + 1],
+        bytes[offset + 2],
+        bytes[offset + 3],
+    ])
+}
+
+#[cfg(test)]
+mod test {
+    use super::WavSampleFormat;
+    use super::parse_wav;
+    use crate::audio::AudioFormat;
+    use crate::audio::create_wav_file;
+
+    #[test]
+    fn round_trips_written_wav() -> eyre::Result<()> {
+        let audio_data = [
+            0x01, 0x00, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x7F, 0x00, 0x00, 0x80,
+        ];
+        let wav_bytes = create_wav_file(&audio_data, AudioFormat::for_test(2, 48_000, 24, false))?;
+
+        let (format, data) = parse_wav(&wav_bytes)?;
+        assert_eq!(format.channels, 2);
+        assert_eq!(format.sample_rate, 48_000);
+        assert_eq!(format.bits_per_sample, 24);
+        assert_eq!(format.sample_format, WavSampleFormat::Int);
+        assert_eq!(data, audio_data);
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_truncated_data() -> eyre::Result<()> {
+        let wav_bytes = create_wav_file(&[0u8; 8], AudioFormat::for_test(1, 8_000, 16, false))?;
+        assert!(parse_wav(&wav_bytes[..wav_bytes.len() - 1]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_overflowing_header_fields() -> eyre::Result<()> {
+        let wav_bytes = create_wav_file(&[0u8; 8], AudioFormat::for_test(1, 8_000, 16, false))?;
+
+        // nChannels is at offset 22, nSamplesPerSec at 24
+        let mut many_channels = wav_bytes.clone();
+        many_channels[22..24].copy_from_slice(&u16::MAX.to_le_bytes());
+        assert!(parse_wav(&many_channels).is_err());
+
+        let mut huge_rate = wav_bytes;
+        huge_rate[24..28].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(parse_wav(&huge_rate).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_maximal_lengths() -> eyre::Result<()> {
+        let wav_bytes = create_wav_file(&[0u8; 8], AudioFormat::for_test(1, 8_000, 16, false))?;
+
+        // The RIFF length is at offset 4 and the fmt chunk length at 16
+        let mut huge_riff = wav_bytes.clone();
+        huge_riff[4..8].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(parse_wav(&huge_riff).is_err());
+
+        let mut tiny_riff = wav_bytes.clone();
+        tiny_riff[4..8].copy_from_slice(&0u32.to_le_bytes());
+        assert!(parse_wav(&tiny_riff).is_err());
+
+        let mut huge_chunk = wav_bytes;
+        huge_chunk[16..20].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(parse_wav(&huge_chunk).is_err());
+        Ok(())
+    }
+
+    /// Writes `audio_data` with hound through [`create_wav_file`] and checks [`parse_wav`] reads back the same format and bytes.
+    fn assert_round_trip(audio_data: &[u8], audio_format: AudioFormat) -> eyre::Result<()> {
+        let AudioFormat {
+            channels,
+            sample_rate,
+            bits_per_sample,
+            is_float,
+        } = audio_format;
+        let wav_bytes = create_wav_file(audio_data, audio_format)?;
+
+        let (format, data) = parse_wav(&wav_bytes)?;
+        assert_eq!(format.channels, channels);
+        assert_eq!(format.sample_rate, sample_rate);
+        assert_eq!(format.bits_per_sample, bits_per_sample);
+        assert_eq!(
+            format.sample_format,
+            if is_float {
+                WavSampleFormat::Float
+            } else {
+                WavSampleFormat::Int
+            }
+        );
+        assert_eq!(data, audio_data);
+        Ok(())
+    }
+
+    #[test]
+    fn round_trips_mono_16_bit() -> eyre::Result<()> {
+        let audio_data: Vec<u8> = [0i16, 1, -1, i16::MAX, i16::MIN]
+            .iter()
+            .flat_map(|s| s.to_le_bytes())
+            .collect();
+        assert_round_trip(&audio_data, AudioFormat::for_test(1, 16_000, 16, false))
+    }
+
+    #[test]
+    fn round_trips_stereo_float() -> eyre::Result<()> {
+        let audio_data: Vec<u8> = [0.0f32, 0.5, -0.5, 1.0, -1.0, 0.25]
+            .iter()
+            .flat_map(|s| s.to_le_bytes())
+            .collect();
+        assert_round_trip(&audio_data, AudioFormat::for_test(2, 48_000, 32, true))
+    }
+
+    #[test]
+    fn round_trips_six_channel_24_bit() -> eyre::Result<()> {
+        // Two frames of 5.1 audio, each channel holding a distinct packed 24-bit sample
+        let audio_data: Vec<u8> = (0..12i32)
+            .flat_map(|i| {
+                let sample = (i - 6) * 0x01_0203;
+                sample.to_le_bytes()[..3].to_vec()
+            })
+            .collect();
+        assert_round_trip(&audio_data, AudioFormat::for_test(6, 44_100, 24, false))?;
+
+        // More than two channels requires WAVEFORMATEXTENSIBLE, with the first six speaker positions assigned
+        let wav_bytes = create_wav_file(&audio_data, AudioFormat::for_test(6, 44_100, 24, false))?;
+        let (format, _) = parse_wav(&wav_bytes)?;
+        assert_eq!(format.channel_mask, Some(0x3F));
+        Ok(())
+    }
+
+    #[test]
+    fn round_trips_32_bit_int() -> eyre::Result<()> {
+        let audio_data: Vec<u8> = [0i32, 1, -1, i32::MAX, i32::MIN, 0x1234_5678]
+            .iter()
+            .flat_map(|s| s.to_le_bytes())
+            .collect();
+        assert_round_trip(&audio_data, AudioFormat::for_test(2, 48_000, 32, false))
+    }
+}
@@ -1,3 +1,4 @@
+use crate::cli::global_args::GlobalArgs;
 use crate::cli::to_args::ToArgs;
 use arbitrary::Arbitrary;
 use clap::Args;
@@ -21,8 +22,8 @@ impl ToArgs for ClipboardArgs {
 }
 
 impl ClipboardArgs {
-    pub fn invoke(self) -> Result<()> {
-        self.command.invoke()
+    pub fn invoke(self, global_args: &GlobalArgs) -> Result<()> {
+        self.command.invoke(global_args)
     }
 }
 
@@ -50,9 +51,9 @@ impl ToArgs for ClipboardCommand {
 }
 
 impl ClipboardCommand {
-    pub fn invoke(self) -> Result<()> {
+    pub fn invoke(self, global_args: &GlobalArgs) -> Result<()> {
         match self {
-            ClipboardCommand::Show(args) => args.invoke(),
+            ClipboardCommand::Show(args) => args.invoke(global_args),
             ClipboardCommand::Set(args) => args.invoke(),
         }
     }
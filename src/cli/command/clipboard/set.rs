@@ -1,25 +1,68 @@
 use crate::cli::to_args::ToArgs;
+use crate::clipboard::set_clipboard_image;
 use crate::clipboard::write_clipboard;
 use arbitrary::Arbitrary;
 use clap::Args;
 use eyre::Context;
 use eyre::Result;
 use std::ffi::OsString;
+use std::path::PathBuf;
 
-#[derive(Args, Debug, Arbitrary, PartialEq)]
+#[derive(Args, Debug, PartialEq)]
+#[group(required = true, multiple = false)]
 pub struct ClipboardSetArgs {
     #[arg(value_name = "TEXT")]
-    pub value: String,
+    pub value: Option<String>,
+
+    /// Put the image at this path on the clipboard instead of text.
+    #[arg(long)]
+    pub image: Option<PathBuf>,
+}
+
+impl<'a> Arbitrary<'a> for ClipboardSetArgs {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        if bool::arbitrary(u)? {
+            let mut image = PathBuf::arbitrary(u)?;
+            if image.as_os_str().is_empty() {
+                image = PathBuf::from("image.png");
+            }
+            Ok(ClipboardSetArgs {
+                value: None,
+                image: Some(image),
+            })
+        } else {
+            Ok(ClipboardSetArgs {
+                value: Some(String::arbitrary(u)?),
+                image: None,
+            })
+        }
+    }
 }
 
 impl ToArgs for ClipboardSetArgs {
     fn to_args(&self) -> Vec<OsString> {
-        vec![self.value.clone().into()]
+        let mut args = Vec::new();
+        if let Some(image) = &self.image {
+            let mut arg = OsString::from("--image=");
+            arg.push(image);
+            args.push(arg);
+        }
+        if let Some(value) = &self.value {
+            args.push("--".into());
+            args.push(value.clone().into());
+        }
+        args
     }
 }
 
 impl ClipboardSetArgs {
     pub fn invoke(self) -> Result<()> {
-        write_clipboard(self.value).wrap_err("Failed to set clipboard text")
+        if let Some(path) = self.image {
+            let image = image::open(&path)
+                .wrap_err_with(|| format!("Failed to open image {}", path.display()))?
+                .to_rgba8();
+            return set_clipboard_image(&image).wrap_err("Failed to set clipboard image");
+        }
+        write_clipboard(self.value.unwrap_or_default()).wrap_err("Failed to set clipboard text")
     }
 }
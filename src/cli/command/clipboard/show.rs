@@ -1,15 +1,24 @@
+use crate::cli::global_args::GlobalArgs;
+use crate::cli::output_format::OutputFormat;
+use crate::cli::output_format::render;
 use crate::cli::to_args::ToArgs;
 use crate::clipboard::ClipboardFormatExt;
 use crate::clipboard::ClipboardGuard;
+use crate::clipboard::decode_clipboard_text;
+use crate::clipboard::read_open_clipboard_image;
+use crate::clipboard::read_open_clipboard_image_size;
 use arbitrary::Arbitrary;
 use clap::Args;
 use eyre::Context;
 use eyre::Result;
+use facet::Facet;
+use image::RgbaImage;
 use std::convert::TryFrom;
-use std::ffi::CStr;
 use std::ffi::OsString;
-use std::os::raw::c_char;
 use std::os::windows::ffi::OsStringExt;
+use std::path::PathBuf;
+use tracing::info;
+use tracing::warn;
 use widestring::U16CStr;
 use windows::Win32::Foundation::ERROR_SUCCESS;
 use windows::Win32::Foundation::GetLastError;
@@ -29,27 +38,142 @@ use windows::Win32::System::Ole::CLIPBOARD_FORMAT;
 use windows::Win32::UI::Shell::DragQueryFileW;
 use windows::Win32::UI::Shell::HDROP;
 
-#[derive(Args, Debug, Arbitrary, PartialEq)]
-pub struct ClipboardShowArgs {}
+#[derive(Args, Debug, PartialEq)]
+pub struct ClipboardShowArgs {
+    /// Output format.
+    #[clap(long, value_enum, default_value_t = OutputFormat::Auto)]
+    pub output_format: OutputFormat,
+
+    /// Also save the clipboard image to this path, as PNG unless the extension says otherwise.
+    #[clap(long)]
+    pub save_image: Option<PathBuf>,
+}
+
+impl<'a> Arbitrary<'a> for ClipboardShowArgs {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let save_image = Option::<PathBuf>::arbitrary(u)?.map(|path| {
+            if path.as_os_str().is_empty() {
+                PathBuf::from("clipboard.png")
+            } else {
+                path
+            }
+        });
+        Ok(ClipboardShowArgs {
+            output_format: OutputFormat::arbitrary(u)?,
+            save_image,
+        })
+    }
+}
 
 impl ToArgs for ClipboardShowArgs {
     fn to_args(&self) -> Vec<OsString> {
-        Vec::new()
+        let mut args = self.output_format.to_args("--output-format");
+        if let Some(path) = &self.save_image {
+            let mut arg = OsString::from("--save-image=");
+            arg.push(path);
+            args.push(arg);
+        }
+        args
     }
 }
 
 impl ClipboardShowArgs {
-    pub fn invoke(self) -> Result<()> {
-        let description = describe_clipboard_contents()?;
-        println!("{}", description);
-        Ok(())
+    pub fn invoke(self, global_args: &GlobalArgs) -> Result<()> {
+        let (contents, image) = read_clipboard_contents_and_image(self.save_image.is_some())?;
+        if let Some(path) = &self.save_image {
+            let image = image.ok_or_else(|| eyre::eyre!("No image data on the clipboard"))?;
+            let format = image::ImageFormat::from_path(path).unwrap_or(image::ImageFormat::Png);
+            image.save_with_format(path, format).wrap_err_with(|| {
+                format!("Failed to save clipboard image to {}", path.display())
+            })?;
+            info!("Saved clipboard image to {}", path.display());
+        }
+        render(&contents, self.output_format, global_args, |contents| {
+            println!("{}", contents.describe());
+            Ok(())
+        })
+    }
+}
+
+/// Snapshot of everything currently on the clipboard.
+#[derive(Facet, Debug)]
+pub struct ClipboardContents {
+    /// Paths from drag-and-drop (`CF_HDROP`) data, if present.
+    pub files: Option<Vec<String>>,
+    /// Size of the bitmap (`CF_DIBV5` or `CF_DIB`) data, if present.
+    pub image: Option<ClipboardImageSize>,
+    pub formats: Vec<ClipboardFormatContents>,
+    /// Error code from `EnumClipboardFormats` if enumeration stopped early.
+    pub enum_error: Option<u32>,
+}
+
+#[derive(Facet, Debug)]
+pub struct ClipboardImageSize {
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Facet, Debug)]
+pub struct ClipboardFormatContents {
+    pub id: u32,
+    pub name: String,
+    /// `None` when the clipboard returned no data for this format.
+    pub content: Option<String>,
+}
+
+impl ClipboardContents {
+    /// Human-readable listing of the files and formats.
+    pub fn describe(&self) -> String {
+        let mut description = String::new();
+
+        if let Some(files) = &self.files {
+            description.push_str(&format!("Found {} files in clipboard:\n", files.len()));
+            for file in files {
+                description.push_str(&format!("- {}\n", file));
+            }
+        }
+
+        if let Some(image) = &self.image {
+            description.push_str(&format!("Image: {}x{}\n", image.width, image.height));
+        }
+
+        for format in &self.formats {
+            description.push_str(&format!("\nFormat: {} (0x{:X})\n", format.name, format.id));
+            if let Some(content) = &format.content {
+                description.push_str(&format!("Content: {}\n", content));
+            }
+        }
+
+        if let Some(error) = self.enum_error {
+            description.push_str(&format!("\nEnumClipboardFormats error: {}\n", error));
+        }
+
+        description
     }
 }
 
 pub fn describe_clipboard_contents() -> Result<String> {
+    Ok(read_clipboard_contents()?.describe())
+}
+
+pub fn read_clipboard_contents() -> Result<ClipboardContents> {
+    read_clipboard_contents_and_image(false).map(|(contents, _)| contents)
+}
+
+/// Like [`read_clipboard_contents`], also decoding the clipboard image when `decode_image` is set.
+///
+/// Otherwise only the bitmap header is read for its size, so listing a large image stays cheap.
+fn read_clipboard_contents_and_image(
+    decode_image: bool,
+) -> Result<(ClipboardContents, Option<RgbaImage>)> {
     let _guard = ClipboardGuard::open().wrap_err("Failed to open clipboard")?;
 
-    let mut description = String::new();
+    let mut contents = ClipboardContents {
+        files: None,
+        image: None,
+        formats: Vec::new(),
+        enum_error: None,
+    };
 
     // If the clipboard currently contains drag-and-drop data, list the file paths.
     if unsafe { IsClipboardFormatAvailable(CF_HDROP.0 as u32).is_ok() } {
@@ -57,16 +181,33 @@ pub fn describe_clipboard_contents() -> Result<String> {
         if !file_data.is_invalid() {
             let hdrop = HDROP(file_data.0);
             let file_count = unsafe { DragQueryFileW(hdrop, u32::MAX, None) };
-            description.push_str(&format!("Found {} files in clipboard:\n", file_count));
+            let mut files = Vec::with_capacity(file_count as usize);
 
             for i in 0..file_count {
                 let mut buffer = vec![0u16; MAX_PATH as usize];
                 let len = unsafe { DragQueryFileW(hdrop, i, Some(buffer.as_mut_slice())) };
                 if len > 0 {
                     let path = OsString::from_wide(&buffer[..len as usize]);
-                    description.push_str(&format!("- {}\n", path.to_string_lossy()));
+                    files.push(path.to_string_lossy().into_owned());
                 }
             }
+            contents.files = Some(files);
+        }
+    }
+
+    let mut image = None;
+    if decode_image {
+        image = read_open_clipboard_image()?;
+        contents.image = image.as_ref().map(|image| ClipboardImageSize {
+            width: image.width(),
+            height: image.height(),
+        });
+    } else {
+        match read_open_clipboard_image_size() {
+            Ok(size) => {
+                contents.image = size.map(|(width, height)| ClipboardImageSize { width, height });
+            }
+            Err(e) => warn!("Failed to read clipboard image size: {:?}", e),
         }
     }
 
@@ -76,21 +217,22 @@ pub fn describe_clipboard_contents() -> Result<String> {
         if next_format == 0 {
             let error = unsafe { GetLastError() };
             if error != ERROR_SUCCESS {
-                description.push_str(&format!("\nEnumClipboardFormats error: {}\n", error.0));
+                contents.enum_error = Some(error.0);
             }
             break;
         }
 
         format = next_format;
         let format_name = CLIPBOARD_FORMAT(u16::try_from(format)?);
-        description.push_str(&format!(
-            "\nFormat: {} (0x{:X})\n",
-            format_name.display(),
-            format
-        ));
+        let mut entry = ClipboardFormatContents {
+            id: format,
+            name: format_name.display().into_owned(),
+            content: None,
+        };
 
         let data_handle = unsafe { GetClipboardData(format)? };
         if data_handle.is_invalid() {
+            contents.formats.push(entry);
             continue;
         }
 
@@ -98,8 +240,9 @@ pub fn describe_clipboard_contents() -> Result<String> {
         let hglobal = HGLOBAL(data_handle.0);
 
         let content = match format {
-            x if x == CF_TEXT.0 as u32 => read_clipboard_ascii(hglobal),
-            x if x == CF_OEMTEXT.0 as u32 => read_clipboard_ascii(hglobal),
+            x if x == CF_TEXT.0 as u32 || x == CF_OEMTEXT.0 as u32 => {
+                decode_clipboard_text(hglobal, x).unwrap_or_else(|e| format!("[{e}]"))
+            }
             x if x == CF_UNICODETEXT.0 as u32 => read_clipboard_unicode(hglobal),
             _ => {
                 // Fallback for unknown formats: report the raw buffer length.
@@ -108,24 +251,11 @@ pub fn describe_clipboard_contents() -> Result<String> {
             }
         };
 
-        description.push_str(&format!("Content: {}\n", content));
-    }
-
-    Ok(description)
-}
-
-fn read_clipboard_ascii(handle: HGLOBAL) -> String {
-    // Lock the global handle so we can read the raw bytes safely.
-    let lock = unsafe { GlobalLock(handle) };
-    if lock.is_null() {
-        return "[Failed to lock clipboard data]".into();
+        entry.content = Some(content);
+        contents.formats.push(entry);
     }
 
-    let data_ptr = lock as *const u8;
-    let c_str = unsafe { CStr::from_ptr(data_ptr as *const c_char) };
-    let result = String::from_utf8_lossy(c_str.to_bytes()).to_string();
-    let _ = unsafe { GlobalUnlock(handle) };
-    result
+    Ok((contents, image))
 }
 
 fn read_clipboard_unicode(handle: HGLOBAL) -> String {
@@ -20,6 +20,6 @@ impl DaemonRunArgs {
 
 impl ToArgs for DaemonRunArgs {
     fn to_args(&self) -> Vec<OsString> {
-        vec!["--id".into(), self.daemon_id.clone().into()]
+        vec![format!("--id={}", self.daemon_id).into()]
     }
 }
@@ -22,6 +22,6 @@ impl DaemonStartArgs {
 
 impl ToArgs for DaemonStartArgs {
     fn to_args(&self) -> Vec<OsString> {
-        vec!["--id".into(), self.daemon_id.clone().into()]
+        vec![format!("--id={}", self.daemon_id).into()]
     }
 }
@@ -1,16 +1,30 @@
+use crate::cli::global_args::GlobalArgs;
+use crate::cli::output_format::OutputFormat;
+use crate::cli::output_format::render;
 use crate::cli::to_args::ToArgs;
-use crate::shell::context_menu::get_context_menu_entries;
+use crate::shell::context_menu::ContextMenuEntry;
+use crate::shell::context_menu::get_context_menu_entries_with_flags;
 use crate::shell::path_extensions::PathExtensions;
 use arbitrary::Arbitrary;
 use clap::Args;
 use eyre::Result;
 use std::ffi::OsString;
 use std::path::PathBuf;
+use windows::Win32::UI::Shell::CMF_EXTENDEDVERBS;
+use windows::Win32::UI::Shell::CMF_NORMAL;
 
 #[derive(Args, Debug, PartialEq)]
 pub struct EntryListArgs {
     #[arg(long)]
     pub r#for: PathBuf,
+
+    /// Include the extended verbs normally shown only on Shift+right-click.
+    #[arg(long)]
+    pub extended: bool,
+
+    /// Output format.
+    #[clap(long, value_enum, default_value_t = OutputFormat::Auto)]
+    pub output_format: OutputFormat,
 }
 
 impl<'a> Arbitrary<'a> for EntryListArgs {
@@ -19,29 +33,46 @@ impl<'a> Arbitrary<'a> for EntryListArgs {
         if p.as_os_str().is_empty() {
             p = PathBuf::from(".");
         }
-        Ok(EntryListArgs { r#for: p })
+        Ok(EntryListArgs {
+            r#for: p,
+            extended: bool::arbitrary(u)?,
+            output_format: OutputFormat::arbitrary(u)?,
+        })
     }
 }
 
 impl ToArgs for EntryListArgs {
     fn to_args(&self) -> Vec<OsString> {
-        vec!["--for".into(), self.r#for.clone().into()]
+        let mut arg = OsString::from("--for=");
+        arg.push(&self.r#for);
+        let mut args = vec![arg];
+        if self.extended {
+            args.push("--extended".into());
+        }
+        args.extend(self.output_format.to_args("--output-format"));
+        args
     }
 }
 
 impl EntryListArgs {
-    pub fn invoke(self) -> Result<()> {
+    pub fn invoke(self, global_args: &GlobalArgs) -> Result<()> {
         let path = self.r#for.unc_canonicalize()?;
-        println!("Inspecting context menu for: {}", path.display());
-
-        let entries = unsafe { get_context_menu_entries(&path)? };
-        print_entries(&entries, 0);
 
-        Ok(())
+        let flags = if self.extended {
+            CMF_NORMAL | CMF_EXTENDEDVERBS
+        } else {
+            CMF_NORMAL
+        };
+        let entries = unsafe { get_context_menu_entries_with_flags(&path, flags)? };
+        render(&entries, self.output_format, global_args, |entries| {
+            println!("Inspecting context menu for: {}", path.display());
+            print_entries(entries, 0);
+            Ok(())
+        })
     }
 }
 
-fn print_entries(entries: &[crate::shell::context_menu::ContextMenuEntry], depth: usize) {
+fn print_entries(entries: &[ContextMenuEntry], depth: usize) {
     let indent = "  ".repeat(depth);
     for entry in entries {
         if entry.is_separator {
@@ -1,3 +1,4 @@
+use crate::cli::global_args::GlobalArgs;
 use crate::cli::to_args::ToArgs;
 use arbitrary::Arbitrary;
 use clap::Args;
@@ -20,8 +21,8 @@ impl ToArgs for EntryArgs {
 }
 
 impl EntryArgs {
-    pub fn invoke(self) -> Result<()> {
-        self.command.invoke()
+    pub fn invoke(self, global_args: &GlobalArgs) -> Result<()> {
+        self.command.invoke(global_args)
     }
 }
 
@@ -43,9 +44,9 @@ impl ToArgs for EntryCommand {
 }
 
 impl EntryCommand {
-    pub fn invoke(self) -> Result<()> {
+    pub fn invoke(self, global_args: &GlobalArgs) -> Result<()> {
         match self {
-            EntryCommand::List(args) => args.invoke(),
+            EntryCommand::List(args) => args.invoke(global_args),
         }
     }
 }
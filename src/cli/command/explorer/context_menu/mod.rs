@@ -1,3 +1,4 @@
+use crate::cli::global_args::GlobalArgs;
 use crate::cli::to_args::ToArgs;
 use arbitrary::Arbitrary;
 use clap::Args;
@@ -20,8 +21,8 @@ impl ToArgs for ContextMenuArgs {
 }
 
 impl ContextMenuArgs {
-    pub fn invoke(self) -> Result<()> {
-        self.command.invoke()
+    pub fn invoke(self, global_args: &GlobalArgs) -> Result<()> {
+        self.command.invoke(global_args)
     }
 }
 
@@ -43,9 +44,9 @@ impl ToArgs for ContextMenuCommand {
 }
 
 impl ContextMenuCommand {
-    pub fn invoke(self) -> Result<()> {
+    pub fn invoke(self, global_args: &GlobalArgs) -> Result<()> {
         match self {
-            ContextMenuCommand::Entry(args) => args.invoke(),
+            ContextMenuCommand::Entry(args) => args.invoke(global_args),
         }
     }
 }
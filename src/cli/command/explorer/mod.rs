@@ -1,3 +1,4 @@
+use crate::cli::global_args::GlobalArgs;
 use crate::cli::to_args::ToArgs;
 use arbitrary::Arbitrary;
 use clap::Args;
@@ -21,8 +22,8 @@ impl ToArgs for ExplorerArgs {
 }
 
 impl ExplorerArgs {
-    pub fn invoke(self) -> Result<()> {
-        self.command.invoke()
+    pub fn invoke(self, global_args: &GlobalArgs) -> Result<()> {
+        self.command.invoke(global_args)
     }
 }
 
@@ -51,9 +52,9 @@ impl ToArgs for ExplorerCommand {
 }
 
 impl ExplorerCommand {
-    pub fn invoke(self) -> Result<()> {
+    pub fn invoke(self, global_args: &GlobalArgs) -> Result<()> {
         match self {
-            ExplorerCommand::ContextMenu(args) => args.invoke(),
+            ExplorerCommand::ContextMenu(args) => args.invoke(global_args),
             ExplorerCommand::Show(args) => args.invoke(),
         }
     }
@@ -34,7 +34,9 @@ impl<'a> Arbitrary<'a> for ShowArgs {
 
 impl ToArgs for ShowArgs {
     fn to_args(&self) -> Vec<OsString> {
-        self.paths.iter().map(|p| p.clone().into()).collect()
+        let mut args = vec!["--".into()];
+        args.extend(self.paths.iter().map(|p| p.clone().into()));
+        args
     }
 }
 
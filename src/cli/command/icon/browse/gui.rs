@@ -1,5 +1,6 @@
-use crate::hicon::hicon_to_rgba;
-use crate::string::EasyPCWSTR;
+use crate::clipboard::get_clipboard_image;
+use crate::hicon::extract_icon_rgba;
+use crate::hicon::get_icon_count;
 use eframe::egui;
 use egui_tiles::TileId;
 use egui_tiles::Tiles;
@@ -7,11 +8,8 @@ use eyre::Result;
 use std::collections::HashMap;
 use std::path::Path;
 use std::path::PathBuf;
-use windows::Win32::UI::Shell::ExtractIconExW;
-use windows::Win32::UI::WindowsAndMessaging::HICON;
-use windows::Win32::UI::WindowsAndMessaging::PrivateExtractIconsW;
 
-pub fn run_icon_browser(paths: Vec<PathBuf>) -> Result<()> {
+pub fn run_icon_browser(paths: Vec<PathBuf>, from_clipboard: bool) -> Result<()> {
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default().with_inner_size([900.0, 600.0]),
         ..Default::default()
@@ -20,7 +18,7 @@ pub fn run_icon_browser(paths: Vec<PathBuf>) -> Result<()> {
     eframe::run_native(
         "Icon Browser",
         options,
-        Box::new(|cc| Ok(Box::new(IconBrowserApp::new(cc, paths)))),
+        Box::new(move |cc| Ok(Box::new(IconBrowserApp::new(cc, paths, from_clipboard)))),
     )
     .map_err(|e| eyre::eyre!("Failed to run eframe: {}", e))
 }
@@ -38,6 +36,15 @@ pub struct LoadedIconInfo {
     pub height: u32,
 }
 
+/// An image pasted from the clipboard, shown in the preview pane instead of a selected icon
+pub struct PastedImage {
+    pub texture: egui::TextureHandle,
+    pub width: u32,
+    pub height: u32,
+    pub transparent_pixels: usize,
+    pub partially_transparent_pixels: usize,
+}
+
 /// Key for caching icons: (path, index, requested_size)
 type IconCacheKey = (PathBuf, u32, u32);
 
@@ -56,6 +63,8 @@ pub enum Pane {
 struct TreeBehavior {
     dll_entries: Vec<DllEntry>,
     selected_icon: Option<IconEntry>,
+    pasted_image: Option<PastedImage>,
+    paste_error: Option<String>,
     textures: HashMap<IconCacheKey, Option<LoadedIconInfo>>, // None means failed to load
     texture_handles: Vec<egui::TextureHandle>,               // Keep handles alive
 }
@@ -83,6 +92,8 @@ impl TreeBehavior {
         Self {
             dll_entries,
             selected_icon: None,
+            pasted_image: None,
+            paste_error: None,
             textures: HashMap::new(),
             texture_handles: Vec::new(),
         }
@@ -101,7 +112,7 @@ impl TreeBehavior {
         }
 
         // Try to load the icon at the requested size
-        if let Ok(rgba_image) = load_icon_from_dll_sized(dll_path, index, size) {
+        if let Ok(rgba_image) = extract_icon_rgba(dll_path, index, size) {
             let width = rgba_image.width();
             let height = rgba_image.height();
             let img_size = [width as usize, height as usize];
@@ -127,6 +138,40 @@ impl TreeBehavior {
         None
     }
 
+    /// Replace the preview with the image currently on the clipboard
+    fn paste_from_clipboard(&mut self, ctx: &egui::Context) {
+        match get_clipboard_image() {
+            Ok(rgba_image) => {
+                let width = rgba_image.width();
+                let height = rgba_image.height();
+                let alphas = rgba_image.pixels().map(|pixel| pixel.0[3]);
+                let transparent_pixels = alphas.clone().filter(|&a| a == 0).count();
+                let partially_transparent_pixels = alphas.filter(|&a| a != 0 && a != 255).count();
+                let color_image = egui::ColorImage::from_rgba_unmultiplied(
+                    [width as usize, height as usize],
+                    rgba_image.as_raw(),
+                );
+                let texture = ctx.load_texture(
+                    "clipboard_image",
+                    color_image,
+                    egui::TextureOptions::default(),
+                );
+                self.pasted_image = Some(PastedImage {
+                    texture,
+                    width,
+                    height,
+                    transparent_pixels,
+                    partially_transparent_pixels,
+                });
+                self.selected_icon = None;
+                self.paste_error = None;
+            }
+            Err(e) => {
+                self.paste_error = Some(format!("{e:#}"));
+            }
+        }
+    }
+
     /// Load icon at default 32x32 size for tree view
     fn load_icon_texture_default(
         &mut self,
@@ -173,6 +218,16 @@ impl egui_tiles::Behavior<Pane> for TreeBehavior {
 
 impl TreeBehavior {
     fn render_tree_pane(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            if ui.button("Paste image").clicked() {
+                self.paste_from_clipboard(ui.ctx());
+            }
+            if let Some(error) = &self.paste_error {
+                ui.colored_label(ui.visuals().error_fg_color, error);
+            }
+        });
+        ui.separator();
+
         egui::ScrollArea::vertical().show(ui, |ui| {
             let dll_entries = self.dll_entries.clone();
             for dll_entry in dll_entries.iter() {
@@ -211,6 +266,7 @@ impl TreeBehavior {
 
                                 if response.clicked() {
                                     self.selected_icon = Some(icon.clone());
+                                    self.pasted_image = None;
                                 }
 
                                 let hover_text = if let Some(ref info) = loaded_info {
@@ -233,6 +289,11 @@ impl TreeBehavior {
     }
 
     fn render_preview_pane(&mut self, ui: &mut egui::Ui) {
+        if let Some(pasted) = &self.pasted_image {
+            Self::render_pasted_preview(ui, pasted);
+            return;
+        }
+
         let selected_icon = self.selected_icon.clone();
         if let Some(icon) = selected_icon {
             egui::ScrollArea::vertical().show(ui, |ui| {
@@ -293,6 +354,43 @@ impl TreeBehavior {
             });
         }
     }
+
+    fn render_pasted_preview(ui: &mut egui::Ui, pasted: &PastedImage) {
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            ui.vertical_centered(|ui| {
+                ui.heading("Clipboard Image");
+                ui.separator();
+
+                ui.label(format!("Size: {}x{}", pasted.width, pasted.height));
+                ui.label(format!("Transparent pixels: {}", pasted.transparent_pixels));
+                ui.label(format!(
+                    "Partially transparent pixels: {}",
+                    pasted.partially_transparent_pixels
+                ));
+
+                ui.separator();
+                ui.heading("Actual Size");
+                ui.image((
+                    pasted.texture.id(),
+                    egui::vec2(pasted.width as f32, pasted.height as f32),
+                ));
+
+                ui.separator();
+                ui.heading("Scaled");
+
+                let sizes = [16, 24, 32, 48, 64, 96, 128, 256];
+
+                ui.horizontal_wrapped(|ui| {
+                    for &size in &sizes {
+                        ui.vertical(|ui| {
+                            ui.label(format!("{}x{}", size, size));
+                            ui.image((pasted.texture.id(), egui::vec2(size as f32, size as f32)));
+                        });
+                    }
+                });
+            });
+        });
+    }
 }
 
 struct IconBrowserApp {
@@ -301,7 +399,7 @@ struct IconBrowserApp {
 }
 
 impl IconBrowserApp {
-    fn new(_cc: &eframe::CreationContext<'_>, paths: Vec<PathBuf>) -> Self {
+    fn new(cc: &eframe::CreationContext<'_>, paths: Vec<PathBuf>, from_clipboard: bool) -> Self {
         let mut tiles = Tiles::default();
 
         let tree_pane = tiles.insert_pane(Pane::Tree);
@@ -310,7 +408,10 @@ impl IconBrowserApp {
         let root = tiles.insert_horizontal_tile(vec![tree_pane, preview_pane]);
 
         let tree = egui_tiles::Tree::new("icon_browser", root, tiles);
-        let behavior = TreeBehavior::new(paths);
+        let mut behavior = TreeBehavior::new(paths);
+        if from_clipboard {
+            behavior.paste_from_clipboard(&cc.egui_ctx);
+        }
 
         Self { tree, behavior }
     }
@@ -323,96 +424,3 @@ impl eframe::App for IconBrowserApp {
         });
     }
 }
-
-fn get_icon_count(path: &Path) -> Result<u32> {
-    let path_str = path.to_string_lossy();
-    let pcwstr = path_str.as_ref().easy_pcwstr()?;
-
-    // Pass -1 as nIconIndex and NULL for both icon arrays to get the count
-    let count = unsafe { ExtractIconExW(pcwstr.as_ref(), -1, None, None, 0) };
-
-    Ok(count)
-}
-
-fn load_icon_from_dll_sized(path: &Path, index: u32, size: u32) -> Result<image::RgbaImage> {
-    let path_str = path.to_string_lossy();
-
-    // PrivateExtractIconsW requires a fixed-size buffer of 260 u16s
-    let mut filename_buf: [u16; 260] = [0; 260];
-    for (i, c) in path_str.encode_utf16().take(259).enumerate() {
-        filename_buf[i] = c;
-    }
-
-    let mut icons: [HICON; 1] = [HICON::default()];
-    let mut icon_id: u32 = 0;
-
-    // Use PrivateExtractIconsW to extract icon at specific size
-    let extracted = unsafe {
-        PrivateExtractIconsW(
-            &filename_buf,
-            index as i32,
-            size as i32,
-            size as i32,
-            Some(&mut icons),
-            Some(&raw mut icon_id),
-            1,
-        )
-    };
-
-    if extracted == 0 || icons[0].is_invalid() {
-        // Fallback to ExtractIconExW for 32x32 icons
-        if size == 32 {
-            return load_icon_from_dll_extract(path, index);
-        }
-        eyre::bail!(
-            "Failed to extract icon at index {} with size {}",
-            index,
-            size
-        );
-    }
-
-    // The icon handle needs to be destroyed after use
-    let result = unsafe { hicon_to_rgba(icons[0]) };
-
-    // Destroy the icon handle
-    unsafe {
-        _ = windows::Win32::UI::WindowsAndMessaging::DestroyIcon(icons[0]);
-    }
-
-    result
-}
-
-/// Fallback using ExtractIconExW which works better for some DLLs
-fn load_icon_from_dll_extract(path: &Path, index: u32) -> Result<image::RgbaImage> {
-    let path_str = path.to_string_lossy();
-    let pcwstr = path_str.as_ref().easy_pcwstr()?;
-
-    let mut large_icon: HICON = HICON::default();
-
-    let extracted = unsafe {
-        ExtractIconExW(
-            pcwstr.as_ref(),
-            index as i32,
-            Some(&mut large_icon),
-            None,
-            1,
-        )
-    };
-
-    if extracted == 0 || large_icon.is_invalid() {
-        eyre::bail!(
-            "Failed to extract icon at index {} using ExtractIconExW",
-            index
-        );
-    }
-
-    // The icon handle needs to be destroyed after use
-    let result = unsafe { hicon_to_rgba(large_icon) };
-
-    // Destroy the icon handle
-    unsafe {
-        _ = windows::Win32::UI::WindowsAndMessaging::DestroyIcon(large_icon);
-    }
-
-    result
-}
@@ -7,11 +7,27 @@ use std::ffi::OsString;
 use std::path::PathBuf;
 
 /// Browse for icons in DLL files.
-#[derive(Args, Debug, Arbitrary, PartialEq)]
+#[derive(Args, Debug, PartialEq)]
 pub struct IconBrowseArgs {
     /// Paths to DLL files to browse for icons. If not provided, defaults to mmres.dll.
     #[arg()]
     pub paths: Vec<PathBuf>,
+
+    /// Open with the image currently on the clipboard in the preview pane.
+    #[arg(long)]
+    pub from_clipboard: bool,
+}
+
+impl<'a> Arbitrary<'a> for IconBrowseArgs {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let mut paths = Vec::<PathBuf>::arbitrary(u)?;
+        // Empty paths are rejected by clap's PathBuf parser
+        paths.retain(|p| !p.as_os_str().is_empty());
+        Ok(IconBrowseArgs {
+            paths,
+            from_clipboard: bool::arbitrary(u)?,
+        })
+    }
 }
 
 impl IconBrowseArgs {
@@ -35,15 +51,20 @@ impl IconBrowseArgs {
         } else {
             self.paths
         };
-        gui::run_icon_browser(paths)
+        gui::run_icon_browser(paths, self.from_clipboard)
     }
 }
 
 impl ToArgs for IconBrowseArgs {
     fn to_args(&self) -> Vec<OsString> {
-        self.paths
-            .iter()
-            .map(|p| p.as_os_str().to_owned())
-            .collect()
+        let mut args = Vec::new();
+        if self.from_clipboard {
+            args.push("--from-clipboard".into());
+        }
+        if !self.paths.is_empty() {
+            args.push("--".into());
+            args.extend(self.paths.iter().map(|p| p.as_os_str().to_owned()));
+        }
+        args
     }
 }
@@ -1,4 +1,5 @@
 use crate::cli::command::icon::browse::IconBrowseArgs;
+use crate::cli::command::icon::sheet::IconSheetArgs;
 use crate::cli::to_args::ToArgs;
 use arbitrary::Arbitrary;
 use clap::Args;
@@ -16,12 +17,14 @@ pub struct IconArgs {
 #[derive(Subcommand, Debug, Arbitrary, PartialEq)]
 pub enum IconCommand {
     Browse(IconBrowseArgs),
+    Sheet(IconSheetArgs),
 }
 
 impl IconArgs {
     pub fn invoke(self) -> Result<()> {
         match self.command {
             IconCommand::Browse(args) => args.invoke(),
+            IconCommand::Sheet(args) => args.invoke(),
         }
     }
 }
@@ -34,6 +37,10 @@ impl ToArgs for IconArgs {
                 args.push("browse".into());
                 args.extend(browse_args.to_args());
             }
+            IconCommand::Sheet(sheet_args) => {
+                args.push("sheet".into());
+                args.extend(sheet_args.to_args());
+            }
         }
         args
     }
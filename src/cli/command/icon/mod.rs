@@ -1,4 +1,5 @@
 pub mod browse;
 mod icon_cli;
+pub mod sheet;
 
 pub use icon_cli::*;
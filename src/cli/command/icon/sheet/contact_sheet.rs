@@ -0,0 +1,101 @@
+use image::Rgba;
+use image::RgbaImage;
+use image::imageops::overlay;
+
+const PADDING: u32 = 4;
+const GLYPH_WIDTH: u32 = 3;
+const GLYPH_HEIGHT: u32 = 5;
+const GLYPH_SCALE: u32 = 2;
+const GLYPH_SPACING: u32 = 1;
+const BACKGROUND: Rgba<u8> = Rgba([255, 255, 255, 255]);
+const LABEL_COLOR: Rgba<u8> = Rgba([64, 64, 64, 255]);
+
+/// 3x5 bitmaps for the digits 0-9, one row per byte using the low three bits.
+const DIGITS: [[u8; 5]; 10] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b111, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b001, 0b001, 0b001],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+];
+
+/// Tiles icons into a grid, labelling each cell with its index. Missing icons leave their cell blank.
+pub fn render_contact_sheet(icons: &[Option<RgbaImage>], size: u32, columns: u32) -> RgbaImage {
+    let columns = columns.clamp(1, icons.len().max(1) as u32);
+    let rows = (icons.len() as u32).div_ceil(columns).max(1);
+
+    let widest_label = label_width(icons.len().saturating_sub(1));
+    let cell_width = size.max(widest_label) + PADDING * 2;
+    let cell_height = size + GLYPH_HEIGHT * GLYPH_SCALE + PADDING * 3;
+
+    let mut sheet = RgbaImage::from_pixel(cell_width * columns, cell_height * rows, BACKGROUND);
+    for (index, icon) in icons.iter().enumerate() {
+        let column = index as u32 % columns;
+        let row = index as u32 / columns;
+        let cell_x = column * cell_width;
+        let cell_y = row * cell_height;
+
+        if let Some(icon) = icon {
+            let x = cell_x + (cell_width - icon.width().min(cell_width)) / 2;
+            overlay(&mut sheet, icon, x as i64, (cell_y + PADDING) as i64);
+        }
+
+        let label_x = cell_x + (cell_width - label_width(index)) / 2;
+        let label_y = cell_y + size + PADDING * 2;
+        draw_number(&mut sheet, index, label_x, label_y);
+    }
+    sheet
+}
+
+fn label_width(number: usize) -> u32 {
+    let digits = number.to_string().len() as u32;
+    digits * GLYPH_WIDTH * GLYPH_SCALE + (digits - 1) * GLYPH_SPACING * GLYPH_SCALE
+}
+
+fn draw_number(image: &mut RgbaImage, number: usize, x: u32, y: u32) {
+    let advance = (GLYPH_WIDTH + GLYPH_SPACING) * GLYPH_SCALE;
+    for (i, digit) in number.to_string().bytes().enumerate() {
+        let glyph = DIGITS[(digit - b'0') as usize];
+        let glyph_x = x + i as u32 * advance;
+        for (glyph_row, bits) in glyph.iter().enumerate() {
+            for glyph_column in 0..GLYPH_WIDTH {
+                if bits & (0b100 >> glyph_column) == 0 {
+                    continue;
+                }
+                for dy in 0..GLYPH_SCALE {
+                    for dx in 0..GLYPH_SCALE {
+                        let px = glyph_x + glyph_column * GLYPH_SCALE + dx;
+                        let py = y + glyph_row as u32 * GLYPH_SCALE + dy;
+                        if px < image.width() && py < image.height() {
+                            image.put_pixel(px, py, LABEL_COLOR);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::render_contact_sheet;
+    use image::Rgba;
+    use image::RgbaImage;
+
+    #[test]
+    fn lays_out_grid() {
+        let icon = RgbaImage::from_pixel(32, 32, Rgba([255, 0, 0, 255]));
+        let icons = vec![Some(icon.clone()), None, Some(icon.clone()), Some(icon)];
+        let sheet = render_contact_sheet(&icons, 32, 3);
+
+        // 3 columns, 2 rows of 40x54 cells
+        assert_eq!(sheet.dimensions(), (120, 108));
+        assert_eq!(sheet.get_pixel(20, 20).0, [255, 0, 0, 255]);
+        assert_eq!(sheet.get_pixel(60, 20).0, [255, 255, 255, 255]);
+    }
+}
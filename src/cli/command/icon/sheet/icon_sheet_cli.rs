@@ -0,0 +1,96 @@
+use super::render_contact_sheet;
+use crate::cli::to_args::ToArgs;
+use crate::hicon::extract_icon_rgba;
+use crate::hicon::get_icon_count;
+use arbitrary::Arbitrary;
+use clap::Args;
+use eyre::Context;
+use eyre::Result;
+use std::ffi::OsString;
+use std::path::PathBuf;
+use tracing::info;
+use tracing::warn;
+
+/// Extract every icon from a file into a labelled grid PNG.
+#[derive(Args, Debug, PartialEq)]
+pub struct IconSheetArgs {
+    /// Exe, dll or ico file to extract icons from.
+    #[arg(long)]
+    pub path: PathBuf,
+
+    /// Where to write the PNG.
+    #[arg(long)]
+    pub output: PathBuf,
+
+    /// Number of icons per row.
+    #[arg(long, default_value_t = 16, value_parser = clap::value_parser!(u32).range(1..))]
+    pub columns: u32,
+
+    /// Icon size in pixels.
+    #[arg(long, default_value_t = 32, value_parser = clap::value_parser!(u32).range(1..=256))]
+    pub size: u32,
+}
+
+impl<'a> Arbitrary<'a> for IconSheetArgs {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let mut path = PathBuf::arbitrary(u)?;
+        if path.as_os_str().is_empty() {
+            path = PathBuf::from("shell32.dll");
+        }
+        let mut output = PathBuf::arbitrary(u)?;
+        if output.as_os_str().is_empty() {
+            output = PathBuf::from("sheet.png");
+        }
+        Ok(IconSheetArgs {
+            path,
+            output,
+            columns: u.int_in_range(1..=u32::MAX)?,
+            size: u.int_in_range(1..=256)?,
+        })
+    }
+}
+
+impl ToArgs for IconSheetArgs {
+    fn to_args(&self) -> Vec<OsString> {
+        let mut path = OsString::from("--path=");
+        path.push(&self.path);
+        let mut output = OsString::from("--output=");
+        output.push(&self.output);
+        vec![
+            path,
+            output,
+            format!("--columns={}", self.columns).into(),
+            format!("--size={}", self.size).into(),
+        ]
+    }
+}
+
+impl IconSheetArgs {
+    pub fn invoke(self) -> Result<()> {
+        let count = get_icon_count(&self.path)?;
+        eyre::ensure!(count > 0, "No icons found in {}", self.path.display());
+
+        let icons = (0..count)
+            .map(
+                |index| match extract_icon_rgba(&self.path, index, self.size) {
+                    Ok(icon) => Some(icon),
+                    Err(e) => {
+                        warn!(index, "Failed to extract icon: {e}");
+                        None
+                    }
+                },
+            )
+            .collect::<Vec<_>>();
+
+        let sheet = render_contact_sheet(&icons, self.size, self.columns);
+        sheet
+            .save(&self.output)
+            .wrap_err_with(|| format!("Failed to write {}", self.output.display()))?;
+        info!(
+            "Wrote {} icons to {}",
+            icons.iter().flatten().count(),
+            self.output.display()
+        );
+        Ok(())
+    }
+}
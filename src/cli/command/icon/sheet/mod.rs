@@ -0,0 +1,5 @@
+mod contact_sheet;
+mod icon_sheet_cli;
+
+pub use contact_sheet::*;
+pub use icon_sheet_cli::*;
@@ -0,0 +1,71 @@
+use crate::audio::TeamyImmDeviceIconPath;
+use crate::cli::to_args::ToArgs;
+use crate::storage::write_atomic;
+use arbitrary::Arbitrary;
+use clap::Args;
+use eyre::Context;
+use eyre::Result;
+use image::ImageFormat;
+use std::ffi::OsString;
+use std::io::Cursor;
+use std::path::PathBuf;
+use tracing::info;
+
+/// Export a device's icon as a PNG.
+#[derive(Args, Debug, PartialEq)]
+pub struct MicIconArgs {
+    /// Device ID, as shown by `mic list`.
+    #[clap(long)]
+    pub id: String,
+
+    /// Where to write the PNG file.
+    #[clap(long)]
+    pub output: PathBuf,
+}
+
+impl<'a> Arbitrary<'a> for MicIconArgs {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let mut output = PathBuf::arbitrary(u)?;
+        if output.as_os_str().is_empty() {
+            output = PathBuf::from("icon.png");
+        }
+        Ok(MicIconArgs {
+            id: String::arbitrary(u)?,
+            output,
+        })
+    }
+}
+
+impl MicIconArgs {
+    pub fn invoke(self) -> Result<()> {
+        let icon_path = TeamyImmDeviceIconPath::for_device_id(&self.id)?;
+        info!("Loading icon from {}", *icon_path);
+        let icon = icon_path
+            .load_device_icon()
+            .wrap_err_with(|| format!("Failed to load icon {}", *icon_path))?;
+
+        let mut png = Vec::new();
+        icon.0
+            .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+            .wrap_err("Failed to encode icon as PNG")?;
+        write_atomic(&self.output, &png)
+            .wrap_err_with(|| format!("Failed to write {}", self.output.display()))?;
+        info!(
+            "Wrote {}x{} icon to {}",
+            icon.width(),
+            icon.height(),
+            self.output.display()
+        );
+        Ok(())
+    }
+}
+
+impl ToArgs for MicIconArgs {
+    fn to_args(&self) -> Vec<OsString> {
+        let mut args = vec![OsString::from(format!("--id={}", self.id))];
+        let mut output = OsString::from("--output=");
+        output.push(&self.output);
+        args.push(output);
+        args
+    }
+}
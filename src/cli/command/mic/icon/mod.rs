@@ -0,0 +1,2 @@
+mod mic_icon_cli;
+pub use mic_icon_cli::*;
@@ -1,18 +1,16 @@
-use crate::audio::list_audio_input_devices;
+use crate::audio::DeviceFlow;
+use crate::audio::list_audio_devices;
+use crate::cli::global_args::GlobalArgs;
+use crate::cli::output_format::OutputFormat;
+use crate::cli::output_format::render;
 use crate::cli::to_args::ToArgs;
 use arbitrary::Arbitrary;
 use clap::Args;
-use clap::ValueEnum;
 use color_eyre::owo_colors::OwoColorize;
 use color_eyre::owo_colors::colors::BrightBlack;
 use color_eyre::owo_colors::colors::Yellow;
 use eyre::Result;
-use facet::Facet;
-use facet_pretty::ColorMode;
-use facet_pretty::PrettyPrinter;
 use std::ffi::OsString;
-use std::io::IsTerminal;
-use std::ops::Deref;
 
 /// List microphones.
 #[derive(Args, Debug, Arbitrary, PartialEq)]
@@ -20,103 +18,53 @@ pub struct MicListArgs {
     /// Output format.
     #[clap(long, value_enum, default_value_t = OutputFormat::Auto)]
     pub output_format: OutputFormat,
-}
 
-#[derive(ValueEnum, Clone, Debug, PartialEq, Eq, Hash, Arbitrary)]
-pub enum OutputFormat {
-    Auto,
-    Text,
-    Facet,
-    Json,
+    /// Also list output devices, which can be recorded in loopback mode to capture system audio.
+    #[clap(long)]
+    pub include_loopback: bool,
 }
-impl MicListArgs {
-    pub fn invoke(mut self) -> Result<()> {
-        let is_terminal = std::io::stdout().is_terminal();
-        if matches!(self.output_format, OutputFormat::Auto) {
-            self.output_format = if is_terminal {
-                OutputFormat::Text
-            } else {
-                OutputFormat::Json
-            };
-        }
 
-        let devices = list_audio_input_devices()?;
-
-        match self.output_format {
-            OutputFormat::Auto => unreachable!(),
-            OutputFormat::Text => {
-                if devices.is_empty() {
-                    println!("{}", "No microphones found.".red());
-                    return Ok(());
-                }
+impl MicListArgs {
+    pub fn invoke(self, global_args: &GlobalArgs) -> Result<()> {
+        let mics = list_audio_devices(self.include_loopback)?;
 
-                for device in devices {
-                    let default_marker = if device.is_default { " (default)" } else { "" };
-                    println!(
-                        "({id}) {name} {default_marker}",
-                        id = device.id.deref().fg::<BrightBlack>(),
-                        name = device.name,
-                        default_marker = default_marker.fg::<Yellow>()
-                    );
-                }
+        // Arrays are emitted directly for easier PowerShell piping
+        render(&mics, self.output_format, global_args, |mics| {
+            if mics.is_empty() {
+                let message = if self.include_loopback {
+                    "No audio devices found."
+                } else {
+                    "No microphones found."
+                };
+                println!("{}", message.red());
+                return Ok(());
             }
-            OutputFormat::Json | OutputFormat::Facet => {
-                // emit json
-                structstruck::strike! {
-                    #[structstruck::each[derive(Facet)]]
-                    struct MicListOutput {
-                        microphones: Vec<struct Mic {
-                            id: String,
-                            name: String,
-                            is_default: bool,
-                        }>,
-                    }
-                }
-                let mics: Vec<Mic> = devices
-                    .into_iter()
-                    .map(|device| Mic {
-                        id: device.id.0,
-                        name: device.name,
-                        is_default: device.is_default,
-                    })
-                    .collect();
-                match (is_terminal, &self.output_format) {
-                    (true, OutputFormat::Facet) => {
-                        let output = MicListOutput { microphones: mics };
-                        let out = PrettyPrinter::new()
-                            .with_colors(ColorMode::Always)
-                            .with_doc_comments(true)
-                            .format(&output);
-                        println!("{}", out);
-                    }
-                    (false, OutputFormat::Facet) => {
-                        let output = MicListOutput { microphones: mics };
-                        let out = PrettyPrinter::new()
-                            .with_colors(ColorMode::Never)
-                            .format(&output);
-                        println!("{}", out);
-                    }
-                    (true, OutputFormat::Json) => {
-                        // Output array directly for easier PowerShell piping
-                        let json = facet_json::to_string_pretty(&mics)?;
-                        println!("{}", json);
-                    }
-                    (false, OutputFormat::Json) => {
-                        // Output array directly for easier PowerShell piping
-                        let json = facet_json::to_string(&mics)?;
-                        println!("{}", json);
-                    }
-                    _ => unreachable!(),
-                }
-            }
-        }
 
-        Ok(())
+            for mic in mics {
+                let default_marker = if mic.is_default { " (default)" } else { "" };
+                let loopback_marker = match mic.flow {
+                    DeviceFlow::Capture => "",
+                    DeviceFlow::Render => " (loopback)",
+                };
+                println!(
+                    "({id}) {name}{loopback_marker} {default_marker}",
+                    id = mic.id.as_str().fg::<BrightBlack>(),
+                    name = mic.name,
+                    loopback_marker = loopback_marker.fg::<BrightBlack>(),
+                    default_marker = default_marker.fg::<Yellow>()
+                );
+            }
+            Ok(())
+        })
     }
 }
 
 impl ToArgs for MicListArgs {
     fn to_args(&self) -> Vec<OsString> {
-        Vec::new()
+        let mut args = self.output_format.to_args("--output-format");
+        if self.include_loopback {
+            args.push("--include-loopback".into());
+        }
+        args
     }
 }
@@ -1,5 +1,8 @@
+use crate::cli::command::mic::icon::MicIconArgs;
 use crate::cli::command::mic::list::MicListArgs;
-// use crate::cli::command::mic::record::MicRecordArgs;
+use crate::cli::command::mic::monitor::MicMonitorArgs;
+use crate::cli::command::mic::record::MicRecordArgs;
+use crate::cli::global_args::GlobalArgs;
 use crate::cli::to_args::ToArgs;
 use arbitrary::Arbitrary;
 use clap::Args;
@@ -17,14 +20,18 @@ pub struct MicArgs {
 #[derive(Subcommand, Debug, Arbitrary, PartialEq)]
 pub enum MicCommand {
     List(MicListArgs),
-    // Record(MicRecordArgs),
+    Record(MicRecordArgs),
+    Monitor(MicMonitorArgs),
+    Icon(MicIconArgs),
 }
 
 impl MicArgs {
-    pub fn invoke(self) -> Result<()> {
+    pub fn invoke(self, global_args: &GlobalArgs) -> Result<()> {
         match self.command {
-            MicCommand::List(args) => args.invoke(),
-            // MicCommand::Record(args) => args.invoke(),
+            MicCommand::List(args) => args.invoke(global_args),
+            MicCommand::Record(args) => args.invoke(),
+            MicCommand::Monitor(args) => args.invoke(),
+            MicCommand::Icon(args) => args.invoke(),
         }
     }
 }
@@ -36,10 +43,19 @@ impl ToArgs for MicArgs {
             MicCommand::List(list_args) => {
                 args.push("list".into());
                 args.extend(list_args.to_args());
-            } // MicCommand::Record(record_args) => {
-              //     args.push("record".into());
-              //     args.extend(record_args.to_args());
-              // }
+            }
+            MicCommand::Record(record_args) => {
+                args.push("record".into());
+                args.extend(record_args.to_args());
+            }
+            MicCommand::Monitor(monitor_args) => {
+                args.push("monitor".into());
+                args.extend(monitor_args.to_args());
+            }
+            MicCommand::Icon(icon_args) => {
+                args.push("icon".into());
+                args.extend(icon_args.to_args());
+            }
         }
         args
     }
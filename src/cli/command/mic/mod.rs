@@ -1,5 +1,7 @@
+pub mod icon;
 pub mod list;
 mod mic_cli;
-// pub mod record;
+pub mod monitor;
+pub mod record;
 
 pub use mic_cli::*;
@@ -0,0 +1,113 @@
+use crate::audio::StopSignal;
+use crate::audio::get_peak_meter;
+use crate::audio::resolve_audio_input_device_id;
+use crate::cli::to_args::ToArgs;
+use crate::console::attach_ctrl_c_callback;
+use arbitrary::Arbitrary;
+use clap::Args;
+use color_eyre::owo_colors::OwoColorize;
+use eyre::Context;
+use eyre::Result;
+use std::ffi::OsString;
+use std::io::IsTerminal;
+use std::io::Write;
+use std::time::Duration;
+
+const METER_WIDTH: usize = 40;
+
+/// Show a live level meter for a microphone until Ctrl-C.
+#[derive(Args, Debug, Arbitrary, PartialEq)]
+pub struct MicMonitorArgs {
+    /// Device ID as shown by `mic list`, `default`, or a unique part of the device name.
+    #[clap(long)]
+    pub id: String,
+
+    /// How often to refresh the meter.
+    #[clap(long, default_value_t = 50)]
+    pub interval_ms: u64,
+}
+
+impl MicMonitorArgs {
+    pub fn invoke(self) -> Result<()> {
+        let stop = StopSignal::new();
+        attach_ctrl_c_callback({
+            let stop = stop.clone();
+            move || stop.stop()
+        })
+        .wrap_err("Failed to attach Ctrl-C handler")?;
+
+        let meter = get_peak_meter(&resolve_audio_input_device_id(&self.id)?)?;
+        let is_terminal = std::io::stdout().is_terminal();
+        let mut stdout = std::io::stdout();
+        while !stop.is_stopped() {
+            let peak = meter.peak()?;
+            if is_terminal {
+                // Redraw in place
+                write!(stdout, "\r{}", render_meter(peak, true))?;
+                stdout.flush()?;
+            } else {
+                writeln!(stdout, "{}", render_meter(peak, false))?;
+            }
+            std::thread::sleep(Duration::from_millis(self.interval_ms));
+        }
+        if is_terminal {
+            writeln!(stdout)?;
+        }
+        Ok(())
+    }
+}
+
+/// A bar like `[#######.................] -18.2 dBFS`, coloured by level when `color` is set.
+fn render_meter(peak: f32, color: bool) -> String {
+    let peak = peak.clamp(0.0, 1.0);
+    let filled = (peak * METER_WIDTH as f32).round() as usize;
+    let bar = "#".repeat(filled);
+    let bar = if !color {
+        bar
+    } else if peak < 0.5 {
+        bar.green().to_string()
+    } else if peak < 0.9 {
+        bar.yellow().to_string()
+    } else {
+        bar.red().to_string()
+    };
+    let decibels = if peak > 0.0 {
+        format!("{:6.1} dBFS", 20.0 * peak.log10())
+    } else {
+        "  -inf dBFS".to_string()
+    };
+    format!(
+        "[{bar}{empty}] {decibels}",
+        empty = ".".repeat(METER_WIDTH - filled)
+    )
+}
+
+impl ToArgs for MicMonitorArgs {
+    fn to_args(&self) -> Vec<OsString> {
+        vec![
+            format!("--id={}", self.id).into(),
+            format!("--interval-ms={}", self.interval_ms).into(),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::METER_WIDTH;
+    use super::render_meter;
+
+    #[test]
+    fn renders_levels() {
+        assert_eq!(
+            render_meter(0.0, false),
+            format!("[{}]   -inf dBFS", ".".repeat(METER_WIDTH))
+        );
+        assert_eq!(
+            render_meter(1.0, false),
+            format!("[{}]    0.0 dBFS", "#".repeat(METER_WIDTH))
+        );
+        assert!(
+            render_meter(0.5, false).starts_with(&format!("[{}.", "#".repeat(METER_WIDTH / 2)))
+        );
+    }
+}
@@ -0,0 +1,2 @@
+mod mic_monitor_cli;
+pub use mic_monitor_cli::*;
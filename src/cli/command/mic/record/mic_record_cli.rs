@@ -0,0 +1,115 @@
+#[cfg(feature = "encoding")]
+use crate::audio::AudioEncoding;
+use crate::audio::RecordingOptions;
+use crate::audio::StopSignal;
+#[cfg(feature = "encoding")]
+use crate::audio::record_to_file_with_encoding;
+#[cfg(not(feature = "encoding"))]
+use crate::audio::record_to_file_with_options;
+use crate::audio::resolve_audio_input_device_id;
+use crate::cli::to_args::ToArgs;
+use crate::console::attach_ctrl_c_callback;
+use arbitrary::Arbitrary;
+use clap::Args;
+#[cfg(feature = "encoding")]
+use clap::ValueEnum;
+use eyre::Context;
+use eyre::Result;
+use std::ffi::OsString;
+use std::path::PathBuf;
+use std::time::Duration;
+use tracing::info;
+
+/// Record from a microphone to a WAV file, or FLAC or MP3 with the `encoding` feature.
+///
+/// Recording runs in this process, not through a service, so there is no call that can hang:
+/// it ends after `--duration-ms` or on Ctrl-C, and either way writes what was captured.
+#[derive(Args, Debug, PartialEq)]
+pub struct MicRecordArgs {
+    /// Device ID as shown by `mic list`, `default`, or a unique part of the device name.
+    #[clap(long)]
+    pub id: String,
+
+    /// How long to record for. Records until Ctrl-C when omitted.
+    #[clap(long)]
+    pub duration_ms: Option<u64>,
+
+    /// Where to write the recording.
+    #[clap(long)]
+    pub output: PathBuf,
+
+    /// File encoding. Guessed from the output extension when omitted, falling back to WAV.
+    #[cfg(feature = "encoding")]
+    #[clap(long)]
+    pub encoding: Option<AudioEncoding>,
+}
+
+impl<'a> Arbitrary<'a> for MicRecordArgs {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let mut output = PathBuf::arbitrary(u)?;
+        if output.as_os_str().is_empty() {
+            output = PathBuf::from("recording.wav");
+        }
+        Ok(MicRecordArgs {
+            id: String::arbitrary(u)?,
+            duration_ms: Option::<u64>::arbitrary(u)?,
+            output,
+            #[cfg(feature = "encoding")]
+            encoding: Option::<AudioEncoding>::arbitrary(u)?,
+        })
+    }
+}
+
+impl MicRecordArgs {
+    pub fn invoke(self) -> Result<()> {
+        let device_id = resolve_audio_input_device_id(&self.id)?;
+
+        // Ctrl-C ends the recording early but still writes what was captured
+        let stop = StopSignal::new();
+        attach_ctrl_c_callback({
+            let stop = stop.clone();
+            move || stop.stop()
+        })
+        .wrap_err("Failed to attach Ctrl-C handler")?;
+
+        match self.duration_ms {
+            Some(duration_ms) => {
+                info!("Recording for {duration_ms} ms, press Ctrl-C to stop early")
+            }
+            None => info!("Recording, press Ctrl-C to stop"),
+        }
+        let options = RecordingOptions {
+            stop,
+            ..Default::default()
+        };
+        let duration = Duration::from_millis(self.duration_ms.unwrap_or(u64::MAX));
+        #[cfg(feature = "encoding")]
+        {
+            let encoding = self
+                .encoding
+                .or_else(|| AudioEncoding::from_path(&self.output))
+                .unwrap_or_default();
+            record_to_file_with_encoding(&device_id, duration, &self.output, &options, encoding)?;
+        }
+        #[cfg(not(feature = "encoding"))]
+        record_to_file_with_options(&device_id, duration, &self.output, &options)?;
+        Ok(())
+    }
+}
+
+impl ToArgs for MicRecordArgs {
+    fn to_args(&self) -> Vec<OsString> {
+        let mut args = vec![OsString::from(format!("--id={}", self.id))];
+        if let Some(duration_ms) = self.duration_ms {
+            args.push(format!("--duration-ms={duration_ms}").into());
+        }
+        let mut output = OsString::from("--output=");
+        output.push(&self.output);
+        args.push(output);
+        #[cfg(feature = "encoding")]
+        if let Some(encoding) = self.encoding.and_then(|e| e.to_possible_value()) {
+            args.push(format!("--encoding={}", encoding.get_name()).into());
+        }
+        args
+    }
+}
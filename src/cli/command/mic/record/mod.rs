@@ -0,0 +1,2 @@
+mod mic_record_cli;
+pub use mic_record_cli::*;
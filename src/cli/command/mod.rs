@@ -1,3 +1,4 @@
+use crate::cli::global_args::GlobalArgs;
 use crate::cli::to_args::ToArgs;
 use arbitrary::Arbitrary;
 use clap::Subcommand;
@@ -9,6 +10,8 @@ pub mod daemon;
 pub mod explorer;
 pub mod icon;
 pub mod mic;
+pub mod paths;
+pub mod version;
 pub mod window;
 
 #[derive(Subcommand, Debug, Arbitrary, PartialEq)]
@@ -18,6 +21,8 @@ pub enum CliCommand {
     Explorer(explorer::ExplorerArgs),
     Icon(icon::IconArgs),
     Mic(mic::MicArgs),
+    Paths(paths::PathsArgs),
+    Version(version::VersionArgs),
     Window(window::WindowArgs),
 }
 
@@ -49,6 +54,16 @@ impl ToArgs for CliCommand {
                 ret.extend(args.to_args());
                 ret
             }
+            CliCommand::Paths(args) => {
+                let mut ret = vec!["paths".into()];
+                ret.extend(args.to_args());
+                ret
+            }
+            CliCommand::Version(args) => {
+                let mut ret = vec!["version".into()];
+                ret.extend(args.to_args());
+                ret
+            }
             CliCommand::Window(args) => {
                 let mut ret = vec!["window".into()];
                 ret.extend(args.to_args());
@@ -59,14 +74,16 @@ impl ToArgs for CliCommand {
 }
 
 impl CliCommand {
-    pub fn invoke(self) -> Result<()> {
+    pub fn invoke(self, global_args: &GlobalArgs) -> Result<()> {
         match self {
-            CliCommand::Clipboard(args) => args.invoke(),
+            CliCommand::Clipboard(args) => args.invoke(global_args),
             CliCommand::Daemon(args) => args.invoke(),
-            CliCommand::Explorer(args) => args.invoke(),
+            CliCommand::Explorer(args) => args.invoke(global_args),
             CliCommand::Icon(args) => args.invoke(),
-            CliCommand::Mic(args) => args.invoke(),
-            CliCommand::Window(args) => args.invoke(),
+            CliCommand::Mic(args) => args.invoke(global_args),
+            CliCommand::Paths(args) => args.invoke(),
+            CliCommand::Version(args) => args.invoke(global_args),
+            CliCommand::Window(args) => args.invoke(global_args),
         }
     }
 }
@@ -0,0 +1,37 @@
+use crate::cli::to_args::ToArgs;
+use crate::paths::APP_CACHE_ENV_VAR;
+use crate::paths::APP_HOME;
+use crate::paths::APP_HOME_ENV_VAR;
+use crate::paths::CACHE_DIR;
+use arbitrary::Arbitrary;
+use clap::Args;
+use eyre::Result;
+use std::ffi::OsString;
+use std::path::Path;
+
+/// Show the resolved application home and cache directories.
+#[derive(Args, Debug, Arbitrary, PartialEq)]
+pub struct PathsArgs {}
+
+impl ToArgs for PathsArgs {
+    fn to_args(&self) -> Vec<OsString> {
+        Vec::new()
+    }
+}
+
+impl PathsArgs {
+    pub fn invoke(self) -> Result<()> {
+        print_path("home", &APP_HOME, APP_HOME_ENV_VAR);
+        print_path("cache", &CACHE_DIR, APP_CACHE_ENV_VAR);
+        Ok(())
+    }
+}
+
+fn print_path(label: &str, path: &Path, env_var: &str) {
+    let source = if std::env::var_os(env_var).is_some() {
+        format!("from {env_var}")
+    } else {
+        "default".to_string()
+    };
+    println!("{label:<6} {} ({source})", path.display());
+}
@@ -0,0 +1,76 @@
+use crate::cli::global_args::GlobalArgs;
+use crate::cli::output_format::OutputFormat;
+use crate::cli::output_format::render;
+use crate::cli::to_args::ToArgs;
+use arbitrary::Arbitrary;
+use clap::Args;
+use eyre::Result;
+use facet::Facet;
+use std::ffi::OsString;
+
+/// Show the version, with `--verbose` adding the build metadata to include in bug reports.
+#[derive(Args, Debug, Arbitrary, PartialEq)]
+pub struct VersionArgs {
+    /// Include the git commit, build time and target.
+    #[arg(long, short)]
+    pub verbose: bool,
+
+    /// Output format.
+    #[arg(long, value_enum, default_value_t = OutputFormat::Auto)]
+    pub output_format: OutputFormat,
+}
+
+/// Build metadata embedded by `build.rs`.
+#[derive(Facet, Debug, Clone, PartialEq, Eq)]
+pub struct VersionInfo {
+    pub version: String,
+    /// Short commit hash, suffixed with `-dirty` if built with uncommitted changes.
+    pub git_hash: String,
+    /// RFC 3339 UTC time of the build.
+    pub build_timestamp: String,
+    pub target: String,
+}
+
+impl VersionInfo {
+    pub fn current() -> Self {
+        let build_timestamp = env!("TEAMY_BUILD_TIMESTAMP")
+            .parse()
+            .ok()
+            .and_then(|secs| chrono::DateTime::from_timestamp(secs, 0))
+            .map(|time| time.to_rfc3339())
+            .unwrap_or_else(|| "unknown".to_string());
+        Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            git_hash: env!("TEAMY_GIT_HASH").to_string(),
+            build_timestamp,
+            target: format!("{}-{}", std::env::consts::ARCH, std::env::consts::OS),
+        }
+    }
+}
+
+impl ToArgs for VersionArgs {
+    fn to_args(&self) -> Vec<OsString> {
+        let mut args = Vec::new();
+        if self.verbose {
+            args.push("--verbose".into());
+        }
+        args.extend(self.output_format.to_args("--output-format"));
+        args
+    }
+}
+
+impl VersionArgs {
+    pub fn invoke(self, global_args: &GlobalArgs) -> Result<()> {
+        let info = VersionInfo::current();
+        let verbose = self.verbose;
+        render(&info, self.output_format, global_args, |info| {
+            println!("{} {}", env!("CARGO_PKG_NAME"), info.version);
+            if verbose {
+                println!("commit  {}", info.git_hash);
+                println!("built   {}", info.build_timestamp);
+                println!("target  {}", info.target);
+            }
+            Ok(())
+        })
+    }
+}
@@ -1,25 +1,71 @@
 use crate::cli::to_args::ToArgs;
-use crate::window::focus_window;
+use crate::window::focus_window_with;
+use crate::window::main_window_for_pid;
 use arbitrary::Arbitrary;
 use clap::Args;
 use eyre::Result;
+use eyre::bail;
 use std::ffi::OsString;
 
-#[derive(Args, Debug, Arbitrary, PartialEq)]
+#[derive(Args, Debug, PartialEq)]
 pub struct WindowFocusArgs {
     /// The HWND of the window to focus
-    pub hwnd: isize,
+    #[clap(required_unless_present = "pid", conflicts_with = "pid")]
+    pub hwnd: Option<isize>,
+
+    /// Focus the main window of this process instead, see `main_window_for_pid`
+    #[clap(long)]
+    pub pid: Option<u32>,
+
+    /// If attaching to the foreground thread isn't enough, briefly zero the system foreground lock timeout.
+    #[clap(long)]
+    pub override_lock_timeout: bool,
+}
+
+impl<'a> Arbitrary<'a> for WindowFocusArgs {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        // Exactly one of the window handle and the process ID is accepted
+        let (hwnd, pid) = if bool::arbitrary(u)? {
+            (None, Some(u32::arbitrary(u)?))
+        } else {
+            (Some(isize::arbitrary(u)?), None)
+        };
+        Ok(WindowFocusArgs {
+            hwnd,
+            pid,
+            override_lock_timeout: bool::arbitrary(u)?,
+        })
+    }
 }
 
 impl ToArgs for WindowFocusArgs {
     fn to_args(&self) -> Vec<OsString> {
-        vec![self.hwnd.to_string().into()]
+        let mut args = Vec::new();
+        if self.override_lock_timeout {
+            args.push("--override-lock-timeout".into());
+        }
+        if let Some(pid) = self.pid {
+            args.push(format!("--pid={pid}").into());
+        }
+        if let Some(hwnd) = self.hwnd {
+            args.push("--".into());
+            args.push(hwnd.to_string().into());
+        }
+        args
     }
 }
 
 impl WindowFocusArgs {
     pub fn invoke(self) -> Result<()> {
-        focus_window(self.hwnd)?;
+        let hwnd = match (self.hwnd, self.pid) {
+            (Some(hwnd), _) => hwnd,
+            (None, Some(pid)) => match main_window_for_pid(pid)? {
+                Some(hwnd) => hwnd.0 as isize,
+                None => bail!("Process {pid} has no visible windows"),
+            },
+            (None, None) => bail!("Either a window handle or --pid is required"),
+        };
+        focus_window_with(hwnd, self.override_lock_timeout)?;
         Ok(())
     }
 }
@@ -1,4 +1,9 @@
+use crate::cli::csv::push_csv_row;
+use crate::cli::global_args::GlobalArgs;
+use crate::cli::output_format::OutputFormat;
+use crate::cli::output_format::render;
 use crate::cli::to_args::ToArgs;
+use crate::window::WindowSummary;
 use crate::window::enumerate_windows;
 use arbitrary::Arbitrary;
 use clap::Args;
@@ -6,20 +11,42 @@ use clap::ValueEnum;
 use eyre::Result;
 use std::ffi::OsString;
 
-#[derive(ValueEnum, Clone, Debug, PartialEq, Arbitrary)]
-pub enum WindowListArgsOutputFormat {
-    Text,
-    #[cfg(feature = "serde")]
-    Json,
-}
-
 #[derive(Args, Debug, Arbitrary, PartialEq)]
 pub struct WindowListArgs {
     #[arg(long)]
     pub all: bool,
 
-    #[arg(long, short, default_value = "text")]
-    pub output: WindowListArgsOutputFormat,
+    #[arg(long, short, value_enum, default_value_t = WindowListFormat::Auto)]
+    pub output: WindowListFormat,
+}
+
+/// The shared [`OutputFormat`]s plus CSV, which only suits flat lists like this one.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq, Hash, Default, Arbitrary)]
+pub enum WindowListFormat {
+    /// Text when stdout is a terminal, JSON when it is piped.
+    #[default]
+    Auto,
+    /// Human-readable table.
+    Text,
+    /// Facet pretty-printed structure.
+    Facet,
+    /// JSON, pretty-printed when stdout is a terminal.
+    Json,
+    /// Comma-separated values with a header row.
+    Csv,
+}
+
+impl WindowListFormat {
+    /// The shared format to render with, or `None` for CSV.
+    fn as_output_format(self) -> Option<OutputFormat> {
+        match self {
+            WindowListFormat::Auto => Some(OutputFormat::Auto),
+            WindowListFormat::Text => Some(OutputFormat::Text),
+            WindowListFormat::Facet => Some(OutputFormat::Facet),
+            WindowListFormat::Json => Some(OutputFormat::Json),
+            WindowListFormat::Csv => None,
+        }
+    }
 }
 
 impl ToArgs for WindowListArgs {
@@ -28,61 +55,83 @@ impl ToArgs for WindowListArgs {
         if self.all {
             args.push("--all".into());
         }
-        args.push("--output".into());
-        args.push(
-            match self.output {
-                WindowListArgsOutputFormat::Text => "text",
-                #[cfg(feature = "serde")]
-                WindowListArgsOutputFormat::Json => "json",
-            }
-            .into(),
-        );
+        if let Some(value) = self.output.to_possible_value() {
+            args.push("--output".into());
+            args.push(value.get_name().into());
+        }
         args
     }
 }
 
 impl WindowListArgs {
-    pub fn invoke(self) -> Result<()> {
+    pub fn invoke(self, global_args: &GlobalArgs) -> Result<()> {
         let mut windows = enumerate_windows()?;
 
         if !self.all {
-            windows.retain(|w| {
-                let width = w.rect.right - w.rect.left;
-                let height = w.rect.bottom - w.rect.top;
-                w.is_visible && width > 0 && height > 0
-            });
-        }
-
-        #[cfg(feature = "serde")]
-        if self.output == WindowListArgsOutputFormat::Json {
-            let json = serde_json::to_string_pretty(&windows)?;
-            println!("{}", json);
-            return Ok(());
+            windows.retain(|w| w.is_visible && !w.rect.is_empty());
         }
 
-        println!(
-            "{:<10} {:<10} {:<10} {:<40} {:<20} Title",
-            "HWND", "PID", "TID", "Class", "Rect"
-        );
-        println!(
-            "{:-<10} {:-<10} {:-<10} {:-<40} {:-<20} {:-<20}",
-            "", "", "", "", "", ""
-        );
-
-        for w in windows {
-            let rect_str = format!(
-                "{},{},{},{}",
-                w.rect.left,
-                w.rect.top,
-                w.rect.right - w.rect.left,
-                w.rect.bottom - w.rect.top
+        let windows: Vec<WindowSummary> = windows.into_iter().map(WindowSummary::from).collect();
+        // The global --json override wins over CSV like it does over every other format
+        let output = match self.output.as_output_format() {
+            Some(output) => output,
+            None if global_args.json_output => OutputFormat::Json,
+            None => {
+                print!("{}", windows_to_csv(&windows));
+                return Ok(());
+            }
+        };
+        render(&windows, output, global_args, |windows| {
+            println!(
+                "{:<10} {:<10} {:<10} {:<40} {:<20} Title",
+                "HWND", "PID", "TID", "Class", "Rect"
             );
             println!(
-                "{:<10?} {:<10} {:<10} {:<40} {:<20} {}",
-                w.hwnd, w.process_id, w.thread_id, w.class_name, rect_str, w.title
+                "{:-<10} {:-<10} {:-<10} {:-<40} {:-<20} {:-<20}",
+                "", "", "", "", "", ""
             );
-        }
 
-        Ok(())
+            for w in windows {
+                let rect_str = format!(
+                    "{},{},{},{}",
+                    w.rect.left,
+                    w.rect.top,
+                    w.rect.width(),
+                    w.rect.height()
+                );
+                println!(
+                    "{:<10} {:<10} {:<10} {:<40} {:<20} {}",
+                    w.hwnd, w.process_id, w.thread_id, w.class_name, rect_str, w.title
+                );
+            }
+            Ok(())
+        })
+    }
+}
+
+/// One row per window with a header; titles and paths are quoted as needed.
+fn windows_to_csv(windows: &[WindowSummary]) -> String {
+    let mut out = String::new();
+    push_csv_row(
+        &mut out,
+        [
+            "hwnd", "pid", "title", "exe_path", "left", "top", "right", "bottom",
+        ],
+    );
+    for w in windows {
+        push_csv_row(
+            &mut out,
+            [
+                w.hwnd.to_string(),
+                w.process_id.to_string(),
+                w.title.clone(),
+                w.exe_path.clone(),
+                w.rect.left.to_string(),
+                w.rect.top.to_string(),
+                w.rect.right.to_string(),
+                w.rect.bottom.to_string(),
+            ],
+        );
     }
+    out
 }
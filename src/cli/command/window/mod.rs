@@ -1,3 +1,4 @@
+use crate::cli::global_args::GlobalArgs;
 use crate::cli::to_args::ToArgs;
 use arbitrary::Arbitrary;
 use clap::Args;
@@ -9,6 +10,7 @@ pub mod focus;
 pub mod list;
 pub mod open;
 pub mod pick;
+pub mod wait;
 
 #[derive(Args, Debug, Arbitrary, PartialEq)]
 pub struct WindowArgs {
@@ -23,8 +25,8 @@ impl ToArgs for WindowArgs {
 }
 
 impl WindowArgs {
-    pub fn invoke(self) -> Result<()> {
-        self.command.invoke()
+    pub fn invoke(self, global_args: &GlobalArgs) -> Result<()> {
+        self.command.invoke(global_args)
     }
 }
 
@@ -34,6 +36,7 @@ pub enum WindowCommand {
     Focus(focus::WindowFocusArgs),
     Open(open::WindowOpenArgs),
     Pick(pick::WindowPickArgs),
+    Wait(wait::WindowWaitArgs),
 }
 
 impl ToArgs for WindowCommand {
@@ -59,17 +62,23 @@ impl ToArgs for WindowCommand {
                 ret.extend(args.to_args());
                 ret
             }
+            WindowCommand::Wait(args) => {
+                let mut ret = vec!["wait".into()];
+                ret.extend(args.to_args());
+                ret
+            }
         }
     }
 }
 
 impl WindowCommand {
-    pub fn invoke(self) -> Result<()> {
+    pub fn invoke(self, global_args: &GlobalArgs) -> Result<()> {
         match self {
-            WindowCommand::List(args) => args.invoke(),
+            WindowCommand::List(args) => args.invoke(global_args),
             WindowCommand::Focus(args) => args.invoke(),
             WindowCommand::Open(args) => args.invoke(),
-            WindowCommand::Pick(args) => args.invoke(),
+            WindowCommand::Pick(args) => args.invoke(global_args),
+            WindowCommand::Wait(args) => args.invoke(global_args),
         }
     }
 }
@@ -19,10 +19,8 @@ pub struct WindowOpenArgs {
 impl ToArgs for WindowOpenArgs {
     fn to_args(&self) -> Vec<OsString> {
         vec![
-            "--daemon-id".into(),
-            self.daemon_id.clone().into(),
-            "--title".into(),
-            self.title.clone().into(),
+            format!("--daemon-id={}", self.daemon_id).into(),
+            format!("--title={}", self.title).into(),
         ]
     }
 }
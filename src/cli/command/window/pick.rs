@@ -1,21 +1,16 @@
+use crate::cli::global_args::GlobalArgs;
+use crate::cli::output_format::OutputFormat;
+use crate::cli::output_format::render;
 use crate::cli::to_args::ToArgs;
-use crate::window::WindowInfo;
+use crate::window::WindowSummary;
 use crate::window::enumerate_windows;
 use arbitrary::Arbitrary;
 use clap::Args;
-use clap::ValueEnum;
 use cloud_terrastodon_user_input::Choice;
 use cloud_terrastodon_user_input::PickerTui;
 use eyre::Result;
 use std::ffi::OsString;
 
-#[derive(ValueEnum, Clone, Debug, PartialEq, Arbitrary)]
-pub enum WindowPickArgsOutputFormat {
-    Text,
-    #[cfg(feature = "serde")]
-    Json,
-}
-
 #[derive(Args, Debug, Arbitrary, PartialEq)]
 pub struct WindowPickArgs {
     #[arg(long)]
@@ -23,8 +18,8 @@ pub struct WindowPickArgs {
     #[arg(long)]
     pub many: bool,
 
-    #[arg(long, short, default_value = "text")]
-    pub output: WindowPickArgsOutputFormat,
+    #[arg(long, short, value_enum, default_value_t = OutputFormat::Auto)]
+    pub output: OutputFormat,
 }
 
 impl ToArgs for WindowPickArgs {
@@ -36,61 +31,42 @@ impl ToArgs for WindowPickArgs {
         if self.many {
             args.push("--many".into());
         }
-        if let Some(format) = self.output.to_possible_value() {
-            args.push("--output".into());
-            args.push(format.get_name().into());
-        }
+        args.extend(self.output.to_args("--output"));
         args
     }
 }
 
 impl WindowPickArgs {
-    pub fn invoke(self) -> Result<()> {
+    pub fn invoke(self, global_args: &GlobalArgs) -> Result<()> {
         let mut windows = enumerate_windows()?;
 
         if !self.all {
-            windows.retain(|w| {
-                let width = w.rect.right - w.rect.left;
-                let height = w.rect.bottom - w.rect.top;
-                w.is_visible && width > 0 && height > 0
-            });
+            windows.retain(|w| w.is_visible && !w.rect.is_empty());
         }
 
-        let picker: PickerTui<WindowInfo> =
+        let picker: PickerTui<WindowSummary> =
             PickerTui::new(windows.into_iter().map(|window| Choice {
                 key: format!("{} - {}", window.title, window.exe_path),
-                value: window,
+                value: WindowSummary::from(window),
             }));
 
         if self.many {
             let selected_windows = picker.pick_many()?;
-            match self.output {
-                WindowPickArgsOutputFormat::Text => {
-                    for window in selected_windows {
-                        println!("{:?}\t{}\t{}", window.hwnd, window.title, window.exe_path);
-                    }
-                }
-                #[cfg(feature = "serde")]
-                WindowPickArgsOutputFormat::Json => {
-                    println!("{}", serde_json::to_string_pretty(&selected_windows)?);
-                }
-            }
+            render(&selected_windows, self.output, global_args, |windows| {
+                print_windows(windows)
+            })
         } else {
             let selected_window = picker.pick_one()?;
-            match self.output {
-                WindowPickArgsOutputFormat::Text => {
-                    println!(
-                        "{:?}\t{}\t{}",
-                        selected_window.hwnd, selected_window.title, selected_window.exe_path
-                    );
-                }
-                #[cfg(feature = "serde")]
-                WindowPickArgsOutputFormat::Json => {
-                    println!("{}", serde_json::to_string_pretty(&selected_window)?);
-                }
-            }
+            render(&selected_window, self.output, global_args, |window| {
+                print_windows(std::slice::from_ref(window))
+            })
         }
+    }
+}
 
-        Ok(())
+fn print_windows(windows: &[WindowSummary]) -> Result<()> {
+    for window in windows {
+        println!("{}\t{}\t{}", window.hwnd, window.title, window.exe_path);
     }
+    Ok(())
 }
@@ -0,0 +1,158 @@
+use crate::cli::global_args::GlobalArgs;
+use crate::cli::output_format::OutputFormat;
+use crate::cli::output_format::render;
+use crate::cli::to_args::ToArgs;
+use crate::window::WindowSummary;
+use crate::window::wait_for_window;
+use arbitrary::Arbitrary;
+use clap::Args;
+use eyre::Context;
+use eyre::Result;
+use eyre::bail;
+use regex::Regex;
+use std::ffi::OsString;
+use std::time::Duration;
+
+/// Block until a matching window exists, then print it.
+///
+/// Exits with an error if `--timeout` elapses first.
+#[derive(Args, Debug, PartialEq)]
+pub struct WindowWaitArgs {
+    /// Regex matched against the window title.
+    #[arg(long)]
+    pub title_regex: Option<String>,
+
+    /// Regex matched against the window class name.
+    #[arg(long)]
+    pub class_regex: Option<String>,
+
+    /// Also match invisible windows.
+    #[arg(long)]
+    pub all: bool,
+
+    /// Give up after this long, e.g. `10s`, `500ms` or `2m`. Waits forever if omitted.
+    #[arg(long, value_parser = parse_duration)]
+    pub timeout: Option<Duration>,
+
+    /// How often to re-enumerate windows.
+    #[arg(long, value_parser = parse_duration, default_value = "250ms")]
+    pub interval: Duration,
+
+    #[arg(long, short, value_enum, default_value_t = OutputFormat::Auto)]
+    pub output: OutputFormat,
+}
+
+impl<'a> Arbitrary<'a> for WindowWaitArgs {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        // Durations round-trip through the command line in whole milliseconds
+        let timeout = Option::<u32>::arbitrary(u)?.map(|ms| Duration::from_millis(ms.into()));
+        Ok(WindowWaitArgs {
+            title_regex: Option::<String>::arbitrary(u)?,
+            class_regex: Option::<String>::arbitrary(u)?,
+            all: bool::arbitrary(u)?,
+            timeout,
+            interval: Duration::from_millis(u32::arbitrary(u)?.into()),
+            output: OutputFormat::arbitrary(u)?,
+        })
+    }
+}
+
+impl ToArgs for WindowWaitArgs {
+    fn to_args(&self) -> Vec<OsString> {
+        let mut args = Vec::new();
+        if let Some(title_regex) = &self.title_regex {
+            args.push(format!("--title-regex={title_regex}").into());
+        }
+        if let Some(class_regex) = &self.class_regex {
+            args.push(format!("--class-regex={class_regex}").into());
+        }
+        if self.all {
+            args.push("--all".into());
+        }
+        if let Some(timeout) = self.timeout {
+            args.push(format!("--timeout={}ms", timeout.as_millis()).into());
+        }
+        args.push(format!("--interval={}ms", self.interval.as_millis()).into());
+        args.extend(self.output.to_args("--output"));
+        args
+    }
+}
+
+impl WindowWaitArgs {
+    pub fn invoke(self, global_args: &GlobalArgs) -> Result<()> {
+        let title_regex = compile(self.title_regex.as_deref(), "--title-regex")?;
+        let class_regex = compile(self.class_regex.as_deref(), "--class-regex")?;
+
+        let window = wait_for_window(
+            |w| {
+                (self.all || w.is_visible)
+                    && title_regex.as_ref().is_none_or(|re| re.is_match(&w.title))
+                    && class_regex
+                        .as_ref()
+                        .is_none_or(|re| re.is_match(&w.class_name))
+            },
+            self.timeout,
+            self.interval,
+        )?;
+        let Some(window) = window else {
+            bail!(
+                "Timed out after {:?} waiting for a matching window",
+                self.timeout.unwrap_or_default()
+            );
+        };
+
+        let window = WindowSummary::from(window);
+        render(&window, self.output, global_args, |w| {
+            println!("{} {} {} {}", w.hwnd, w.process_id, w.class_name, w.title);
+            Ok(())
+        })
+    }
+}
+
+fn compile(pattern: Option<&str>, flag: &str) -> Result<Option<Regex>> {
+    pattern
+        .map(|pattern| {
+            Regex::new(pattern).wrap_err_with(|| format!("Invalid {flag} pattern {pattern:?}"))
+        })
+        .transpose()
+}
+
+/// Parses durations like `10s`, `500ms` or `2m`; a bare number is taken as seconds.
+fn parse_duration(s: &str) -> Result<Duration, String> {
+    let s = s.trim();
+    let split = s
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(s.len());
+    let (number, unit) = s.split_at(split);
+    let number: f64 = number
+        .parse()
+        .map_err(|_| format!("Invalid duration {s:?}, expected something like 10s or 500ms"))?;
+    let seconds = match unit.trim() {
+        "ms" => number / 1000.0,
+        "" | "s" => number,
+        "m" => number * 60.0,
+        "h" => number * 3600.0,
+        other => {
+            return Err(format!(
+                "Unknown duration unit {other:?}, expected ms, s, m or h"
+            ));
+        }
+    };
+    Duration::try_from_secs_f64(seconds).map_err(|e| format!("Invalid duration {s:?}: {e}"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::parse_duration;
+    use std::time::Duration;
+
+    #[test]
+    fn parses_duration_units() {
+        assert_eq!(parse_duration("10s"), Ok(Duration::from_secs(10)));
+        assert_eq!(parse_duration("250ms"), Ok(Duration::from_millis(250)));
+        assert_eq!(parse_duration("2m"), Ok(Duration::from_secs(120)));
+        assert_eq!(parse_duration("1.5"), Ok(Duration::from_millis(1500)));
+        assert!(parse_duration("soon").is_err());
+        assert!(parse_duration("5d").is_err());
+    }
+}
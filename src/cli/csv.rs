@@ -0,0 +1,69 @@
+use std::borrow::Cow;
+
+/// Quotes a CSV field per RFC 4180 when it contains a comma, quote or line break, doubling any quotes.
+///
+/// Fields with leading or trailing whitespace are quoted too, since Excel trims them otherwise.
+/// Text starting with `=`, `+`, `-` or `@`, such as a window title, is prefixed with `'` so spreadsheets
+/// show it instead of evaluating it as a formula; plain numbers like `-8` are left alone.
+/// <https://owasp.org/www-community/attacks/CSV_Injection>
+pub fn csv_field(value: &str) -> Cow<'_, str> {
+    let value: Cow<'_, str> =
+        if value.starts_with(['=', '+', '-', '@']) && value.parse::<f64>().is_err() {
+            Cow::Owned(format!("'{value}"))
+        } else {
+            Cow::Borrowed(value)
+        };
+    let needs_quotes = value.contains([',', '"', '\n', '\r'])
+        || value.starts_with(char::is_whitespace)
+        || value.ends_with(char::is_whitespace);
+    if needs_quotes {
+        Cow::Owned(format!("\"{}\"", value.replace('"', "\"\"")))
+    } else {
+        value
+    }
+}
+
+/// Appends one CRLF-terminated CSV record to `out`.
+pub fn push_csv_row<S: AsRef<str>>(out: &mut String, fields: impl IntoIterator<Item = S>) {
+    for (i, field) in fields.into_iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        out.push_str(&csv_field(field.as_ref()));
+    }
+    out.push_str("\r\n");
+}
+
+#[cfg(test)]
+mod test {
+    use super::csv_field;
+    use super::push_csv_row;
+
+    #[test]
+    fn escapes_special_characters() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a, b"), "\"a, b\"");
+        assert_eq!(csv_field(r#"say "hi""#), r#""say ""hi""""#);
+        assert_eq!(csv_field("line\nbreak"), "\"line\nbreak\"");
+        assert_eq!(csv_field(" padded"), "\" padded\"");
+        assert_eq!(csv_field(""), "");
+    }
+
+    #[test]
+    fn defuses_formulas() {
+        assert_eq!(csv_field("=cmd|' /C calc'!A0"), "'=cmd|' /C calc'!A0");
+        assert_eq!(csv_field("@SUM(A1)"), "'@SUM(A1)");
+        assert_eq!(csv_field("+1+1"), "'+1+1");
+        assert_eq!(csv_field("-2, or so"), "\"'-2, or so\"");
+        assert_eq!(csv_field("-1920"), "-1920");
+        assert_eq!(csv_field("a=b"), "a=b");
+    }
+
+    #[test]
+    fn rows_are_crlf_terminated() {
+        let mut out = String::new();
+        push_csv_row(&mut out, ["hwnd", "title"]);
+        push_csv_row(&mut out, ["1", "Save As, \"draft\""]);
+        assert_eq!(out, "hwnd,title\r\n1,\"Save As, \"\"draft\"\"\"\r\n");
+    }
+}
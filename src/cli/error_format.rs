@@ -0,0 +1,38 @@
+use arbitrary::Arbitrary;
+use clap::ValueEnum;
+use facet::Facet;
+
+/// How a failed command reports its error.
+#[derive(ValueEnum, Clone, Debug, PartialEq, Eq, Default, Arbitrary)]
+pub enum ErrorFormat {
+    /// Pretty color-eyre report.
+    #[default]
+    Text,
+    /// A single line of `{ "error": "...", "chain": [...] }` on stderr.
+    Json,
+}
+
+#[derive(Facet, Debug)]
+struct JsonErrorReport {
+    error: String,
+    chain: Vec<String>,
+}
+
+/// Writes the error and its causes to stderr as a single JSON object.
+///
+/// `chain` holds the underlying causes, outermost first, excluding the top-level `error` message.
+pub fn eprint_json_error(report: &eyre::Report) {
+    let output = JsonErrorReport {
+        error: report.to_string(),
+        chain: report
+            .chain()
+            .skip(1)
+            .map(|cause| cause.to_string())
+            .collect(),
+    };
+    match facet_json::to_string(&output) {
+        Ok(json) => eprintln!("{json}"),
+        // Fall back to the debug report rather than losing the error
+        Err(_) => eprintln!("{report:?}"),
+    }
+}
@@ -1,7 +1,9 @@
+use crate::cli::error_format::ErrorFormat;
 use crate::cli::json_log_behaviour::JsonLogBehaviour;
 use crate::cli::to_args::ToArgs;
 use arbitrary::Arbitrary;
 use clap::Args;
+use clap::ValueEnum;
 use std::ffi::OsString;
 
 #[derive(Args, Default, Arbitrary, PartialEq, Debug)]
@@ -10,17 +12,25 @@ pub struct GlobalArgs {
     #[clap(long, global = true)]
     pub debug: bool,
 
+    /// Force JSON output for every command, overriding any per-command output format.
+    #[clap(long = "json", global = true)]
+    pub json_output: bool,
+
     /// Emit structured JSON logs alongside stderr output.
     /// Optionally specify a filename; if not provided, a timestamped filename will be generated.
     #[clap(
-        long,
+        long = "log-json",
         global = true,
         value_name = "FILE",
         num_args = 0..=1,
         default_missing_value = "",
         require_equals = false
     )]
-    json: Option<String>,
+    log_json: Option<String>,
+
+    /// How to report a failed command; `json` prints a machine-readable object to stderr.
+    #[clap(long, global = true, value_enum, default_value_t = ErrorFormat::Text)]
+    pub error_format: ErrorFormat,
 }
 
 impl GlobalArgs {
@@ -32,9 +42,9 @@ impl GlobalArgs {
         }
     }
 
-    /// Get the JSON log behaviour based on the --json argument.
+    /// Get the JSON log behaviour based on the --log-json argument.
     pub fn json_log_behaviour(&self) -> JsonLogBehaviour {
-        match &self.json {
+        match &self.log_json {
             None => JsonLogBehaviour::None,
             Some(s) if s.is_empty() => JsonLogBehaviour::SomeAutomaticPath,
             Some(s) => JsonLogBehaviour::Some(s.into()),
@@ -48,16 +58,22 @@ impl ToArgs for GlobalArgs {
         if self.debug {
             args.push("--debug".into());
         }
-        match &self.json {
+        if self.json_output {
+            args.push("--json".into());
+        }
+        match &self.log_json {
             None => {}
-            Some(s) if s.is_empty() => {
-                args.push("--json".into());
-            }
+            // Use the attached form so the optional value can't swallow the subcommand
             Some(path) => {
-                args.push("--json".into());
-                args.push(path.into());
+                args.push(format!("--log-json={path}").into());
             }
         }
+        if self.error_format != ErrorFormat::default()
+            && let Some(format) = self.error_format.to_possible_value()
+        {
+            args.push("--error-format".into());
+            args.push(format.get_name().into());
+        }
         args
     }
 }
@@ -1,15 +1,34 @@
 use crate::cli::Cli;
+use crate::cli::error_format::ErrorFormat;
+use crate::cli::error_format::eprint_json_error;
 use crate::cli::tracing::init_tracing;
+use crate::window::DpiAwareness;
+use crate::window::set_process_dpi_aware;
 use clap::Parser;
+use tracing::warn;
 
 pub fn cli_main() -> eyre::Result<()> {
     color_eyre::install()?;
 
     let cli = Cli::parse();
-    init_tracing(
+    let error_format = cli.global_args.error_format.clone();
+    let result = init_tracing(
         cli.global_args.log_level(),
         cli.global_args.json_log_behaviour(),
-    )?;
-    cli.invoke()?;
-    Ok(())
+    )
+    .and_then(|()| {
+        // Before any window exists, so rects and monitor geometry are in physical pixels
+        if let Err(e) = set_process_dpi_aware(DpiAwareness::default()) {
+            warn!("Failed to set DPI awareness: {}", e);
+        }
+        cli.invoke()
+    });
+
+    match (result, error_format) {
+        (Err(report), ErrorFormat::Json) => {
+            eprint_json_error(&report);
+            std::process::exit(1);
+        }
+        (result, _) => result,
+    }
 }
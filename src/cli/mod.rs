@@ -5,9 +5,12 @@ use std::ffi::OsString;
 use to_args::ToArgs;
 
 pub mod command;
+pub mod csv;
+pub mod error_format;
 pub mod global_args;
 pub mod json_log_behaviour;
 pub mod main;
+pub mod output_format;
 pub mod to_args;
 pub mod tracing;
 
@@ -33,6 +36,6 @@ impl ToArgs for Cli {
 
 impl Cli {
     pub fn invoke(self) -> Result<()> {
-        self.command.invoke()
+        self.command.invoke(&self.global_args)
     }
 }
@@ -0,0 +1,127 @@
+use crate::cli::global_args::GlobalArgs;
+use arbitrary::Arbitrary;
+use clap::ValueEnum;
+use eyre::Result;
+use facet::Facet;
+use facet_pretty::ColorMode;
+use facet_pretty::PrettyPrinter;
+use std::ffi::OsString;
+use std::io::IsTerminal;
+
+/// How a command prints its result.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq, Hash, Default, Arbitrary)]
+pub enum OutputFormat {
+    /// Text when stdout is a terminal, JSON when it is piped.
+    #[default]
+    Auto,
+    /// Human-readable text specific to each command.
+    Text,
+    /// Facet pretty-printed structure.
+    Facet,
+    /// JSON, pretty-printed when stdout is a terminal.
+    Json,
+}
+
+impl OutputFormat {
+    /// Applies the global `--json` override and resolves [`OutputFormat::Auto`], never returning `Auto`.
+    pub fn resolve(self, global_args: &GlobalArgs, is_terminal: bool) -> OutputFormat {
+        match self {
+            _ if global_args.json_output => OutputFormat::Json,
+            OutputFormat::Auto if is_terminal => OutputFormat::Text,
+            OutputFormat::Auto => OutputFormat::Json,
+            format => format,
+        }
+    }
+
+    /// The `--flag <name>` pair that reproduces this format.
+    pub fn to_args(&self, flag: &str) -> Vec<OsString> {
+        match self.to_possible_value() {
+            Some(value) => vec![flag.into(), value.get_name().into()],
+            None => Vec::new(),
+        }
+    }
+}
+
+/// Formats `value` as facet or JSON, or returns `None` when the command should print its own text.
+pub fn format_output<'a, T: Facet<'a> + ?Sized>(
+    value: &T,
+    format: OutputFormat,
+    is_terminal: bool,
+) -> Result<Option<String>> {
+    let output = match (format, is_terminal) {
+        (OutputFormat::Auto | OutputFormat::Text, _) => return Ok(None),
+        (OutputFormat::Facet, true) => PrettyPrinter::new()
+            .with_colors(ColorMode::Always)
+            .with_doc_comments(true)
+            .format(value),
+        (OutputFormat::Facet, false) => PrettyPrinter::new()
+            .with_colors(ColorMode::Never)
+            .format(value),
+        (OutputFormat::Json, true) => facet_json::to_string_pretty(value)?,
+        (OutputFormat::Json, false) => facet_json::to_string(value)?,
+    };
+    Ok(Some(output))
+}
+
+/// Prints `value` to stdout in the requested format, calling `text` for [`OutputFormat::Text`].
+///
+/// `format` is resolved with [`OutputFormat::resolve`] first, so commands share the same terminal detection.
+pub fn render<'a, T: Facet<'a> + ?Sized>(
+    value: &T,
+    format: OutputFormat,
+    global_args: &GlobalArgs,
+    text: impl FnOnce(&T) -> Result<()>,
+) -> Result<()> {
+    let is_terminal = std::io::stdout().is_terminal();
+    let format = format.resolve(global_args, is_terminal);
+    match format_output(value, format, is_terminal)? {
+        Some(output) => println!("{output}"),
+        None => text(value)?,
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::OutputFormat;
+    use super::format_output;
+    use crate::cli::global_args::GlobalArgs;
+
+    #[test]
+    fn auto_follows_terminal() {
+        let global_args = GlobalArgs::default();
+        assert_eq!(
+            OutputFormat::Auto.resolve(&global_args, true),
+            OutputFormat::Text
+        );
+        assert_eq!(
+            OutputFormat::Auto.resolve(&global_args, false),
+            OutputFormat::Json
+        );
+        assert_eq!(
+            OutputFormat::Facet.resolve(&global_args, false),
+            OutputFormat::Facet
+        );
+    }
+
+    #[test]
+    fn global_json_wins() {
+        let mut global_args = GlobalArgs::default();
+        global_args.json_output = true;
+        assert_eq!(
+            OutputFormat::Text.resolve(&global_args, true),
+            OutputFormat::Json
+        );
+    }
+
+    #[test]
+    fn json_is_compact_when_piped() -> eyre::Result<()> {
+        let value = vec![1u32, 2, 3];
+        assert_eq!(
+            format_output(&value, OutputFormat::Json, false)?.as_deref(),
+            Some("[1,2,3]")
+        );
+        assert_eq!(format_output(&value, OutputFormat::Text, false)?, None);
+        Ok(())
+    }
+}
@@ -1,4 +1,5 @@
 use crate::cli::json_log_behaviour::JsonLogBehaviour;
+use crate::console::try_enable_ansi_support;
 use chrono::Local;
 use eyre::Result;
 use std::fs::File;
@@ -22,6 +23,7 @@ pub fn init_tracing(level: impl Into<Directive>, json_behaviour: JsonLogBehaviou
         .with_file(cfg!(debug_assertions))
         .with_target(true)
         .with_line_number(cfg!(debug_assertions))
+        .with_ansi(try_enable_ansi_support())
         .with_writer(std::io::stderr)
         .pretty()
         .without_time();
@@ -1,5 +1,6 @@
 use eyre::Context;
 use eyre::Result;
+use windows::Win32::Foundation::HWND;
 use windows::Win32::System::DataExchange::CloseClipboard;
 use windows::Win32::System::DataExchange::OpenClipboard;
 
@@ -10,6 +11,12 @@ impl ClipboardGuard {
         unsafe { OpenClipboard(None) }.wrap_err("Failed to open clipboard")?;
         Ok(Self)
     }
+
+    /// Opens the clipboard on behalf of `hwnd`, which becomes the clipboard owner once it is emptied.
+    pub fn open_for(hwnd: HWND) -> Result<Self> {
+        unsafe { OpenClipboard(Some(hwnd)) }.wrap_err("Failed to open clipboard")?;
+        Ok(Self)
+    }
 }
 
 impl Drop for ClipboardGuard {
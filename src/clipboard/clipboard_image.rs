@@ -0,0 +1,353 @@
+use super::clipboard_guard::ClipboardGuard;
+use super::delayed_render::clipboard_global_from_bytes;
+use crate::error::last_error_context;
+use eyre::Context;
+use eyre::Result;
+use eyre::bail;
+use image::RgbaImage;
+use image::codecs::bmp::BmpDecoder;
+use std::io::Cursor;
+use std::ptr;
+use windows::Win32::Foundation::GlobalFree;
+use windows::Win32::Foundation::HGLOBAL;
+use windows::Win32::Graphics::Gdi::BI_BITFIELDS;
+use windows::Win32::Graphics::Gdi::BI_JPEG;
+use windows::Win32::Graphics::Gdi::BI_PNG;
+use windows::Win32::Graphics::Gdi::BI_RGB;
+use windows::Win32::Graphics::Gdi::BITMAPINFOHEADER;
+use windows::Win32::Graphics::Gdi::BITMAPV5HEADER;
+use windows::Win32::Graphics::Gdi::LCS_GM_IMAGES;
+use windows::Win32::System::DataExchange::EmptyClipboard;
+use windows::Win32::System::DataExchange::GetClipboardData;
+use windows::Win32::System::DataExchange::IsClipboardFormatAvailable;
+use windows::Win32::System::DataExchange::SetClipboardData;
+use windows::Win32::System::Memory::GlobalLock;
+use windows::Win32::System::Memory::GlobalSize;
+use windows::Win32::System::Memory::GlobalUnlock;
+use windows::Win32::System::Ole::CF_DIB;
+use windows::Win32::System::Ole::CF_DIBV5;
+
+/// Reads the image currently on the clipboard.
+///
+/// Prefers `CF_DIBV5`, which can carry an alpha mask, and falls back to `CF_DIB`.
+/// Windows synthesizes whichever of the two the source application didn't provide.
+pub fn get_clipboard_image() -> Result<RgbaImage> {
+    let _guard = ClipboardGuard::open().wrap_err("Failed to open clipboard")?;
+    read_open_clipboard_image()?.ok_or_else(|| eyre::eyre!("No image data on the clipboard"))
+}
+
+/// Like [`get_clipboard_image`], for callers that already hold a [`ClipboardGuard`].
+///
+/// Returns `None` when there is no bitmap on the clipboard.
+pub fn read_open_clipboard_image() -> Result<Option<RgbaImage>> {
+    let Some(handle) = open_clipboard_dib()? else {
+        return Ok(None);
+    };
+    let dib = read_global_bytes(handle)?;
+    decode_dib(dib).map(Some)
+}
+
+/// Width and height of the bitmap on the clipboard, read from its header without decoding the pixels.
+///
+/// Like [`read_open_clipboard_image`], the caller must already hold a [`ClipboardGuard`].
+pub fn read_open_clipboard_image_size() -> Result<Option<(u32, u32)>> {
+    let Some(handle) = open_clipboard_dib()? else {
+        return Ok(None);
+    };
+    let lock = unsafe { GlobalLock(handle) };
+    if lock.is_null() {
+        bail!("Failed to lock clipboard data: {}", last_error_context())
+    }
+
+    let size = unsafe { GlobalSize(handle) };
+    let header_len = size.min(size_of::<BITMAPINFOHEADER>());
+    let header = unsafe { std::slice::from_raw_parts(lock as *const u8, header_len) }.to_vec();
+    let _ = unsafe { GlobalUnlock(handle) };
+    dib_dimensions(&header).map(Some)
+}
+
+/// Handle to the clipboard's `CF_DIBV5` data, or its `CF_DIB` data when there is no V5 bitmap.
+fn open_clipboard_dib() -> Result<Option<HGLOBAL>> {
+    let format = if unsafe { IsClipboardFormatAvailable(CF_DIBV5.0 as u32).is_ok() } {
+        CF_DIBV5
+    } else if unsafe { IsClipboardFormatAvailable(CF_DIB.0 as u32).is_ok() } {
+        CF_DIB
+    } else {
+        return Ok(None);
+    };
+
+    let handle = unsafe { GetClipboardData(format.0 as u32)? };
+    if handle.is_invalid() {
+        bail!("Clipboard image handle was invalid");
+    }
+    Ok(Some(HGLOBAL(handle.0)))
+}
+
+/// Reads `biWidth` and `biHeight` from the start of a packed DIB.
+///
+/// A negative height marks a top-down bitmap, so only its magnitude is the row count.
+fn dib_dimensions(header: &[u8]) -> Result<(u32, u32)> {
+    if header.len() < size_of::<BITMAPINFOHEADER>() {
+        bail!(
+            "Clipboard bitmap is only {} bytes, too short for a header",
+            header.len()
+        );
+    }
+    let width = i32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+    let height = i32::from_le_bytes([header[8], header[9], header[10], header[11]]);
+    if width < 0 {
+        bail!("Clipboard bitmap has a negative width of {width}");
+    }
+    Ok((width.unsigned_abs(), height.unsigned_abs()))
+}
+
+/// Decodes a packed DIB: a `BITMAPINFOHEADER` (or a V4/V5 extension of it) followed by the pixels.
+///
+/// Handles bottom-up and top-down rows, 24-bit BGR and 32-bit BGRA among the other bitmap layouts.
+/// DIBs wrapping compressed JPEG or PNG data are rejected.
+fn decode_dib(dib: Vec<u8>) -> Result<RgbaImage> {
+    if dib.len() < size_of::<BITMAPINFOHEADER>() {
+        bail!(
+            "Clipboard bitmap is only {} bytes, too short for a header",
+            dib.len()
+        );
+    }
+    // biCompression sits after biSize, biWidth, biHeight, biPlanes and biBitCount
+    let compression = u32::from_le_bytes([dib[16], dib[17], dib[18], dib[19]]);
+    match compression {
+        x if x == BI_JPEG.0 => {
+            bail!("Clipboard bitmap holds JPEG-compressed data, which is not supported")
+        }
+        x if x == BI_PNG.0 => {
+            bail!("Clipboard bitmap holds PNG-compressed data, which is not supported")
+        }
+        _ => {}
+    }
+
+    let decoder = BmpDecoder::new_without_file_header(Cursor::new(dib))
+        .wrap_err("Failed to parse clipboard bitmap header")?;
+    let image =
+        image::DynamicImage::from_decoder(decoder).wrap_err("Failed to decode clipboard bitmap")?;
+    Ok(image.to_rgba8())
+}
+
+/// Puts `image` on the clipboard as both `CF_DIBV5` and `CF_DIB`.
+///
+/// `CF_DIBV5` carries straight alpha through an explicit alpha mask, which is what
+/// [`get_clipboard_image`] and most alpha-aware applications read.
+/// `CF_DIB` has no defined alpha, so it gets premultiplied pixels: applications that ignore
+/// the fourth byte then see transparent areas as black rather than as stray colors.
+pub fn set_clipboard_image(image: &RgbaImage) -> Result<()> {
+    if image.width() == 0 || image.height() == 0 {
+        bail!("Cannot put an empty image on the clipboard");
+    }
+    let dib_v5 = dib_v5_bytes(image)?;
+    let dib = dib_bytes(image)?;
+
+    let _guard = ClipboardGuard::open().wrap_err("Failed to open clipboard")?;
+    unsafe { EmptyClipboard().wrap_err("Failed to empty clipboard")? };
+    set_global_bytes(CF_DIBV5.0 as u32, &dib_v5)?;
+    set_global_bytes(CF_DIB.0 as u32, &dib)?;
+    Ok(())
+}
+
+/// A packed `CF_DIBV5`: a `BITMAPV5HEADER` followed by bottom-up rows of straight-alpha BGRA.
+fn dib_v5_bytes(image: &RgbaImage) -> Result<Vec<u8>> {
+    let pixels = bottom_up_bgra(image, false);
+    let header = BITMAPV5HEADER {
+        bV5Size: size_of::<BITMAPV5HEADER>() as u32,
+        bV5Width: i32::try_from(image.width()).wrap_err("Image is too wide")?,
+        bV5Height: i32::try_from(image.height()).wrap_err("Image is too tall")?,
+        bV5Planes: 1,
+        bV5BitCount: 32,
+        bV5Compression: BI_BITFIELDS,
+        bV5SizeImage: u32::try_from(pixels.len()).wrap_err("Image is too large")?,
+        bV5RedMask: 0x00FF_0000,
+        bV5GreenMask: 0x0000_FF00,
+        bV5BlueMask: 0x0000_00FF,
+        bV5AlphaMask: 0xFF00_0000,
+        // LCS_sRGB
+        bV5CSType: u32::from_be_bytes(*b"sRGB"),
+        bV5Intent: LCS_GM_IMAGES as u32,
+        ..Default::default()
+    };
+    Ok(with_header(&header, &pixels))
+}
+
+/// A packed `CF_DIB`: a `BITMAPINFOHEADER` followed by bottom-up rows of premultiplied BGRA.
+fn dib_bytes(image: &RgbaImage) -> Result<Vec<u8>> {
+    let pixels = bottom_up_bgra(image, true);
+    let header = BITMAPINFOHEADER {
+        biSize: size_of::<BITMAPINFOHEADER>() as u32,
+        biWidth: i32::try_from(image.width()).wrap_err("Image is too wide")?,
+        biHeight: i32::try_from(image.height()).wrap_err("Image is too tall")?,
+        biPlanes: 1,
+        biBitCount: 32,
+        biCompression: BI_RGB.0,
+        biSizeImage: u32::try_from(pixels.len()).wrap_err("Image is too large")?,
+        ..Default::default()
+    };
+    Ok(with_header(&header, &pixels))
+}
+
+/// Rows from the bottom of the image up, as 32-bit BGRA.
+///
+/// DIB rows are padded to a multiple of 4 bytes, which 32-bit pixels always are.
+fn bottom_up_bgra(image: &RgbaImage, premultiply: bool) -> Vec<u8> {
+    let mut pixels = Vec::with_capacity(image.as_raw().len());
+    for row in image.rows().rev() {
+        for pixel in row {
+            let [r, g, b, a] = pixel.0;
+            if premultiply {
+                let scale = |c: u8| ((c as u16 * a as u16 + 127) / 255) as u8;
+                pixels.extend_from_slice(&[scale(b), scale(g), scale(r), a]);
+            } else {
+                pixels.extend_from_slice(&[b, g, r, a]);
+            }
+        }
+    }
+    pixels
+}
+
+fn with_header<T>(header: &T, pixels: &[u8]) -> Vec<u8> {
+    // SAFETY: the header structs are plain data without padding
+    let header =
+        unsafe { std::slice::from_raw_parts(ptr::from_ref(header).cast::<u8>(), size_of::<T>()) };
+    let mut bytes = Vec::with_capacity(header.len() + pixels.len());
+    bytes.extend_from_slice(header);
+    bytes.extend_from_slice(pixels);
+    bytes
+}
+
+/// Copies `bytes` into a movable global and hands it to the open clipboard as `format`.
+fn set_global_bytes(format: u32, bytes: &[u8]) -> Result<()> {
+    let handle = clipboard_global_from_bytes(bytes)?;
+    // The clipboard owns the global once this succeeds
+    if let Err(e) = unsafe { SetClipboardData(format, Some(handle)) } {
+        let _ = unsafe { GlobalFree(Some(HGLOBAL(handle.0))) };
+        return Err(e).wrap_err("Failed to set clipboard data");
+    }
+    Ok(())
+}
+
+fn read_global_bytes(handle: HGLOBAL) -> Result<Vec<u8>> {
+    let lock = unsafe { GlobalLock(handle) };
+    if lock.is_null() {
+        bail!("Failed to lock clipboard data: {}", last_error_context())
+    }
+
+    let size = unsafe { GlobalSize(handle) };
+    let bytes = unsafe { std::slice::from_raw_parts(lock as *const u8, size) }.to_vec();
+    let _ = unsafe { GlobalUnlock(handle) };
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod test {
+    use super::decode_dib;
+    use super::dib_bytes;
+    use super::dib_dimensions;
+    use super::dib_v5_bytes;
+    use super::with_header;
+    use image::Rgba;
+    use image::RgbaImage;
+    use image::codecs::bmp::BmpDecoder;
+    use std::io::Cursor;
+    use windows::Win32::Graphics::Gdi::BI_PNG;
+    use windows::Win32::Graphics::Gdi::BI_RGB;
+    use windows::Win32::Graphics::Gdi::BITMAPINFOHEADER;
+
+    fn header(width: i32, height: i32, bit_count: u16, compression: u32) -> BITMAPINFOHEADER {
+        BITMAPINFOHEADER {
+            biSize: size_of::<BITMAPINFOHEADER>() as u32,
+            biWidth: width,
+            biHeight: height,
+            biPlanes: 1,
+            biBitCount: bit_count,
+            biCompression: compression,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn decodes_24_bit_bottom_up_with_padded_rows() -> eyre::Result<()> {
+        // 3 BGR pixels make 9 bytes, padded to 12
+        let pixels = [
+            // Bottom row: blue, green, red
+            255, 0, 0, 0, 255, 0, 0, 0, 255, 0, 0, 0, //
+            // Top row: white, black, gray
+            255, 255, 255, 0, 0, 0, 128, 128, 128, 0, 0, 0,
+        ];
+        let image = decode_dib(with_header(&header(3, 2, 24, BI_RGB.0), &pixels))?;
+        assert_eq!(image.dimensions(), (3, 2));
+        assert_eq!(image.get_pixel(0, 0), &Rgba([255, 255, 255, 255]));
+        assert_eq!(image.get_pixel(2, 0), &Rgba([128, 128, 128, 255]));
+        assert_eq!(image.get_pixel(0, 1), &Rgba([0, 0, 255, 255]));
+        assert_eq!(image.get_pixel(2, 1), &Rgba([255, 0, 0, 255]));
+        Ok(())
+    }
+
+    #[test]
+    fn decodes_32_bit_top_down() -> eyre::Result<()> {
+        let pixels = [
+            // Top row: red, then green
+            0, 0, 255, 255, 0, 255, 0, 255, //
+            // Bottom row: blue, then white
+            255, 0, 0, 255, 255, 255, 255, 255,
+        ];
+        let image = decode_dib(with_header(&header(2, -2, 32, BI_RGB.0), &pixels))?;
+        assert_eq!(image.get_pixel(0, 0), &Rgba([255, 0, 0, 255]));
+        assert_eq!(image.get_pixel(1, 0), &Rgba([0, 255, 0, 255]));
+        assert_eq!(image.get_pixel(0, 1), &Rgba([0, 0, 255, 255]));
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_png_compressed_dib() {
+        let dib = with_header(&header(1, 1, 0, BI_PNG.0), b"\x89PNG");
+        let error = decode_dib(dib).unwrap_err();
+        assert!(error.to_string().contains("PNG-compressed"));
+    }
+
+    #[test]
+    fn reads_dimensions_from_header_alone() -> eyre::Result<()> {
+        let bottom_up = with_header(&header(3, 2, 24, BI_RGB.0), &[]);
+        assert_eq!(dib_dimensions(&bottom_up)?, (3, 2));
+        let top_down = with_header(&header(2, -5, 32, BI_RGB.0), &[]);
+        assert_eq!(dib_dimensions(&top_down)?, (2, 5));
+        assert!(dib_dimensions(&top_down[..8]).is_err());
+        Ok(())
+    }
+
+    fn sample_image() -> RgbaImage {
+        // 3 pixels wide so an unpadded 24-bit row would not be 4-byte aligned
+        RgbaImage::from_fn(3, 2, |x, y| match (x, y) {
+            (0, 0) => Rgba([255, 0, 0, 255]),
+            (1, 0) => Rgba([0, 255, 0, 128]),
+            (2, 0) => Rgba([0, 0, 255, 0]),
+            _ => Rgba([10, 20, 30, 255]),
+        })
+    }
+
+    #[test]
+    fn dib_v5_round_trips_straight_alpha() -> eyre::Result<()> {
+        let image = sample_image();
+        let decoder = BmpDecoder::new_without_file_header(Cursor::new(dib_v5_bytes(&image)?))?;
+        let decoded = image::DynamicImage::from_decoder(decoder)?.to_rgba8();
+        assert_eq!(decoded, image);
+        Ok(())
+    }
+
+    #[test]
+    fn dib_is_bottom_up_and_premultiplied() -> eyre::Result<()> {
+        let bytes = dib_bytes(&sample_image())?;
+        let pixels = &bytes[40..];
+        assert_eq!(pixels.len(), 3 * 2 * 4);
+        // The last row holds the top of the image
+        assert_eq!(&pixels[12..16], &[0, 0, 255, 255]);
+        assert_eq!(&pixels[16..20], &[0, 128, 0, 128]);
+        assert_eq!(&pixels[20..24], &[0, 0, 0, 0]);
+        assert_eq!(&pixels[..4], &[30, 20, 10, 255]);
+        Ok(())
+    }
+}
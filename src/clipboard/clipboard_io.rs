@@ -1,4 +1,6 @@
 use super::clipboard_guard::ClipboardGuard;
+use super::clipboard_text_encoding::decode_clipboard_text;
+use crate::error::last_error_context;
 use eyre::Context;
 use eyre::Result;
 use eyre::bail;
@@ -32,7 +34,7 @@ pub fn read_clipboard() -> Result<String> {
         if handle.is_invalid() {
             bail!("ANSI clipboard handle was invalid");
         }
-        read_clipboard_ascii(HGLOBAL(handle.0))
+        decode_clipboard_text(HGLOBAL(handle.0), CF_TEXT.0 as u32)
     } else {
         bail!("No text data on the clipboard");
     }
@@ -49,12 +51,15 @@ pub fn write_clipboard(value: impl AsRef<str>) -> Result<()> {
     let handle = unsafe { GlobalAlloc(GMEM_MOVEABLE, size) }
         .wrap_err("Failed to allocate clipboard buffer")?;
     if handle.is_invalid() {
-        bail!("Failed to allocate clipboard buffer");
+        bail!(
+            "Failed to allocate clipboard buffer: {}",
+            last_error_context()
+        );
     }
 
     let lock = unsafe { GlobalLock(handle) };
     if lock.is_null() {
-        bail!("Failed to lock clipboard buffer");
+        bail!("Failed to lock clipboard buffer: {}", last_error_context());
     }
 
     unsafe { ptr::copy_nonoverlapping(slice.as_ptr(), lock as *mut u16, slice.len()) };
@@ -66,23 +71,10 @@ pub fn write_clipboard(value: impl AsRef<str>) -> Result<()> {
     Ok(())
 }
 
-fn read_clipboard_ascii(handle: HGLOBAL) -> Result<String> {
-    let lock = unsafe { GlobalLock(handle) };
-    if lock.is_null() {
-        bail!("Failed to lock clipboard data")
-    }
-
-    let data_ptr = lock as *const u8;
-    let c_str = unsafe { std::ffi::CStr::from_ptr(data_ptr as *const i8) };
-    let result = String::from_utf8_lossy(c_str.to_bytes()).to_string();
-    let _ = unsafe { GlobalUnlock(handle) };
-    Ok(result)
-}
-
 fn read_clipboard_unicode(handle: HGLOBAL) -> Result<String> {
     let lock = unsafe { GlobalLock(handle) };
     if lock.is_null() {
-        bail!("Failed to lock clipboard data")
+        bail!("Failed to lock clipboard data: {}", last_error_context())
     }
 
     let data_ptr = lock as *const u16;
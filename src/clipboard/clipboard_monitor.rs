@@ -0,0 +1,225 @@
+use crate::event_loop::run_message_loop;
+use crate::window::WindowBuilder;
+use crossbeam_channel::Receiver;
+use crossbeam_channel::Sender;
+use crossbeam_channel::unbounded;
+use eyre::Context;
+use eyre::Result;
+use eyre::bail;
+use std::sync::mpsc::sync_channel;
+use std::thread;
+use std::thread::JoinHandle;
+use tracing::debug;
+use tracing::warn;
+use windows::Win32::Foundation::ERROR_INSUFFICIENT_BUFFER;
+use windows::Win32::Foundation::HWND;
+use windows::Win32::Foundation::LPARAM;
+use windows::Win32::Foundation::LRESULT;
+use windows::Win32::Foundation::WPARAM;
+use windows::Win32::System::DataExchange::AddClipboardFormatListener;
+use windows::Win32::System::DataExchange::GetClipboardSequenceNumber;
+use windows::Win32::System::DataExchange::GetUpdatedClipboardFormats;
+use windows::Win32::System::DataExchange::RemoveClipboardFormatListener;
+use windows::Win32::UI::Shell::DefSubclassProc;
+use windows::Win32::UI::Shell::RemoveWindowSubclass;
+use windows::Win32::UI::Shell::SetWindowSubclass;
+use windows::Win32::UI::WindowsAndMessaging::DestroyWindow;
+use windows::Win32::UI::WindowsAndMessaging::PostMessageW;
+use windows::Win32::UI::WindowsAndMessaging::PostQuitMessage;
+use windows::Win32::UI::WindowsAndMessaging::WM_CLIPBOARDUPDATE;
+use windows::Win32::UI::WindowsAndMessaging::WM_CLOSE;
+use windows::Win32::UI::WindowsAndMessaging::WM_DESTROY;
+use windows::Win32::UI::WindowsAndMessaging::WM_NCDESTROY;
+
+const CLIPBOARD_MONITOR_SUBCLASS_ID: usize = 0x636c_6d6e; // "clmn"
+
+/// A clipboard change reported by [`ClipboardMonitor`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClipboardEvent {
+    /// `GetClipboardSequenceNumber` after the change.
+    pub sequence_number: u32,
+    /// Formats now on the clipboard, in the order the owner offered them.
+    pub formats: Vec<u32>,
+}
+
+/// Delivers a [`ClipboardEvent`] every time the clipboard contents change.
+///
+/// A message-only window registered with `AddClipboardFormatListener` pumps messages on a dedicated thread.
+/// Dropping the monitor destroys the window, which unregisters the listener, and waits for the thread to finish.
+/// <https://learn.microsoft.com/en-us/windows/win32/dataxchg/using-the-clipboard#monitoring-clipboard-contents>
+pub struct ClipboardMonitor {
+    // HWND isn't Send, keep the raw value for posting WM_CLOSE from whichever thread drops the monitor
+    hwnd: isize,
+    thread: Option<JoinHandle<Result<()>>>,
+    receiver: Receiver<ClipboardEvent>,
+}
+
+impl ClipboardMonitor {
+    pub fn new() -> Result<Self> {
+        let (sender, receiver) = unbounded();
+        let (ready_tx, ready_rx) = sync_channel(1);
+        let thread = thread::Builder::new()
+            .name("clipboard-monitor".into())
+            .spawn(move || {
+                let hwnd = match create_listener_window(sender) {
+                    Ok(hwnd) => hwnd,
+                    Err(e) => {
+                        let _ = ready_tx.send(Err(e));
+                        return Ok(());
+                    }
+                };
+                let _ = ready_tx.send(Ok(hwnd.0 as isize));
+                run_message_loop(None)
+            })
+            .wrap_err("Failed to spawn clipboard-monitor thread")?;
+
+        let hwnd = ready_rx
+            .recv()
+            .wrap_err("Clipboard monitor thread exited during startup")??;
+        Ok(Self {
+            hwnd,
+            thread: Some(thread),
+            receiver,
+        })
+    }
+
+    /// Receives events as they arrive; clone it to wait on events from another thread.
+    pub fn receiver(&self) -> &Receiver<ClipboardEvent> {
+        &self.receiver
+    }
+}
+
+impl Drop for ClipboardMonitor {
+    fn drop(&mut self) {
+        let hwnd = HWND(self.hwnd as _);
+        if let Err(e) = unsafe { PostMessageW(Some(hwnd), WM_CLOSE, WPARAM(0), LPARAM(0)) } {
+            warn!("Failed to close clipboard monitor window: {}", e);
+            return;
+        }
+        if let Some(thread) = self.thread.take() {
+            match thread.join() {
+                Ok(Ok(())) => debug!("Clipboard monitor stopped"),
+                Ok(Err(e)) => warn!("Clipboard monitor message loop failed: {:?}", e),
+                Err(_) => warn!("Clipboard monitor thread panicked"),
+            }
+        }
+    }
+}
+
+/// Creates the message-only window on the current thread and starts listening for clipboard updates.
+fn create_listener_window(sender: Sender<ClipboardEvent>) -> Result<HWND> {
+    let hwnd = WindowBuilder::new("TeamyClipboardMonitor")
+        .title("Clipboard Monitor")
+        .message_only(true)
+        .build()?;
+
+    let state = Box::into_raw(Box::new(sender));
+    let subclassed = unsafe {
+        SetWindowSubclass(
+            hwnd,
+            Some(clipboard_monitor_subclass_proc),
+            CLIPBOARD_MONITOR_SUBCLASS_ID,
+            state as usize,
+        )
+    }
+    .as_bool();
+    if !subclassed {
+        drop(unsafe { Box::from_raw(state) });
+        let _ = unsafe { DestroyWindow(hwnd) };
+        bail!("Failed to subclass clipboard monitor window");
+    }
+
+    if let Err(e) = unsafe { AddClipboardFormatListener(hwnd) } {
+        // Frees the sender through WM_NCDESTROY
+        let _ = unsafe { DestroyWindow(hwnd) };
+        return Err(e).wrap_err("Failed to add clipboard format listener");
+    }
+    debug!(?hwnd, "Listening for clipboard updates");
+    Ok(hwnd)
+}
+
+/// Formats on the clipboard, read without opening it.
+fn updated_clipboard_formats() -> Vec<u32> {
+    let mut formats = vec![0u32; 32];
+    loop {
+        let mut count = 0;
+        match unsafe { GetUpdatedClipboardFormats(&mut formats, &mut count) } {
+            Ok(()) => {
+                formats.truncate(count as usize);
+                return formats;
+            }
+            Err(e) if e.code() == ERROR_INSUFFICIENT_BUFFER.to_hresult() => {
+                let len = (count as usize).max(formats.len() * 2);
+                formats.resize(len, 0);
+            }
+            Err(e) => {
+                warn!("Failed to get updated clipboard formats: {}", e);
+                return Vec::new();
+            }
+        }
+    }
+}
+
+unsafe extern "system" fn clipboard_monitor_subclass_proc(
+    hwnd: HWND,
+    message: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+    subclass_id: usize,
+    ref_data: usize,
+) -> LRESULT {
+    let state = ref_data as *mut Sender<ClipboardEvent>;
+    match message {
+        WM_CLIPBOARDUPDATE => {
+            let event = ClipboardEvent {
+                sequence_number: unsafe { GetClipboardSequenceNumber() },
+                formats: updated_clipboard_formats(),
+            };
+            // Nobody listening is fine, the monitor just hasn't been dropped yet
+            let _ = unsafe { &*state }.send(event);
+            return LRESULT(0);
+        }
+        WM_DESTROY => {
+            _ = unsafe { RemoveClipboardFormatListener(hwnd) };
+            unsafe { PostQuitMessage(0) };
+        }
+        WM_NCDESTROY => {
+            _ = unsafe {
+                RemoveWindowSubclass(hwnd, Some(clipboard_monitor_subclass_proc), subclass_id)
+            };
+            drop(unsafe { Box::from_raw(state) });
+        }
+        _ => {}
+    }
+    unsafe { DefSubclassProc(hwnd, message, wparam, lparam) }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ClipboardMonitor;
+    use crate::clipboard::write_clipboard;
+    use std::time::Duration;
+    use windows::Win32::System::Ole::CF_UNICODETEXT;
+
+    #[test]
+    fn starts_and_stops() -> eyre::Result<()> {
+        let monitor = ClipboardMonitor::new()?;
+        let receiver = monitor.receiver().clone();
+        drop(monitor);
+        // The window and its sender are gone, so the channel is disconnected
+        assert!(receiver.recv_timeout(Duration::from_secs(5)).is_err());
+        Ok(())
+    }
+
+    // Replaces the clipboard contents
+    #[test]
+    #[ignore]
+    fn reports_clipboard_writes() -> eyre::Result<()> {
+        let monitor = ClipboardMonitor::new()?;
+        write_clipboard("clipboard monitor test")?;
+        let event = monitor.receiver().recv_timeout(Duration::from_secs(5))?;
+        assert_ne!(event.sequence_number, 0);
+        assert!(event.formats.contains(&(CF_UNICODETEXT.0 as u32)));
+        Ok(())
+    }
+}
@@ -0,0 +1,147 @@
+use crate::error::last_error_context;
+use eyre::Result;
+use eyre::bail;
+use windows::Win32::Foundation::HGLOBAL;
+use windows::Win32::Globalization::CP_ACP;
+use windows::Win32::Globalization::CP_OEMCP;
+use windows::Win32::Globalization::GetLocaleInfoW;
+use windows::Win32::Globalization::LOCALE_IDEFAULTANSICODEPAGE;
+use windows::Win32::Globalization::LOCALE_IDEFAULTCODEPAGE;
+use windows::Win32::Globalization::LOCALE_RETURN_NUMBER;
+use windows::Win32::Globalization::MULTI_BYTE_TO_WIDE_CHAR_FLAGS;
+use windows::Win32::Globalization::MultiByteToWideChar;
+use windows::Win32::System::DataExchange::GetClipboardData;
+use windows::Win32::System::DataExchange::IsClipboardFormatAvailable;
+use windows::Win32::System::Memory::GlobalLock;
+use windows::Win32::System::Memory::GlobalSize;
+use windows::Win32::System::Memory::GlobalUnlock;
+use windows::Win32::System::Ole::CF_LOCALE;
+use windows::Win32::System::Ole::CF_OEMTEXT;
+
+/// Reads the locale identifier (`CF_LOCALE`) that tells how the clipboard's narrow text is encoded.
+///
+/// The clipboard must already be open.
+/// <https://learn.microsoft.com/en-us/windows/win32/dataxchg/standard-clipboard-formats>
+pub fn clipboard_locale() -> Option<u32> {
+    unsafe { IsClipboardFormatAvailable(CF_LOCALE.0 as u32) }.ok()?;
+    let handle = unsafe { GetClipboardData(CF_LOCALE.0 as u32) }.ok()?;
+    let handle = HGLOBAL(handle.0);
+    if unsafe { GlobalSize(handle) } < size_of::<u32>() {
+        return None;
+    }
+    let lock = unsafe { GlobalLock(handle) };
+    if lock.is_null() {
+        return None;
+    }
+    let lcid = unsafe { (lock as *const u32).read_unaligned() };
+    let _ = unsafe { GlobalUnlock(handle) };
+    Some(lcid)
+}
+
+/// The code page used by `format` (`CF_TEXT` or `CF_OEMTEXT`) for text tagged with `lcid`.
+///
+/// Falls back to the system ANSI or OEM code page when there is no locale or it has no narrow code page.
+pub fn code_page_for_locale(lcid: Option<u32>, format: u32) -> u32 {
+    let is_oem = format == CF_OEMTEXT.0 as u32;
+    let default = if is_oem { CP_OEMCP } else { CP_ACP };
+    let Some(lcid) = lcid else {
+        return default;
+    };
+    let lctype = if is_oem {
+        LOCALE_IDEFAULTCODEPAGE
+    } else {
+        LOCALE_IDEFAULTANSICODEPAGE
+    };
+    // With LOCALE_RETURN_NUMBER the buffer receives a DWORD rather than a string
+    let mut buffer = [0u16; 2];
+    let written = unsafe { GetLocaleInfoW(lcid, lctype | LOCALE_RETURN_NUMBER, Some(&mut buffer)) };
+    let code_page = u32::from(buffer[0]) | (u32::from(buffer[1]) << 16);
+    if written == 0 || code_page == 0 {
+        default
+    } else {
+        code_page
+    }
+}
+
+/// Decodes `bytes` from `code_page` into a string. Decoding stops at the first nul.
+pub fn decode_code_page(bytes: &[u8], code_page: u32) -> Result<String> {
+    let bytes = match bytes.iter().position(|&b| b == 0) {
+        Some(nul) => &bytes[..nul],
+        None => bytes,
+    };
+    if bytes.is_empty() {
+        return Ok(String::new());
+    }
+    let flags = MULTI_BYTE_TO_WIDE_CHAR_FLAGS(0);
+    let len = unsafe { MultiByteToWideChar(code_page, flags, bytes, None) };
+    if len <= 0 {
+        bail!(
+            "Failed to measure text in code page {}: {}",
+            code_page,
+            windows::core::Error::from_thread()
+        );
+    }
+    let mut wide = vec![0u16; len as usize];
+    let len = unsafe { MultiByteToWideChar(code_page, flags, bytes, Some(&mut wide)) };
+    if len <= 0 {
+        bail!(
+            "Failed to decode text in code page {}: {}",
+            code_page,
+            windows::core::Error::from_thread()
+        );
+    }
+    Ok(String::from_utf16_lossy(&wide[..len as usize]))
+}
+
+/// Decodes `CF_TEXT` or `CF_OEMTEXT` clipboard data using the code page implied by `CF_LOCALE`.
+///
+/// The clipboard must already be open.
+pub fn decode_clipboard_text(handle: HGLOBAL, format: u32) -> Result<String> {
+    let code_page = code_page_for_locale(clipboard_locale(), format);
+    let size = unsafe { GlobalSize(handle) };
+    let lock = unsafe { GlobalLock(handle) };
+    if lock.is_null() {
+        bail!("Failed to lock clipboard data: {}", last_error_context());
+    }
+    let bytes = unsafe { std::slice::from_raw_parts(lock as *const u8, size) };
+    let result = decode_code_page(bytes, code_page);
+    let _ = unsafe { GlobalUnlock(handle) };
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::code_page_for_locale;
+    use super::decode_code_page;
+    use windows::Win32::System::Ole::CF_OEMTEXT;
+    use windows::Win32::System::Ole::CF_TEXT;
+
+    const LCID_EN_US: u32 = 0x0409;
+    const LCID_RU_RU: u32 = 0x0419;
+
+    #[test]
+    fn locale_picks_code_page() {
+        assert_eq!(
+            code_page_for_locale(Some(LCID_EN_US), CF_TEXT.0 as u32),
+            1252
+        );
+        assert_eq!(
+            code_page_for_locale(Some(LCID_EN_US), CF_OEMTEXT.0 as u32),
+            437
+        );
+        assert_eq!(
+            code_page_for_locale(Some(LCID_RU_RU), CF_TEXT.0 as u32),
+            1251
+        );
+    }
+
+    #[test]
+    fn decodes_non_utf8_text() -> eyre::Result<()> {
+        // "Привет" in Windows-1251, followed by a nul and trailing garbage
+        let bytes = [0xCF, 0xF0, 0xE8, 0xE2, 0xE5, 0xF2, 0x00, 0xFF];
+        assert_eq!(decode_code_page(&bytes, 1251)?, "Привет");
+        // "café" in Windows-1252
+        assert_eq!(decode_code_page(&[0x63, 0x61, 0x66, 0xE9], 1252)?, "café");
+        Ok(())
+    }
+}
@@ -0,0 +1,155 @@
+use super::clipboard_guard::ClipboardGuard;
+use crate::error::last_error_context;
+use eyre::Context;
+use eyre::Result;
+use eyre::bail;
+use std::ptr;
+use tracing::debug;
+use tracing::warn;
+use windows::Win32::Foundation::GlobalFree;
+use windows::Win32::Foundation::HANDLE;
+use windows::Win32::Foundation::HWND;
+use windows::Win32::Foundation::LPARAM;
+use windows::Win32::Foundation::LRESULT;
+use windows::Win32::Foundation::SetLastError;
+use windows::Win32::Foundation::WIN32_ERROR;
+use windows::Win32::Foundation::WPARAM;
+use windows::Win32::System::DataExchange::EmptyClipboard;
+use windows::Win32::System::DataExchange::GetClipboardOwner;
+use windows::Win32::System::DataExchange::SetClipboardData;
+use windows::Win32::System::Memory::GMEM_MOVEABLE;
+use windows::Win32::System::Memory::GlobalAlloc;
+use windows::Win32::System::Memory::GlobalLock;
+use windows::Win32::System::Memory::GlobalUnlock;
+use windows::Win32::UI::Shell::DefSubclassProc;
+use windows::Win32::UI::Shell::RemoveWindowSubclass;
+use windows::Win32::UI::Shell::SetWindowSubclass;
+use windows::Win32::UI::WindowsAndMessaging::WM_DESTROYCLIPBOARD;
+use windows::Win32::UI::WindowsAndMessaging::WM_NCDESTROY;
+use windows::Win32::UI::WindowsAndMessaging::WM_RENDERALLFORMATS;
+use windows::Win32::UI::WindowsAndMessaging::WM_RENDERFORMAT;
+
+const DELAYED_RENDER_SUBCLASS_ID: usize = 0x636c_6970; // "clip"
+
+struct DelayedRender {
+    formats: Vec<u32>,
+    render: Box<dyn Fn(u32) -> Option<HANDLE>>,
+}
+
+/// Offers `formats` on the clipboard without producing their data until another application asks for it.
+///
+/// `hwnd` becomes the clipboard owner and is subclassed to answer `WM_RENDERFORMAT` by calling `render`,
+/// which returns a `GlobalAlloc` handle (see [`clipboard_global_from_bytes`]) that the system then owns,
+/// or `None` to leave the format empty. Use a hidden window owned by this thread, such as a message-only
+/// [`crate::window::WindowBuilder`] window, and keep pumping its messages for as long as the data is offered.
+///
+/// The callback is dropped once another application takes over the clipboard or the window is destroyed,
+/// in which case every format is rendered first so the data outlives this process.
+/// <https://learn.microsoft.com/en-us/windows/win32/dataxchg/clipboard-operations#delayed-rendering>
+pub fn set_clipboard_delayed(
+    hwnd: HWND,
+    formats: impl IntoIterator<Item = u32>,
+    render: impl Fn(u32) -> Option<HANDLE> + 'static,
+) -> Result<()> {
+    let formats: Vec<u32> = formats.into_iter().collect();
+    let _guard = ClipboardGuard::open_for(hwnd)?;
+    // Emptying sends WM_DESTROYCLIPBOARD to the previous owner, which cleans up any earlier offer from this window
+    unsafe { EmptyClipboard() }.wrap_err("Failed to empty clipboard")?;
+
+    let state = Box::into_raw(Box::new(DelayedRender {
+        formats: formats.clone(),
+        render: Box::new(render),
+    }));
+    let subclassed = unsafe {
+        SetWindowSubclass(
+            hwnd,
+            Some(delayed_render_subclass_proc),
+            DELAYED_RENDER_SUBCLASS_ID,
+            state as usize,
+        )
+    }
+    .as_bool();
+    if !subclassed {
+        drop(unsafe { Box::from_raw(state) });
+        bail!("Failed to subclass window for delayed clipboard rendering");
+    }
+
+    for format in formats {
+        // A null handle is how delayed rendering is requested, and also what success looks like
+        unsafe { SetLastError(WIN32_ERROR(0)) };
+        if let Err(e) = unsafe { SetClipboardData(format, None) }
+            && e.code().is_err()
+        {
+            return Err(e).wrap_err_with(|| format!("Failed to offer clipboard format {format}"));
+        }
+    }
+    debug!("Offered clipboard formats for delayed rendering");
+    Ok(())
+}
+
+/// Copies `bytes` into a movable global allocation suitable for handing to the clipboard.
+pub fn clipboard_global_from_bytes(bytes: &[u8]) -> Result<HANDLE> {
+    let handle = unsafe { GlobalAlloc(GMEM_MOVEABLE, bytes.len()) }
+        .wrap_err("Failed to allocate clipboard buffer")?;
+    let lock = unsafe { GlobalLock(handle) };
+    if lock.is_null() {
+        let error = last_error_context();
+        let _ = unsafe { GlobalFree(Some(handle)) };
+        bail!("Failed to lock clipboard buffer: {}", error);
+    }
+    unsafe { ptr::copy_nonoverlapping(bytes.as_ptr(), lock as *mut u8, bytes.len()) };
+    let _ = unsafe { GlobalUnlock(handle) };
+    Ok(HANDLE(handle.0))
+}
+
+fn render_format(state: &DelayedRender, format: u32) {
+    let Some(handle) = (state.render)(format) else {
+        debug!(format, "Delayed clipboard render produced no data");
+        return;
+    };
+    if let Err(e) = unsafe { SetClipboardData(format, Some(handle)) } {
+        warn!("Failed to set rendered clipboard format {}: {}", format, e);
+    }
+}
+
+unsafe extern "system" fn delayed_render_subclass_proc(
+    hwnd: HWND,
+    message: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+    subclass_id: usize,
+    ref_data: usize,
+) -> LRESULT {
+    let state = ref_data as *mut DelayedRender;
+    match message {
+        WM_RENDERFORMAT => {
+            // The clipboard is already open on behalf of whoever is pasting
+            render_format(unsafe { &*state }, wparam.0 as u32);
+            return LRESULT(0);
+        }
+        WM_RENDERALLFORMATS => {
+            // Only render if we still own the clipboard, another app may have taken it meanwhile
+            match ClipboardGuard::open_for(hwnd) {
+                Ok(_guard) => {
+                    if unsafe { GetClipboardOwner() }.is_ok_and(|owner| owner == hwnd) {
+                        let state = unsafe { &*state };
+                        for &format in &state.formats {
+                            render_format(state, format);
+                        }
+                    }
+                }
+                Err(e) => warn!("Failed to open clipboard to render all formats: {}", e),
+            }
+            return LRESULT(0);
+        }
+        WM_DESTROYCLIPBOARD | WM_NCDESTROY => {
+            _ = unsafe {
+                RemoveWindowSubclass(hwnd, Some(delayed_render_subclass_proc), subclass_id)
+            };
+            drop(unsafe { Box::from_raw(state) });
+            debug!("Released delayed clipboard render callback");
+        }
+        _ => {}
+    }
+    unsafe { DefSubclassProc(hwnd, message, wparam, lparam) }
+}
@@ -2,8 +2,16 @@
 
 mod clipboard_format_ext;
 mod clipboard_guard;
+mod clipboard_image;
 mod clipboard_io;
+mod clipboard_monitor;
+mod clipboard_text_encoding;
+mod delayed_render;
 
 pub use clipboard_format_ext::*;
 pub use clipboard_guard::*;
+pub use clipboard_image::*;
 pub use clipboard_io::*;
+pub use clipboard_monitor::*;
+pub use clipboard_text_encoding::*;
+pub use delayed_render::*;
@@ -0,0 +1,61 @@
+use std::ptr::NonNull;
+use windows::Win32::System::Com::CoTaskMemFree;
+
+/// Memory allocated by the COM task allocator, freed with `CoTaskMemFree` on drop.
+///
+/// For out-pointers like `IAudioClient::GetMixFormat` or `SHParseDisplayName`'s PIDL, where every early
+/// return between the call and a manual free would otherwise leak.
+/// <https://learn.microsoft.com/en-us/windows/win32/api/combaseapi/nf-combaseapi-cotaskmemfree>
+#[derive(Debug)]
+pub struct CoTaskMem<T>(NonNull<T>);
+
+impl<T> CoTaskMem<T> {
+    /// Takes ownership of `ptr`, returning `None` if it is null.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must have been allocated with `CoTaskMemAlloc` (directly or by the API that returned it),
+    /// and must not be freed elsewhere.
+    pub unsafe fn from_raw(ptr: *mut T) -> Option<Self> {
+        NonNull::new(ptr).map(Self)
+    }
+
+    pub fn as_ptr(&self) -> *mut T {
+        self.0.as_ptr()
+    }
+
+    /// # Safety
+    ///
+    /// The allocation must hold an initialized `T`.
+    pub unsafe fn as_ref(&self) -> &T {
+        unsafe { self.0.as_ref() }
+    }
+
+    /// Releases ownership without freeing the memory.
+    pub fn into_raw(self) -> *mut T {
+        let ptr = self.0.as_ptr();
+        std::mem::forget(self);
+        ptr
+    }
+}
+
+impl<T> Drop for CoTaskMem<T> {
+    fn drop(&mut self) {
+        unsafe { CoTaskMemFree(Some(self.0.as_ptr() as *const _)) };
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::CoTaskMem;
+    use windows::Win32::System::Com::CoTaskMemAlloc;
+
+    #[test]
+    fn owns_allocation() {
+        let raw = unsafe { CoTaskMemAlloc(size_of::<u32>()) } as *mut u32;
+        unsafe { raw.write(42) };
+        let memory = unsafe { CoTaskMem::from_raw(raw) }.expect("allocation failed");
+        assert_eq!(unsafe { *memory.as_ref() }, 42);
+        assert!(unsafe { CoTaskMem::<u32>::from_raw(std::ptr::null_mut()) }.is_none());
+    }
+}
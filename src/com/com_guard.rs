@@ -1,6 +1,9 @@
 use eyre::Result;
 use windows::Win32::Foundation::RPC_E_CHANGED_MODE;
+use windows::Win32::System::Com::APTTYPE;
+use windows::Win32::System::Com::APTTYPEQUALIFIER;
 use windows::Win32::System::Com::COINIT_APARTMENTTHREADED;
+use windows::Win32::System::Com::CoGetApartmentType;
 use windows::Win32::System::Com::CoInitializeEx;
 use windows::Win32::System::Com::CoUninitialize;
 
@@ -8,6 +11,10 @@ use windows::Win32::System::Com::CoUninitialize;
 ///
 /// Calls `CoInitializeEx` on creation and `CoUninitialize` on drop if initialization was successful
 /// (or if it was already initialized with a compatible mode, incrementing the refcount).
+///
+/// COM apartments are per-thread, so the guard must be created on the thread that makes the COM calls.
+/// Async runtimes move tasks between worker threads whose apartment is unknown,
+/// so run COM work from async code through [`crate::com::com_thread::run_on_com_thread`] instead.
 pub struct ComGuard {
     should_uninitialize: bool,
 }
@@ -39,6 +46,27 @@ impl ComGuard {
     }
 }
 
+impl ComGuard {
+    /// Whether COM has been initialized on the current thread, in any apartment.
+    pub fn is_initialized() -> bool {
+        let mut apartment_type = APTTYPE::default();
+        let mut qualifier = APTTYPEQUALIFIER::default();
+        unsafe { CoGetApartmentType(&mut apartment_type, &mut qualifier) }.is_ok()
+    }
+
+    /// Panics in debug builds if COM has not been initialized on the current thread.
+    ///
+    /// Calls that would otherwise fail later with `CO_E_NOTINITIALIZED` can use this to point at the missing guard.
+    #[track_caller]
+    pub fn assert_initialized() {
+        debug_assert!(
+            Self::is_initialized(),
+            "COM is not initialized on thread {:?}, create a ComGuard first",
+            std::thread::current().name().unwrap_or("<unnamed>")
+        );
+    }
+}
+
 impl Drop for ComGuard {
     fn drop(&mut self) {
         if self.should_uninitialize {
@@ -0,0 +1,39 @@
+use crate::com::com_guard::ComGuard;
+use eyre::Context;
+use eyre::Result;
+
+/// Runs `f` on a fresh thread with COM initialized as a single-threaded apartment, and waits for its result.
+///
+/// Use this to call COM APIs from async code, whose worker threads have no known apartment.
+/// Blocking is fine from `tokio::task::spawn_blocking`; from an async task, wrap the call in it.
+/// COM objects created by `f` must not escape it, since they belong to the dedicated thread's apartment.
+pub fn run_on_com_thread<T, F>(f: F) -> Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T> + Send + 'static,
+{
+    let handle = std::thread::Builder::new()
+        .name("com-worker".into())
+        .spawn(move || {
+            let _com_guard = ComGuard::new()?;
+            f()
+        })
+        .wrap_err("Failed to spawn COM worker thread")?;
+    match handle.join() {
+        Ok(result) => result,
+        Err(panic) => std::panic::resume_unwind(panic),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::run_on_com_thread;
+    use crate::com::com_guard::ComGuard;
+
+    #[test]
+    fn initializes_com_on_worker() -> eyre::Result<()> {
+        let initialized = run_on_com_thread(|| Ok(ComGuard::is_initialized()))?;
+        assert!(initialized);
+        Ok(())
+    }
+}
@@ -1 +1,4 @@
+pub mod co_task_mem;
 pub mod com_guard;
+pub mod com_thread;
+pub mod property_store;
@@ -0,0 +1,118 @@
+use eyre::Context;
+use windows::Win32::Foundation::DEVPROPKEY;
+use windows::Win32::Foundation::PROPERTYKEY;
+use windows::Win32::Media::Audio::IMMDevice;
+use windows::Win32::System::Com::STGM_READ;
+use windows::Win32::System::Com::StructuredStorage::PROPVARIANT;
+use windows::Win32::System::Com::StructuredStorage::PropVariantToGUID;
+use windows::Win32::UI::Shell::PropertiesSystem::IPropertyStore;
+use windows::core::BSTR;
+use windows::core::GUID;
+
+/// Typed reads from an [`IPropertyStore`].
+///
+/// Each getter returns `Ok(None)` when the property isn't set, and an error when it is set to something that
+/// can't be converted. The `PROPVARIANT`s are cleared on drop.
+/// <https://learn.microsoft.com/en-us/windows/win32/api/propsys/nn-propsys-ipropertystore>
+#[derive(Debug, Clone)]
+pub struct PropertyStore(pub IPropertyStore);
+
+impl PropertyStore {
+    pub fn new(store: IPropertyStore) -> Self {
+        Self(store)
+    }
+
+    /// Opens the read-only property store of an audio endpoint.
+    pub fn from_device(device: &IMMDevice) -> eyre::Result<Self> {
+        let store = unsafe { device.OpenPropertyStore(STGM_READ) }
+            .wrap_err("Failed to open device property store")?;
+        Ok(Self(store))
+    }
+
+    /// Reads the raw value, `None` when the property is empty.
+    pub fn get(&self, key: impl Into<PropertyKey>) -> eyre::Result<Option<PROPVARIANT>> {
+        let key = key.into().0;
+        let value = unsafe { self.0.GetValue(&key) }
+            .wrap_err_with(|| format!("Failed to read property {:?},{}", key.fmtid, key.pid))?;
+        Ok((!value.is_empty()).then_some(value))
+    }
+
+    pub fn get_string(&self, key: impl Into<PropertyKey>) -> eyre::Result<Option<String>> {
+        let Some(value) = self.get(key)? else {
+            return Ok(None);
+        };
+        let value = BSTR::try_from(&value).wrap_err("Property is not convertible to a string")?;
+        Ok(Some(value.to_string()))
+    }
+
+    /// Reads a `VT_CLSID` property, or a string property holding a GUID.
+    pub fn get_guid(&self, key: impl Into<PropertyKey>) -> eyre::Result<Option<GUID>> {
+        let Some(value) = self.get(key)? else {
+            return Ok(None);
+        };
+        let guid = unsafe { PropVariantToGUID(&value) }
+            .wrap_err("Property is not convertible to a GUID")?;
+        Ok(Some(guid))
+    }
+
+    pub fn get_u32(&self, key: impl Into<PropertyKey>) -> eyre::Result<Option<u32>> {
+        let Some(value) = self.get(key)? else {
+            return Ok(None);
+        };
+        let value = u32::try_from(&value).wrap_err("Property is not convertible to a u32")?;
+        Ok(Some(value))
+    }
+
+    pub fn get_bool(&self, key: impl Into<PropertyKey>) -> eyre::Result<Option<bool>> {
+        let Some(value) = self.get(key)? else {
+            return Ok(None);
+        };
+        let value = bool::try_from(&value).wrap_err("Property is not convertible to a bool")?;
+        Ok(Some(value))
+    }
+}
+
+/// A property key, accepting both shell `PROPERTYKEY`s and device `DEVPROPKEY`s such as
+/// `DEVPKEY_Device_FriendlyName`, which share a layout.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PropertyKey(pub PROPERTYKEY);
+
+impl From<PROPERTYKEY> for PropertyKey {
+    fn from(key: PROPERTYKEY) -> Self {
+        Self(key)
+    }
+}
+
+impl From<&PROPERTYKEY> for PropertyKey {
+    fn from(key: &PROPERTYKEY) -> Self {
+        Self(*key)
+    }
+}
+
+impl From<DEVPROPKEY> for PropertyKey {
+    fn from(key: DEVPROPKEY) -> Self {
+        Self(PROPERTYKEY {
+            fmtid: key.fmtid,
+            pid: key.pid,
+        })
+    }
+}
+
+impl From<&DEVPROPKEY> for PropertyKey {
+    fn from(key: &DEVPROPKEY) -> Self {
+        Self::from(*key)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::PropertyKey;
+    use windows::Win32::Devices::Properties::DEVPKEY_Device_FriendlyName;
+
+    #[test]
+    fn devpropkey_converts_to_propertykey() {
+        let key = PropertyKey::from(&DEVPKEY_Device_FriendlyName);
+        assert_eq!(key.0.fmtid, DEVPKEY_Device_FriendlyName.fmtid);
+        assert_eq!(key.0.pid, DEVPKEY_Device_FriendlyName.pid);
+    }
+}
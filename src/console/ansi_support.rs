@@ -1,5 +1,7 @@
+use crate::console::get_console_error_handle;
 use crate::console::get_console_output_handle;
 use eyre::Context;
+use windows::Win32::Foundation::HANDLE;
 use windows::Win32::System::Console::CONSOLE_MODE;
 use windows::Win32::System::Console::ENABLE_VIRTUAL_TERMINAL_PROCESSING;
 use windows::Win32::System::Console::GetConsoleMode;
@@ -8,7 +10,10 @@ use windows::Win32::System::Console::SetConsoleMode;
 pub fn enable_ansi_support() -> eyre::Result<()> {
     // Get console handle
     let handle = get_console_output_handle().wrap_err("Failed to get console output handle")?;
+    enable_virtual_terminal_processing(handle)
+}
 
+fn enable_virtual_terminal_processing(handle: HANDLE) -> eyre::Result<()> {
     // Get existing mode
     let mut mode = CONSOLE_MODE::default();
     unsafe { GetConsoleMode(handle, &mut mode) }.wrap_err("Failed to get console mode")?;
@@ -18,3 +23,29 @@ pub fn enable_ansi_support() -> eyre::Result<()> {
         .wrap_err("Failed to set console mode")?;
     Ok(())
 }
+
+/// Whether the current console output handle has virtual terminal processing enabled, i.e. renders ANSI colors.
+///
+/// Returns `false` when stdout is not a console, such as when it is redirected to a file or pipe.
+pub fn is_ansi_enabled() -> bool {
+    let Ok(handle) = get_console_output_handle() else {
+        return false;
+    };
+    let mut mode = CONSOLE_MODE::default();
+    if unsafe { GetConsoleMode(handle, &mut mode) }.is_err() {
+        return false;
+    }
+    mode.contains(ENABLE_VIRTUAL_TERMINAL_PROCESSING)
+}
+
+/// Attempts to enable ANSI escape sequence processing for stderr, where logs are written.
+///
+/// Returns `true` when stderr is a console with virtual terminal processing active, so callers can decide whether to emit colors.
+/// Stdout is not consulted: it can be redirected independently, e.g. `app > out.txt` still logs to the console.
+/// Fails quietly when stderr is not a console or the console does not support VT sequences.
+pub fn try_enable_ansi_support() -> bool {
+    let Ok(handle) = get_console_error_handle() else {
+        return false;
+    };
+    enable_virtual_terminal_processing(handle).is_ok()
+}
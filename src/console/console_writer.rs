@@ -0,0 +1,99 @@
+use std::io::Write;
+use std::sync::Mutex;
+use windows::Win32::Foundation::CloseHandle;
+use windows::Win32::Foundation::HANDLE;
+use windows::Win32::Storage::FileSystem::CreateFileW;
+use windows::Win32::Storage::FileSystem::FILE_ATTRIBUTE_NORMAL;
+use windows::Win32::Storage::FileSystem::FILE_GENERIC_READ;
+use windows::Win32::Storage::FileSystem::FILE_GENERIC_WRITE;
+use windows::Win32::Storage::FileSystem::FILE_SHARE_READ;
+use windows::Win32::Storage::FileSystem::FILE_SHARE_WRITE;
+use windows::Win32::Storage::FileSystem::OPEN_EXISTING;
+use windows::Win32::System::Console::WriteConsoleW;
+use windows::core::w;
+
+/// The CONOUT$ handle used by [`ConsoleWriter`], stored as an address so the static is `Send`.
+static CONOUT: Mutex<Option<usize>> = Mutex::new(None);
+
+/// Writes to whichever console is attached at the time of the write, instead of the std handle captured at startup.
+///
+/// The CONOUT$ handle is opened lazily and closed whenever the std handles are rebound or unbound,
+/// so logs keep streaming after the tray "show logs" transition allocates a new console.
+/// Writes are silently dropped while no console is attached.
+///
+/// Using it as a log writer needs the `tracing-subscriber` feature:
+///
+#[cfg_attr(feature = "tracing-subscriber", doc = "```")]
+#[cfg_attr(not(feature = "tracing-subscriber"), doc = "```ignore")]
+/// use teamy_windows::console::ConsoleWriter;
+/// use teamy_windows::log::LOG_BUFFER;
+/// use tracing_subscriber::fmt::SubscriberBuilder;
+/// use tracing_subscriber::fmt::writer::MakeWriterExt;
+/// use tracing_subscriber::util::SubscriberInitExt;
+/// SubscriberBuilder::default()
+///    .with_writer(ConsoleWriter.and(LOG_BUFFER.clone()))
+///    .finish()
+///    .init();
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ConsoleWriter;
+
+impl Write for ConsoleWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let mut conout = CONOUT.lock().unwrap();
+        if conout.is_none() {
+            *conout = open_conout().map(|handle| handle.0 as usize);
+        }
+        let Some(handle) = *conout else {
+            return Ok(buf.len());
+        };
+        let wide: Vec<u16> = String::from_utf8_lossy(buf).encode_utf16().collect();
+        if unsafe { WriteConsoleW(HANDLE(handle as _), &wide, None, None) }.is_err() {
+            // The console went away underneath us, reopen on the next write
+            close_cached(&mut conout);
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(feature = "tracing-subscriber")]
+impl<'a> tracing_subscriber::fmt::MakeWriter<'a> for ConsoleWriter {
+    type Writer = ConsoleWriter;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        *self
+    }
+}
+
+/// Closes the cached CONOUT$ handle so the next [`ConsoleWriter`] write resolves the current console.
+///
+/// Called whenever the std handles are swapped; holding the handle would otherwise keep a freed console window alive.
+pub fn invalidate_console_writer() {
+    let mut conout = CONOUT.lock().unwrap();
+    close_cached(&mut conout);
+}
+
+fn close_cached(conout: &mut Option<usize>) {
+    if let Some(handle) = conout.take() {
+        let _ = unsafe { CloseHandle(HANDLE(handle as _)) };
+    }
+}
+
+fn open_conout() -> Option<HANDLE> {
+    unsafe {
+        CreateFileW(
+            w!("CONOUT$"),
+            FILE_GENERIC_READ.0 | FILE_GENERIC_WRITE.0,
+            FILE_SHARE_READ | FILE_SHARE_WRITE,
+            None,
+            OPEN_EXISTING,
+            FILE_ATTRIBUTE_NORMAL,
+            None,
+        )
+    }
+    .ok()
+}
@@ -1,11 +1,16 @@
+use crate::console::StdHandles;
 use crate::console::attach_ctrl_c_handler;
 use crate::console::check_inheriting;
 use crate::console::enable_ansi_support;
 use crate::console::rebind_std_handles_to_console;
+use crate::console::rebind_std_handles_to_console_keeping_previous;
+use crate::console::unbind_and_close_std_handles_for_detach;
 use eyre::Context;
 use tracing::error;
 use tracing::info;
+use tracing::warn;
 use windows::Win32::System::Console::AllocConsole;
+use windows::Win32::System::Console::FreeConsole;
 
 pub fn console_create() -> eyre::Result<()> {
     // Create new console
@@ -16,6 +21,61 @@ pub fn console_create() -> eyre::Result<()> {
     // newly created console so println!/eprintln! and tracing output go there.
     rebind_std_handles_to_console().wrap_err("Failed to bind std handles to console")?;
 
+    finish_console_setup();
+    Ok(())
+}
+
+/// Allocates a new console that is freed again when the returned [`OwnedConsole`] is dropped.
+///
+/// Unlike [`console_create`], the std handles from before the call are kept open and reinstalled on drop,
+/// so output redirected to a file or pipe resumes there once the console goes away.
+/// Fails if the process is already attached to a console; detach first with [`console_detach`](crate::console::console_detach).
+pub fn console_create_scoped() -> eyre::Result<OwnedConsole> {
+    unsafe { AllocConsole() }.wrap_err("Failed to allocate console")?;
+    let previous = match rebind_std_handles_to_console_keeping_previous() {
+        Ok(previous) => previous,
+        Err(e) => {
+            _ = unsafe { FreeConsole() };
+            return Err(e.wrap_err("Failed to bind std handles to console"));
+        }
+    };
+    let console = OwnedConsole { previous };
+    finish_console_setup();
+    Ok(console)
+}
+
+/// A console allocated by [`console_create_scoped`].
+///
+/// Dropping it closes the console's std handles, frees the console, and reinstalls the std handles
+/// that were in place before it was created.
+#[must_use = "the console is freed when this is dropped"]
+#[derive(Debug)]
+pub struct OwnedConsole {
+    previous: StdHandles,
+}
+
+impl OwnedConsole {
+    /// The std handles that will be reinstalled on drop.
+    pub fn previous_std_handles(&self) -> StdHandles {
+        self.previous
+    }
+}
+
+impl Drop for OwnedConsole {
+    fn drop(&mut self) {
+        info!("Freeing this console, logs will no longer be visible here.");
+        unbind_and_close_std_handles_for_detach();
+        if let Err(e) = unsafe { FreeConsole() } {
+            warn!("Failed to free console: {e}");
+        }
+        if let Err(e) = self.previous.install() {
+            warn!("Failed to restore std handles after freeing console: {e:?}");
+        }
+        _ = check_inheriting::is_inheriting_console(); // for logging
+    }
+}
+
+fn finish_console_setup() {
     _ = check_inheriting::is_inheriting_console(); // for logging
 
     // Attach ctrl+c handler (continue on error)
@@ -31,5 +91,4 @@ pub fn console_create() -> eyre::Result<()> {
     // Tell the user whats up
     info!("Console allocated, new logs will be visible here.");
     info!("Closing this window will exit the program.");
-    Ok(())
 }
@@ -1,3 +1,4 @@
+use std::sync::Mutex;
 use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering;
 use tracing::debug;
@@ -56,3 +57,131 @@ pub fn attach_ctrl_c_handler() -> windows::core::Result<()> {
     }
     Ok(())
 }
+
+type CtrlCCallback = Box<dyn Fn() + Send + Sync>;
+
+static CTRL_C_CALLBACK: Mutex<Option<CtrlCCallback>> = Mutex::new(None);
+
+unsafe extern "system" fn ctrl_c_callback_handler(ctrl_type: u32) -> BOOL {
+    match ctrl_type {
+        CTRL_C_EVENT | CTRL_BREAK_EVENT => {
+            let callback = CTRL_C_CALLBACK.lock().unwrap();
+            match callback.as_ref() {
+                Some(callback) => {
+                    info!("Received Ctrl-C, stopping...");
+                    callback();
+                    TRUE
+                }
+                None => FALSE,
+            }
+        }
+        _ => FALSE,
+    }
+}
+
+/// Runs `callback` on Ctrl-C or Ctrl-Break instead of terminating the process.
+///
+/// Suited to console commands without a window for [`attach_ctrl_c_handler`] to close.
+/// Calling this again replaces the previous callback.
+/// The callback runs on a thread created by the system, so it should only signal other work to stop.
+pub fn attach_ctrl_c_callback(
+    callback: impl Fn() + Send + Sync + 'static,
+) -> windows::core::Result<()> {
+    debug!("Attaching console ctrl+c callback");
+    let previous = CTRL_C_CALLBACK.lock().unwrap().replace(Box::new(callback));
+    if previous.is_none() {
+        unsafe { SetConsoleCtrlHandler(Some(ctrl_c_callback_handler), true)? };
+    }
+    Ok(())
+}
+
+/// A console control signal, see `HandlerRoutine`.
+/// <https://learn.microsoft.com/en-us/windows/console/handlerroutine>
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConsoleCtrlEvent {
+    CtrlC,
+    CtrlBreak,
+    /// The console window is being closed.
+    Close,
+    /// The user is logging off. Only delivered to services and processes without a window.
+    Logoff,
+    /// The system is shutting down. Only delivered to services and processes without a window.
+    Shutdown,
+}
+
+impl ConsoleCtrlEvent {
+    pub fn from_ctrl_type(ctrl_type: u32) -> Option<Self> {
+        match ctrl_type {
+            CTRL_C_EVENT => Some(Self::CtrlC),
+            CTRL_BREAK_EVENT => Some(Self::CtrlBreak),
+            CTRL_CLOSE_EVENT => Some(Self::Close),
+            CTRL_LOGOFF_EVENT => Some(Self::Logoff),
+            CTRL_SHUTDOWN_EVENT => Some(Self::Shutdown),
+            _ => None,
+        }
+    }
+
+    /// Whether the process is terminated once the handler returns, regardless of its decision.
+    pub fn terminates_process(&self) -> bool {
+        matches!(self, Self::Close | Self::Logoff | Self::Shutdown)
+    }
+}
+
+type ConsoleCtrlCallback = Box<dyn Fn(ConsoleCtrlEvent) -> bool + Send + Sync>;
+
+static CONSOLE_CTRL_CALLBACK: Mutex<Option<ConsoleCtrlCallback>> = Mutex::new(None);
+
+unsafe extern "system" fn console_ctrl_callback_handler(ctrl_type: u32) -> BOOL {
+    let Some(event) = ConsoleCtrlEvent::from_ctrl_type(ctrl_type) else {
+        return FALSE;
+    };
+    let callback = CONSOLE_CTRL_CALLBACK.lock().unwrap();
+    match callback.as_ref() {
+        Some(callback) => {
+            info!("Received console control event {:?}", event);
+            callback(event).into()
+        }
+        None => FALSE,
+    }
+}
+
+/// Runs `callback` for every console control event: Ctrl-C, Ctrl-Break, console close, logoff and shutdown.
+///
+/// Returning `true` marks the event as handled; returning `false` passes it to the next handler,
+/// ending with the default one that terminates the process.
+/// For [`ConsoleCtrlEvent::terminates_process`] events the process ends once the callback returns either way,
+/// so cleanup must finish inside the callback, within the few seconds Windows allows.
+/// Calling this again replaces the previous callback.
+/// The callback runs on a thread created by the system.
+pub fn attach_console_ctrl_callback(
+    callback: impl Fn(ConsoleCtrlEvent) -> bool + Send + Sync + 'static,
+) -> windows::core::Result<()> {
+    debug!("Attaching console ctrl callback");
+    let previous = CONSOLE_CTRL_CALLBACK
+        .lock()
+        .unwrap()
+        .replace(Box::new(callback));
+    if previous.is_none() {
+        unsafe { SetConsoleCtrlHandler(Some(console_ctrl_callback_handler), true)? };
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::ConsoleCtrlEvent;
+    use windows::Win32::System::Console::CTRL_BREAK_EVENT;
+    use windows::Win32::System::Console::CTRL_CLOSE_EVENT;
+
+    #[test]
+    fn maps_ctrl_types() {
+        assert_eq!(
+            ConsoleCtrlEvent::from_ctrl_type(CTRL_BREAK_EVENT),
+            Some(ConsoleCtrlEvent::CtrlBreak)
+        );
+        let close = ConsoleCtrlEvent::from_ctrl_type(CTRL_CLOSE_EVENT);
+        assert_eq!(close, Some(ConsoleCtrlEvent::Close));
+        assert!(close.is_some_and(|event| event.terminates_process()));
+        assert_eq!(ConsoleCtrlEvent::from_ctrl_type(42), None);
+    }
+}
@@ -0,0 +1,113 @@
+use crate::console::console_attach;
+use crate::console::console_create;
+use crate::console::console_detach;
+use crate::console::is_inheriting_console;
+use eyre::Context;
+use tracing::debug;
+use tracing::warn;
+use windows::Win32::System::Console::ATTACH_PARENT_PROCESS;
+use windows::Win32::System::Console::GetConsoleWindow;
+
+/// How the process is currently connected to a console.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConsoleMode {
+    /// No console is attached.
+    Detached,
+    /// Sharing the console of the parent process, e.g. a shell.
+    Inherited,
+    /// Attached to a console that only this process is using, e.g. from [`console_create`].
+    Owned,
+}
+
+impl ConsoleMode {
+    pub fn current() -> Self {
+        let hwnd = unsafe { GetConsoleWindow() };
+        if hwnd.is_invalid() {
+            ConsoleMode::Detached
+        } else if is_inheriting_console() {
+            ConsoleMode::Inherited
+        } else {
+            ConsoleMode::Owned
+        }
+    }
+}
+
+/// Captures the [`ConsoleMode`] on creation and restores it on drop.
+///
+/// An owned console can't be recovered once freed, so restoring [`ConsoleMode::Owned`] allocates a fresh one.
+#[must_use = "the previous console mode is restored when the guard is dropped"]
+pub struct ConsoleGuard {
+    previous: ConsoleMode,
+}
+
+impl ConsoleGuard {
+    pub fn new() -> Self {
+        let previous = ConsoleMode::current();
+        debug!(?previous, "Captured console mode");
+        Self { previous }
+    }
+
+    /// Allocates a new console for the lifetime of the guard, detaching from any current console first.
+    pub fn create() -> eyre::Result<Self> {
+        let guard = Self::new();
+        if guard.previous != ConsoleMode::Detached {
+            console_detach().wrap_err("Failed to detach before creating console")?;
+        }
+        console_create()?;
+        Ok(guard)
+    }
+
+    pub fn previous_mode(&self) -> ConsoleMode {
+        self.previous
+    }
+
+    fn restore(&self) -> eyre::Result<()> {
+        let current = ConsoleMode::current();
+        if current == self.previous {
+            return Ok(());
+        }
+        debug!(?current, previous = ?self.previous, "Restoring console mode");
+        if current != ConsoleMode::Detached {
+            console_detach()?;
+        }
+        match self.previous {
+            ConsoleMode::Detached => {}
+            ConsoleMode::Inherited => console_attach(ATTACH_PARENT_PROCESS)?,
+            ConsoleMode::Owned => console_create()?,
+        }
+        Ok(())
+    }
+}
+
+impl Default for ConsoleGuard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for ConsoleGuard {
+    fn drop(&mut self) {
+        if let Err(e) = self.restore() {
+            warn!(
+                "Failed to restore console mode {:?}: {:?}",
+                self.previous, e
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ConsoleGuard;
+    use super::ConsoleMode;
+
+    #[test]
+    fn unchanged_mode_is_a_no_op() {
+        let before = ConsoleMode::current();
+        {
+            let guard = ConsoleGuard::new();
+            assert_eq!(guard.previous_mode(), before);
+        }
+        assert_eq!(ConsoleMode::current(), before);
+    }
+}
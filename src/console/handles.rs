@@ -1,3 +1,4 @@
+use crate::console::invalidate_console_writer;
 use eyre::Context;
 use windows::Win32::Foundation::CloseHandle;
 use windows::Win32::Foundation::HANDLE;
@@ -28,13 +29,80 @@ pub fn get_console_output_handle() -> eyre::Result<HANDLE> {
     }
 }
 
+/// Returns the current STDERR handle, erroring if it's invalid.
+pub fn get_console_error_handle() -> eyre::Result<HANDLE> {
+    unsafe {
+        let handle =
+            GetStdHandle(STD_ERROR_HANDLE).wrap_err("Failed to get standard error handle")?;
+        if handle.is_invalid() {
+            Err(windows::core::Error::from_thread()).wrap_err("STD_ERROR_HANDLE is invalid")
+        } else {
+            Ok(handle)
+        }
+    }
+}
+
+/// The process's standard handles at some point in time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct StdHandles {
+    pub input: HANDLE,
+    pub output: HANDLE,
+    pub error: HANDLE,
+}
+
+impl StdHandles {
+    /// Reads the current standard handles; unset handles are null.
+    pub fn current() -> Self {
+        Self {
+            input: unsafe { GetStdHandle(STD_INPUT_HANDLE) }.unwrap_or_default(),
+            output: unsafe { GetStdHandle(STD_OUTPUT_HANDLE) }.unwrap_or_default(),
+            error: unsafe { GetStdHandle(STD_ERROR_HANDLE) }.unwrap_or_default(),
+        }
+    }
+
+    /// Installs these as the process's standard handles without closing the ones being replaced.
+    pub fn install(&self) -> eyre::Result<()> {
+        invalidate_console_writer();
+        unsafe { SetStdHandle(STD_INPUT_HANDLE, self.input) }.wrap_err("Failed to set STDIN")?;
+        unsafe { SetStdHandle(STD_OUTPUT_HANDLE, self.output) }.wrap_err("Failed to set STDOUT")?;
+        unsafe { SetStdHandle(STD_ERROR_HANDLE, self.error) }.wrap_err("Failed to set STDERR")?;
+        Ok(())
+    }
+}
+
 /// Rebinds STDOUT/STDERR/STDIN to the current console using CONOUT$/CONIN$.
 /// Closes previously set std handles to avoid keeping the console host alive.
 pub fn rebind_std_handles_to_console() -> eyre::Result<()> {
-    // Capture previous std handles so we can close them after switching
-    let prev_out = unsafe { GetStdHandle(STD_OUTPUT_HANDLE) }.unwrap_or_default();
-    let prev_err = unsafe { GetStdHandle(STD_ERROR_HANDLE) }.unwrap_or_default();
-    let prev_in = unsafe { GetStdHandle(STD_INPUT_HANDLE) }.unwrap_or_default();
+    let previous = bind_std_handles_to_console()?;
+    let current = StdHandles::current();
+
+    // Close previous handles if valid and different from new
+    if !previous.output.is_invalid() && previous.output != current.output {
+        let _ = unsafe { CloseHandle(previous.output) };
+    }
+    if !previous.error.is_invalid()
+        && previous.error != current.error
+        && previous.error != previous.output
+    {
+        let _ = unsafe { CloseHandle(previous.error) };
+    }
+    if !previous.input.is_invalid() && previous.input != current.input {
+        let _ = unsafe { CloseHandle(previous.input) };
+    }
+    Ok(())
+}
+
+/// Like [`rebind_std_handles_to_console`], but leaves the previous handles open and returns them
+/// so they can be reinstalled with [`StdHandles::install`].
+pub fn rebind_std_handles_to_console_keeping_previous() -> eyre::Result<StdHandles> {
+    bind_std_handles_to_console()
+}
+
+fn bind_std_handles_to_console() -> eyre::Result<StdHandles> {
+    invalidate_console_writer();
+
+    // Capture previous std handles so the caller can close or restore them after switching
+    let previous = StdHandles::current();
 
     // OUTPUT/ERROR → CONOUT$
     let conout = unsafe {
@@ -55,14 +123,6 @@ pub fn rebind_std_handles_to_console() -> eyre::Result<()> {
     unsafe { SetStdHandle(STD_ERROR_HANDLE, conout) }
         .wrap_err("Failed to set STDERR to CONOUT$")?;
 
-    // Close previous handles if valid and different from new
-    if !prev_out.is_invalid() && prev_out != conout {
-        let _ = unsafe { CloseHandle(prev_out) };
-    }
-    if !prev_err.is_invalid() && prev_err != conout {
-        let _ = unsafe { CloseHandle(prev_err) };
-    }
-
     // INPUT → CONIN$ (best-effort)
     if let Ok(conin) = unsafe {
         CreateFileW(
@@ -76,15 +136,14 @@ pub fn rebind_std_handles_to_console() -> eyre::Result<()> {
         )
     } {
         let _ = unsafe { SetStdHandle(STD_INPUT_HANDLE, conin) };
-        if !prev_in.is_invalid() && prev_in != conin {
-            let _ = unsafe { CloseHandle(prev_in) };
-        }
     }
-    Ok(())
+    Ok(previous)
 }
 
 /// Unbind and close current STD handles so the console host can close immediately when detaching.
 pub fn unbind_and_close_std_handles_for_detach() {
+    invalidate_console_writer();
+
     let out = unsafe { GetStdHandle(STD_OUTPUT_HANDLE) }.unwrap_or_default();
     let err = unsafe { GetStdHandle(STD_ERROR_HANDLE) }.unwrap_or_default();
     let inp = unsafe { GetStdHandle(STD_INPUT_HANDLE) }.unwrap_or_default();
@@ -0,0 +1,57 @@
+use tracing::debug;
+use windows::Win32::Foundation::HANDLE;
+use windows::Win32::System::RemoteDesktop::ProcessIdToSessionId;
+use windows::Win32::System::RemoteDesktop::WTSGetActiveConsoleSessionId;
+use windows::Win32::System::StationsAndDesktops::GetProcessWindowStation;
+use windows::Win32::System::StationsAndDesktops::GetUserObjectInformationW;
+use windows::Win32::System::StationsAndDesktops::UOI_FLAGS;
+use windows::Win32::System::StationsAndDesktops::USEROBJECTFLAGS;
+use windows::Win32::System::Threading::GetCurrentProcessId;
+use windows::Win32::UI::WindowsAndMessaging::WSF_VISIBLE;
+
+/// Best-effort detection of whether the process was started by the service control manager rather than a user.
+///
+/// Services run in session 0, which is never the interactive console session since Vista,
+/// and on a window station that can't display UI. Either signal is enough, since a service
+/// has no console to attach to and no desktop to show windows on.
+pub fn is_running_as_service() -> bool {
+    let process_id = unsafe { GetCurrentProcessId() };
+    let mut session_id = 0u32;
+    let session_known = unsafe { ProcessIdToSessionId(process_id, &mut session_id) }.is_ok();
+    let console_session_id = unsafe { WTSGetActiveConsoleSessionId() };
+    let in_service_session = session_known && session_id == 0 && session_id != console_session_id;
+
+    let interactive = is_window_station_interactive();
+
+    debug!(
+        session_id,
+        console_session_id, interactive, "Checked whether running as a service"
+    );
+    in_service_session || interactive == Some(false)
+}
+
+/// Whether the process window station is visible, or `None` if it couldn't be queried.
+fn is_window_station_interactive() -> Option<bool> {
+    let station = unsafe { GetProcessWindowStation() }.ok()?;
+    let mut flags = USEROBJECTFLAGS::default();
+    unsafe {
+        GetUserObjectInformationW(
+            HANDLE(station.0),
+            UOI_FLAGS,
+            Some(&raw mut flags as *mut _),
+            std::mem::size_of::<USEROBJECTFLAGS>() as u32,
+            None,
+        )
+    }
+    .ok()?;
+    Some(flags.dwFlags & WSF_VISIBLE as u32 != 0)
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn it_works() {
+        // Tests are run interactively, never from a service
+        assert!(!super::is_running_as_service());
+    }
+}
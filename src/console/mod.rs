@@ -6,17 +6,27 @@
 mod ansi_support;
 mod attach_to_existing;
 mod check_inheriting;
+mod console_writer;
 mod create;
 mod ctrl_c_handler;
 mod detach;
+mod guard;
 mod handles;
 mod init;
+mod is_service;
+#[cfg(feature = "tracing-subscriber")]
+mod tray_console;
 
 pub use ansi_support::*;
 pub use attach_to_existing::*;
 pub use check_inheriting::*;
+pub use console_writer::*;
 pub use create::*;
 pub use ctrl_c_handler::*;
 pub use detach::*;
+pub use guard::*;
 pub use handles::*;
 pub use init::*;
+pub use is_service::*;
+#[cfg(feature = "tracing-subscriber")]
+pub use tray_console::*;
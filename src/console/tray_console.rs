@@ -0,0 +1,126 @@
+use crate::console::ConsoleMode;
+use crate::console::OwnedConsole;
+use crate::console::console_attach;
+use crate::console::console_create_scoped;
+use crate::console::console_detach;
+use crate::log::BufferSink;
+use crate::log::ReplayStyle;
+use eyre::Context;
+use std::io::Write;
+use tracing::debug;
+use tracing::info;
+use windows::Win32::System::Console::ATTACH_PARENT_PROCESS;
+use windows::Win32::System::Console::GetConsoleWindow;
+use windows::Win32::UI::WindowsAndMessaging::SW_RESTORE;
+use windows::Win32::UI::WindowsAndMessaging::SetForegroundWindow;
+use windows::Win32::UI::WindowsAndMessaging::ShowWindow;
+
+/// The "Show logs" / "Hide logs" behaviour of a tray app.
+///
+/// Logs are hidden until [`show_logs`](TrayConsole::show_logs) allocates a dedicated console and replays
+/// everything captured by the [`BufferSink`] so far. Hiding frees that console and, if the app was
+/// launched from a shell, routes logs back to the parent console.
+///
+/// ```text
+/// Detached/Inherited --show_logs--> Owned --hide_logs--> Detached/Inherited
+/// ```
+#[derive(Debug)]
+pub struct TrayConsole {
+    mode: ConsoleMode,
+    owned_console: Option<OwnedConsole>,
+    inherited_console_available: bool,
+    log_buffer: BufferSink,
+}
+
+impl TrayConsole {
+    /// `inherited_console_available` should be captured with [`is_inheriting_console`](crate::console::is_inheriting_console)
+    /// at startup, before the default console is hidden.
+    pub fn new(log_buffer: BufferSink, inherited_console_available: bool) -> Self {
+        let mode = if inherited_console_available {
+            ConsoleMode::Inherited
+        } else {
+            ConsoleMode::Detached
+        };
+        Self {
+            mode,
+            owned_console: None,
+            inherited_console_available,
+            log_buffer,
+        }
+    }
+
+    pub fn mode(&self) -> ConsoleMode {
+        self.mode
+    }
+
+    pub fn can_show_logs(&self) -> bool {
+        self.mode != ConsoleMode::Owned
+    }
+
+    pub fn can_hide_logs(&self) -> bool {
+        self.mode == ConsoleMode::Owned
+    }
+
+    /// Allocates a dedicated console and replays the buffered logs into it. Does nothing if already shown.
+    pub fn show_logs(&mut self) -> eyre::Result<()> {
+        if !self.can_show_logs() {
+            debug!("Show logs requested while already owning console");
+            return Ok(());
+        }
+
+        if self.mode == ConsoleMode::Inherited {
+            console_detach().wrap_err("Failed to detach from inherited console")?;
+            self.mode = ConsoleMode::Detached;
+        }
+
+        self.owned_console =
+            Some(console_create_scoped().wrap_err("Failed to allocate dedicated console")?);
+        self.mode = ConsoleMode::Owned;
+        self.replay_buffer()
+            .wrap_err("Failed to replay buffered logs into new console")?;
+        info!("Console window allocated; new logs will stream live");
+        Ok(())
+    }
+
+    /// Frees the dedicated console, reattaching to the parent console if there was one. Does nothing if already hidden.
+    pub fn hide_logs(&mut self) -> eyre::Result<()> {
+        if !self.can_hide_logs() {
+            debug!("Hide logs requested while console is already hidden");
+            return Ok(());
+        }
+
+        // Frees the console and restores the std handles from before it was created
+        self.owned_console = None;
+        self.mode = ConsoleMode::Detached;
+        if self.inherited_console_available {
+            console_attach(ATTACH_PARENT_PROCESS)
+                .wrap_err("Failed to reattach to parent console")?;
+            self.mode = ConsoleMode::Inherited;
+            info!("Logs routed back to parent console");
+        } else {
+            info!("Logs hidden; no console attached");
+        }
+        Ok(())
+    }
+
+    /// Shows the logs and brings the console window to the front, e.g. when another instance is launched.
+    pub fn show_and_focus_logs(&mut self) -> eyre::Result<()> {
+        self.show_logs()?;
+        let console = unsafe { GetConsoleWindow() };
+        if !console.is_invalid() {
+            _ = unsafe { ShowWindow(console, SW_RESTORE) };
+            _ = unsafe { SetForegroundWindow(console) };
+        }
+        Ok(())
+    }
+
+    /// Writes everything captured by the log buffer to stdout, in color only if the console renders it.
+    pub fn replay_buffer(&self) -> eyre::Result<()> {
+        let mut stdout = std::io::stdout();
+        self.log_buffer
+            .replay_with_style(&mut stdout, ReplayStyle::for_stdout())
+            .wrap_err("Failed to write buffered logs to stdout")?;
+        stdout.flush().ok();
+        Ok(())
+    }
+}
@@ -432,6 +432,7 @@ fn acquire_daemon_lock(paths: &DaemonPaths) -> Result<File> {
     paths.ensure_instance_dir()?;
     let file = OpenOptions::new()
         .create(true)
+        .truncate(false)
         .read(true)
         .write(true)
         .share_mode(0)
@@ -612,12 +613,11 @@ fn remove_children_if_dir_exists(path: &Path) -> Result<()> {
     for entry in std::fs::read_dir(path)? {
         let entry = entry?;
         let child_path = entry.path();
-        if child_path.is_file() {
-            if let Err(err) = std::fs::remove_file(&child_path) {
-                if err.kind() != ErrorKind::NotFound {
-                    warn!(path = %child_path.display(), error = %err, "Failed to remove daemon child file during cleanup");
-                }
-            }
+        if child_path.is_file()
+            && let Err(err) = std::fs::remove_file(&child_path)
+            && err.kind() != ErrorKind::NotFound
+        {
+            warn!(path = %child_path.display(), error = %err, "Failed to remove daemon child file during cleanup");
         }
     }
 
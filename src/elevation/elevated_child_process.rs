@@ -1,21 +1,39 @@
 use eyre::eyre;
 use windows::Win32::Foundation::CloseHandle;
 use windows::Win32::Foundation::HANDLE;
+use windows::Win32::Foundation::WAIT_FAILED;
 use windows::Win32::System::Threading::GetExitCodeProcess;
 use windows::Win32::System::Threading::INFINITE;
 use windows::Win32::System::Threading::WaitForSingleObject;
 
+/// A process launched by [`crate::elevation::run_as_admin`]. The process handle is closed on drop.
 pub struct ElevatedChildProcess {
     pub h_process: HANDLE,
+    pub process_id: u32,
 }
 
 impl ElevatedChildProcess {
+    /// Blocks until the process exits and returns its exit code.
     pub fn wait(self) -> eyre::Result<u32> {
-        unsafe { WaitForSingleObject(self.h_process, INFINITE) };
+        let result = unsafe { WaitForSingleObject(self.h_process, INFINITE) };
+        if result == WAIT_FAILED {
+            return Err(eyre!(
+                "Failed to wait for elevated process {}: {}",
+                self.process_id,
+                windows::core::Error::from_thread()
+            ));
+        }
         let mut code = 0u32;
         unsafe { GetExitCodeProcess(self.h_process, &mut code) }
             .map_err(|e| eyre!("Failed to get exit code: {}", e))?;
-        unsafe { CloseHandle(self.h_process) }?;
         Ok(code)
     }
 }
+
+impl Drop for ElevatedChildProcess {
+    fn drop(&mut self) {
+        if !self.h_process.is_invalid() {
+            _ = unsafe { CloseHandle(self.h_process) };
+        }
+    }
+}
@@ -4,11 +4,29 @@ use eyre::bail;
 use tracing::info;
 use tracing::warn;
 
-/// Check if we're elevated, and relaunch if not
-pub fn ensure_elevated() -> eyre::Result<()> {
+/// What [`ensure_elevated`] does when the process is not elevated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ElevationPolicy {
+    /// Relaunch elevated with the same arguments and console, wait for it, then exit with its exit code.
+    #[default]
+    Relaunch,
+    /// Return an error without relaunching.
+    Error,
+}
+
+/// Check if we're elevated, and apply `policy` if not.
+///
+/// With [`ElevationPolicy::Relaunch`] this never returns in the non-elevated process:
+/// it calls [`std::process::exit`] once the elevated instance finishes, so destructors do not run.
+/// Call it at the top of `main` before acquiring resources.
+pub fn ensure_elevated(policy: ElevationPolicy) -> eyre::Result<()> {
     if is_elevated() {
         return Ok(());
     }
+    match policy {
+        ElevationPolicy::Error => bail!("Program needs to be run with elevated privileges."),
+        ElevationPolicy::Relaunch => {}
+    }
     warn!("Program needs to be run with elevated privileges.");
     info!("Relaunching as administrator...");
     match relaunch_as_admin() {
@@ -0,0 +1,71 @@
+use eyre::Context;
+use eyre::bail;
+use std::marker::PhantomData;
+use tracing::debug;
+use tracing::error;
+use windows::Win32::Foundation::HANDLE;
+use windows::Win32::Security::ImpersonateLoggedOnUser;
+use windows::Win32::Security::RevertToSelf;
+use windows::Win32::System::RemoteDesktop::WTSGetActiveConsoleSessionId;
+use windows::Win32::System::RemoteDesktop::WTSQueryUserToken;
+use windows::core::Owned;
+
+/// The calling thread impersonates a logged-on user until this is dropped, when it reverts to the process token.
+///
+/// Impersonation is per-thread, so the guard can't be sent to another thread.
+pub struct ImpersonationGuard {
+    token: Owned<HANDLE>,
+    session_id: u32,
+    _not_send: PhantomData<*const ()>,
+}
+
+impl ImpersonationGuard {
+    /// The primary token of the impersonated user, e.g. for `CreateProcessAsUserW`.
+    pub fn token(&self) -> HANDLE {
+        *self.token
+    }
+
+    pub fn session_id(&self) -> u32 {
+        self.session_id
+    }
+}
+
+impl Drop for ImpersonationGuard {
+    fn drop(&mut self) {
+        // Carrying on with the user's identity would run the rest of the thread under the wrong security context
+        if let Err(e) = unsafe { RevertToSelf() } {
+            error!("Failed to revert impersonation, aborting: {}", e);
+            std::process::abort();
+        }
+        debug!(session_id = self.session_id, "Reverted impersonation");
+    }
+}
+
+/// Impersonates the user logged on to `session_id` on the calling thread, so per-user state like their
+/// registry hive, AppData and network drives resolve as that user.
+///
+/// Only works from a process running as LocalSystem, such as a service in session 0.
+/// <https://learn.microsoft.com/en-us/windows/win32/api/wtsapi32/nf-wtsapi32-wtsqueryusertoken>
+pub fn impersonate_session_user(session_id: u32) -> eyre::Result<ImpersonationGuard> {
+    let mut raw_token = HANDLE::default();
+    unsafe { WTSQueryUserToken(session_id, &mut raw_token) }
+        .wrap_err_with(|| format!("Failed to query the user token for session {session_id}"))?;
+    let token = unsafe { Owned::new(raw_token) };
+    unsafe { ImpersonateLoggedOnUser(*token) }
+        .wrap_err_with(|| format!("Failed to impersonate the user of session {session_id}"))?;
+    debug!(session_id, "Impersonating session user");
+    Ok(ImpersonationGuard {
+        token,
+        session_id,
+        _not_send: PhantomData,
+    })
+}
+
+/// Impersonates the user at the physical console, see [`impersonate_session_user`].
+pub fn impersonate_console_user() -> eyre::Result<ImpersonationGuard> {
+    let session_id = unsafe { WTSGetActiveConsoleSessionId() };
+    if session_id == u32::MAX {
+        bail!("No session is attached to the physical console");
+    }
+    impersonate_session_user(session_id)
+}
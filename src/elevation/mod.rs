@@ -1,6 +1,7 @@
 mod backup_privilege;
 mod elevated_child_process;
 mod ensure_elevated;
+mod impersonate;
 mod is_elevated;
 mod relaunch_as_admin;
 mod run_as_admin;
@@ -8,6 +9,7 @@ mod run_as_admin;
 pub use backup_privilege::*;
 pub use elevated_child_process::*;
 pub use ensure_elevated::*;
+pub use impersonate::*;
 pub use is_elevated::*;
 pub use relaunch_as_admin::*;
 pub use run_as_admin::*;
@@ -1,8 +1,10 @@
 use crate::elevation::ElevatedChildProcess;
+use crate::invocation::CommandLine;
 use crate::invocation::Invocable;
 use crate::string::EasyPCWSTR;
 use eyre::Context;
-use std::ffi::OsString;
+use eyre::bail;
+use windows::Win32::System::Threading::GetProcessId;
 use windows::Win32::UI::Shell::SEE_MASK_NOCLOSEPROCESS;
 use windows::Win32::UI::Shell::SHELLEXECUTEINFOW;
 use windows::Win32::UI::Shell::ShellExecuteExW;
@@ -10,15 +12,8 @@ use windows::Win32::UI::WindowsAndMessaging::SW_SHOWNORMAL;
 
 /// Runs an invocable with administrative privileges using ShellExecuteExW.
 pub fn run_as_admin(invocable: &impl Invocable) -> eyre::Result<ElevatedChildProcess> {
-    // Build a single space-separated string of arguments
-    let params: OsString = invocable
-        .args()
-        .into_iter()
-        .fold(OsString::new(), |mut acc, arg| {
-            acc.push(arg);
-            acc.push(" ");
-            acc
-        });
+    // Quote the arguments so the elevated process parses them back unchanged
+    let params = CommandLine::from_invocable(invocable).args_to_os_string();
 
     // ---------------- ShellExecuteExW ----------------
     let verb = "runas".easy_pcwstr()?;
@@ -35,7 +30,17 @@ pub fn run_as_admin(invocable: &impl Invocable) -> eyre::Result<ElevatedChildPro
         ..Default::default()
     };
     unsafe { ShellExecuteExW(&mut sei) }.wrap_err("Failed to run as administrator")?;
+    if sei.hProcess.is_invalid() {
+        bail!("ShellExecuteExW did not return a process handle for the elevated process");
+    }
+    let process_id = unsafe { GetProcessId(sei.hProcess) };
     Ok(ElevatedChildProcess {
         h_process: sei.hProcess,
+        process_id,
     })
 }
+
+/// Runs an invocable with administrative privileges and waits for it, returning its exit code.
+pub fn run_as_admin_wait(invocable: &impl Invocable) -> eyre::Result<u32> {
+    run_as_admin(invocable)?.wait()
+}
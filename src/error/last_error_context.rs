@@ -0,0 +1,39 @@
+use windows::Win32::Foundation::GetLastError;
+use windows::Win32::Foundation::WIN32_ERROR;
+
+/// Describes the calling thread's last Win32 error, e.g. `The handle is invalid. (0x80070006)`.
+///
+/// Call it immediately after the failing API, before anything else can overwrite the thread's error code.
+/// <https://learn.microsoft.com/en-us/windows/win32/api/errhandlingapi/nf-errhandlingapi-getlasterror>
+pub fn last_error_context() -> String {
+    describe_win32_error(unsafe { GetLastError() })
+}
+
+/// Formats a Win32 error code with its system message and HRESULT.
+pub fn describe_win32_error(error: WIN32_ERROR) -> String {
+    if error.is_ok() {
+        return "no error code was set".to_string();
+    }
+    let hresult = error.to_hresult();
+    let message = hresult.message();
+    let message = message.trim();
+    if message.is_empty() {
+        format!("unknown error ({:#010X})", hresult.0 as u32)
+    } else {
+        format!("{message} ({:#010X})", hresult.0 as u32)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::describe_win32_error;
+    use windows::Win32::Foundation::ERROR_INVALID_HANDLE;
+    use windows::Win32::Foundation::ERROR_SUCCESS;
+
+    #[test]
+    fn includes_code() {
+        let described = describe_win32_error(ERROR_INVALID_HANDLE);
+        assert!(described.ends_with("(0x80070006)"), "{described}");
+        assert_eq!(describe_win32_error(ERROR_SUCCESS), "no error code was set");
+    }
+}
@@ -0,0 +1,3 @@
+mod last_error_context;
+
+pub use last_error_context::*;
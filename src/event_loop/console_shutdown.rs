@@ -0,0 +1,66 @@
+use crate::console::ConsoleCtrlEvent;
+use crate::console::attach_console_ctrl_callback;
+use eyre::Context;
+use tracing::info;
+use tracing::warn;
+use windows::Win32::Foundation::HWND;
+use windows::Win32::Foundation::LPARAM;
+use windows::Win32::Foundation::WPARAM;
+use windows::Win32::UI::WindowsAndMessaging::PostMessageW;
+use windows::Win32::UI::WindowsAndMessaging::SMTO_ABORTIFHUNG;
+use windows::Win32::UI::WindowsAndMessaging::SendMessageTimeoutW;
+use windows::Win32::UI::WindowsAndMessaging::WM_CLOSE;
+
+/// How long a console close, logoff or shutdown waits for the window to tear down.
+/// Windows terminates the process shortly after anyway, about 5 seconds for a console close.
+const CLOSE_TIMEOUT_MS: u32 = 4_000;
+
+/// Closes `hwnd` when the console asks the process to stop, so [`crate::event_loop::run_message_loop`]
+/// exits through the window's normal `WM_CLOSE` → `WM_DESTROY` teardown instead of the process being killed
+/// with its tray icon still registered.
+///
+/// Ctrl-C and Ctrl-Break post `WM_CLOSE` and return immediately.
+/// Console close, logoff and shutdown end the process as soon as the handler returns, so those send
+/// `WM_CLOSE` and wait for the window to process it.
+/// The window's `WM_DESTROY` handler is expected to call `PostQuitMessage`.
+pub fn close_window_on_console_ctrl(hwnd: HWND) -> eyre::Result<()> {
+    // HWND isn't Send, and the handler runs on a thread created by the system
+    let hwnd_bits = hwnd.0 as isize;
+    attach_console_ctrl_callback(move |event| {
+        let hwnd = HWND(hwnd_bits as *mut _);
+        info!(?event, "Closing window for console control event");
+        if event.terminates_process() {
+            send_close(hwnd, event)
+        } else {
+            match unsafe { PostMessageW(Some(hwnd), WM_CLOSE, WPARAM(0), LPARAM(0)) } {
+                Ok(()) => true,
+                Err(e) => {
+                    warn!("Failed to post WM_CLOSE for {:?}: {}", event, e);
+                    false
+                }
+            }
+        }
+    })
+    .wrap_err("Failed to attach console control handler")
+}
+
+fn send_close(hwnd: HWND, event: ConsoleCtrlEvent) -> bool {
+    let sent = unsafe {
+        SendMessageTimeoutW(
+            hwnd,
+            WM_CLOSE,
+            WPARAM(0),
+            LPARAM(0),
+            SMTO_ABORTIFHUNG,
+            CLOSE_TIMEOUT_MS,
+            None,
+        )
+    };
+    if sent.0 == 0 {
+        warn!(
+            "Window did not finish closing for {:?} within {} ms",
+            event, CLOSE_TIMEOUT_MS
+        );
+    }
+    true
+}
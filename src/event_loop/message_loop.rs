@@ -1,17 +1,38 @@
+use crate::error::last_error_context;
+use eyre::bail;
 use tracing::debug;
 use windows::Win32::Foundation::HWND;
 use windows::Win32::UI::WindowsAndMessaging::DispatchMessageW;
 use windows::Win32::UI::WindowsAndMessaging::GetMessageW;
+use windows::Win32::UI::WindowsAndMessaging::IsWindow;
 use windows::Win32::UI::WindowsAndMessaging::MSG;
 use windows::Win32::UI::WindowsAndMessaging::TranslateMessage;
 
 /// Pump the message loop for the given window handle, or all windows if None is provided.
+///
+/// Returns when `WM_QUIT` is received, or once the given window has been destroyed.
+/// A loop filtered to one window never sees the thread's `WM_QUIT`, and `GetMessageW` fails for a destroyed window.
 pub fn run_message_loop(hwnd: Option<HWND>) -> eyre::Result<()> {
     let mut msg = MSG::default();
     debug!("Starting message loop");
-    while unsafe { GetMessageW(&mut msg, hwnd, 0, 0) }.into() {
-        let _ = unsafe { TranslateMessage(&msg) };
-        unsafe { DispatchMessageW(&msg) };
+    loop {
+        let result = unsafe { GetMessageW(&mut msg, hwnd, 0, 0) };
+        match result.0 {
+            0 => break,
+            -1 => {
+                if let Some(hwnd) = hwnd
+                    && !unsafe { IsWindow(Some(hwnd)) }.as_bool()
+                {
+                    debug!("Window destroyed, ending message loop");
+                    break;
+                }
+                bail!("GetMessageW failed: {}", last_error_context());
+            }
+            _ => {
+                let _ = unsafe { TranslateMessage(&msg) };
+                unsafe { DispatchMessageW(&msg) };
+            }
+        }
     }
     Ok(())
 }
@@ -1,3 +1,5 @@
+mod console_shutdown;
 mod message_loop;
 
+pub use console_shutdown::*;
 pub use message_loop::*;
@@ -1,10 +1,27 @@
+use crate::hicon::OwnedHicon;
 use crate::module::get_current_module;
+use crate::string::EasyPCWSTR;
+use eyre::Context;
 use eyre::bail;
+use std::path::Path;
 use tracing::debug;
 use tracing::instrument;
+use windows::Win32::Foundation::ERROR_RESOURCE_TYPE_NOT_FOUND;
 use windows::Win32::Foundation::HINSTANCE;
+use windows::Win32::Foundation::HMODULE;
+use windows::Win32::Foundation::TRUE;
+use windows::Win32::System::LibraryLoader::EnumResourceNamesW;
+use windows::Win32::System::LibraryLoader::LOAD_LIBRARY_AS_DATAFILE;
+use windows::Win32::System::LibraryLoader::LoadLibraryExW;
 use windows::Win32::UI::WindowsAndMessaging::HICON;
+use windows::Win32::UI::WindowsAndMessaging::IMAGE_ICON;
+use windows::Win32::UI::WindowsAndMessaging::LR_DEFAULTCOLOR;
+use windows::Win32::UI::WindowsAndMessaging::LR_DEFAULTSIZE;
 use windows::Win32::UI::WindowsAndMessaging::LoadIconW;
+use windows::Win32::UI::WindowsAndMessaging::LoadImageW;
+use windows::Win32::UI::WindowsAndMessaging::RT_GROUP_ICON;
+use windows::core::BOOL;
+use windows::core::Owned;
 use windows::core::PCWSTR;
 use windows::core::Param;
 
@@ -23,3 +40,123 @@ pub fn get_icon_from_current_module(
         Err(e) => bail!("Failed to load embedded icon from current module: {}", e),
     }
 }
+
+/// Loads a named icon resource out of another exe or dll without running any of its code.
+///
+/// The module is mapped with `LOAD_LIBRARY_AS_DATAFILE` and freed before returning; the icon is an independent copy.
+/// A `size` of 0 uses the system default icon size.
+#[instrument]
+pub fn get_icon_from_module(path: &Path, name: PCWSTR, size: u32) -> eyre::Result<OwnedHicon> {
+    let raw_module =
+        unsafe { LoadLibraryExW(path.easy_pcwstr()?.as_ref(), None, LOAD_LIBRARY_AS_DATAFILE) }
+            .wrap_err_with(|| format!("Failed to load module {}", path.display()))?;
+    let module = unsafe { Owned::new(raw_module) };
+    debug!(module = ?*module, "Loaded module as datafile");
+
+    let size = i32::try_from(size)?;
+    let flags = if size == 0 {
+        LR_DEFAULTCOLOR | LR_DEFAULTSIZE
+    } else {
+        LR_DEFAULTCOLOR
+    };
+    let handle = unsafe {
+        LoadImageW(
+            Some(HINSTANCE::from(*module)),
+            name,
+            IMAGE_ICON,
+            size,
+            size,
+            flags,
+        )
+    }
+    .wrap_err_with(|| format!("Failed to load icon from module {}", path.display()))?;
+    Ok(unsafe { OwnedHicon::new(HICON(handle.0)) })
+}
+
+/// Lists the names of the icon group resources embedded in the current module.
+///
+/// Integer resource IDs are returned as `#<id>`, which `LoadIconW` and friends accept as a name.
+/// <https://learn.microsoft.com/en-us/windows/win32/api/libloaderapi/nf-libloaderapi-enumresourcenamesw>
+#[instrument]
+pub fn list_embedded_icons() -> eyre::Result<Vec<String>> {
+    let handle = get_current_module()?;
+    let mut names: Vec<String> = Vec::new();
+    let ok = unsafe {
+        EnumResourceNamesW(
+            Some(handle),
+            RT_GROUP_ICON,
+            Some(collect_resource_name),
+            &raw mut names as isize,
+        )
+    };
+    if !ok.as_bool() {
+        let error = windows::core::Error::from_thread();
+        // A module without any icon resources isn't an error for discovery purposes
+        if error.code() != ERROR_RESOURCE_TYPE_NOT_FOUND.to_hresult() {
+            bail!("Failed to enumerate embedded icons: {}", error);
+        }
+    }
+    debug!(?names, "Enumerated embedded icons in current module");
+    Ok(names)
+}
+
+unsafe extern "system" fn collect_resource_name(
+    _module: HMODULE,
+    _resource_type: PCWSTR,
+    name: PCWSTR,
+    lparam: isize,
+) -> BOOL {
+    let names = unsafe { &mut *(lparam as *mut Vec<String>) };
+    // IS_INTRESOURCE: ids are smuggled through the pointer's low word
+    if (name.0 as usize) >> 16 == 0 {
+        names.push(format!("#{}", name.0 as usize));
+    } else {
+        match unsafe { name.to_string() } {
+            Ok(name) => names.push(name),
+            Err(e) => debug!(?e, "Skipping icon resource with an invalid name"),
+        }
+    }
+    TRUE
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn lists_embedded_icons() -> eyre::Result<()> {
+        let names = super::list_embedded_icons()?;
+        println!("Embedded icons: {:?}", names);
+
+        // Every listed name must load back from the module it was found in
+        let exe = std::env::current_exe()?;
+        for name in &names {
+            let icon = match name.strip_prefix('#') {
+                Some(id) => {
+                    let id: usize = id.parse()?;
+                    let name = windows::core::PCWSTR(id as *const u16);
+                    super::get_icon_from_module(&exe, name, 0)?
+                }
+                None => {
+                    let name = widestring::U16CString::from_str(name)?;
+                    let name = windows::core::PCWSTR(name.as_ptr());
+                    super::get_icon_from_module(&exe, name, 0)?
+                }
+            };
+            assert!(!icon.as_raw().is_invalid());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn loads_icon_from_shell32() -> eyre::Result<()> {
+        let system_root = std::env::var("SystemRoot").unwrap_or_else(|_| "C:\\Windows".to_string());
+        let path = std::path::PathBuf::from(system_root)
+            .join("System32")
+            .join("shell32.dll");
+        // Icon group 4 is the folder icon
+        let name = windows::core::PCWSTR(4usize as *const u16);
+        let icon = super::get_icon_from_module(&path, name, 32)?;
+        let image = unsafe { crate::hicon::hicon_to_rgba(icon.as_raw()) }?;
+        assert_eq!(image.dimensions(), (32, 32));
+        Ok(())
+    }
+}
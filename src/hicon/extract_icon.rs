@@ -0,0 +1,91 @@
+use crate::hicon::OwnedHicon;
+use crate::hicon::hicon_to_rgba;
+use crate::string::EasyPCWSTR;
+use image::RgbaImage;
+use std::path::Path;
+use windows::Win32::UI::Shell::ExtractIconExW;
+use windows::Win32::UI::WindowsAndMessaging::HICON;
+use windows::Win32::UI::WindowsAndMessaging::PrivateExtractIconsW;
+
+/// Number of icons in an exe, dll or ico file.
+pub fn get_icon_count(path: &Path) -> eyre::Result<u32> {
+    let path_str = path.to_string_lossy();
+    let pcwstr = path_str.as_ref().easy_pcwstr()?;
+
+    // Pass -1 as nIconIndex and NULL for both icon arrays to get the count
+    let count = unsafe { ExtractIconExW(pcwstr.as_ref(), -1, None, None, 0) };
+
+    Ok(count)
+}
+
+/// Extracts the icon at `index` from an exe, dll or ico file at the requested pixel size.
+pub fn extract_icon_rgba(path: &Path, index: u32, size: u32) -> eyre::Result<RgbaImage> {
+    let path_str = path.to_string_lossy();
+
+    // PrivateExtractIconsW requires a fixed-size buffer of 260 u16s
+    let mut filename_buf: [u16; 260] = [0; 260];
+    for (i, c) in path_str.encode_utf16().take(259).enumerate() {
+        filename_buf[i] = c;
+    }
+
+    let mut icons: [HICON; 1] = [HICON::default()];
+    let mut icon_id: u32 = 0;
+
+    // Use PrivateExtractIconsW to extract icon at specific size
+    let extracted = unsafe {
+        PrivateExtractIconsW(
+            &filename_buf,
+            index as i32,
+            size as i32,
+            size as i32,
+            Some(&mut icons),
+            Some(&raw mut icon_id),
+            1,
+        )
+    };
+
+    if extracted == 0 || icons[0].is_invalid() {
+        // Fallback to ExtractIconExW for 32x32 icons
+        if size == 32 {
+            return extract_icon_rgba_fallback(path, index);
+        }
+        eyre::bail!(
+            "Failed to extract icon at index {} with size {}",
+            index,
+            size
+        );
+    }
+
+    // The icon handle is destroyed when the guard drops
+    let icon = unsafe { OwnedHicon::new(icons[0]) };
+    unsafe { hicon_to_rgba(icon.as_raw()) }
+}
+
+/// Fallback using ExtractIconExW which works better for some DLLs
+fn extract_icon_rgba_fallback(path: &Path, index: u32) -> eyre::Result<RgbaImage> {
+    let path_str = path.to_string_lossy();
+    let pcwstr = path_str.as_ref().easy_pcwstr()?;
+
+    let mut large_icon: HICON = HICON::default();
+
+    let extracted = unsafe {
+        ExtractIconExW(
+            pcwstr.as_ref(),
+            index as i32,
+            Some(&mut large_icon),
+            None,
+            1,
+        )
+    };
+
+    if extracted == 0 || large_icon.is_invalid() {
+        eyre::bail!(
+            "Failed to extract icon at index {} using ExtractIconExW",
+            index
+        );
+    }
+
+    // The icon handle is destroyed when the guard drops
+    let icon = unsafe { OwnedHicon::new(large_icon) };
+    unsafe { hicon_to_rgba(icon.as_raw()) }
+}
@@ -0,0 +1,278 @@
+use crate::error::last_error_context;
+use crate::hicon::ReleaseDCGuard;
+use eyre::ensure;
+use eyre::eyre;
+use image::Rgba;
+use image::RgbaImage;
+use windows::Win32::Graphics::Gdi::BI_RGB;
+use windows::Win32::Graphics::Gdi::BITMAP;
+use windows::Win32::Graphics::Gdi::BITMAPINFO;
+use windows::Win32::Graphics::Gdi::BITMAPINFOHEADER;
+use windows::Win32::Graphics::Gdi::DIB_RGB_COLORS;
+use windows::Win32::Graphics::Gdi::GetDC;
+use windows::Win32::Graphics::Gdi::GetDIBits;
+use windows::Win32::Graphics::Gdi::GetObjectW;
+use windows::Win32::Graphics::Gdi::HBITMAP;
+use windows::Win32::Graphics::Gdi::HDC;
+use windows::Win32::Graphics::Gdi::HGDIOBJ;
+use windows::Win32::Graphics::Gdi::RGBQUAD;
+
+/// Reads the dimensions and format of a bitmap.
+/// <https://learn.microsoft.com/en-us/windows/win32/api/wingdi/nf-wingdi-getobjectw>
+pub fn hbitmap_info(hbitmap: HBITMAP) -> eyre::Result<BITMAP> {
+    let mut bitmap = BITMAP::default();
+    ensure!(
+        unsafe {
+            GetObjectW(
+                HGDIOBJ::from(hbitmap),
+                std::mem::size_of::<BITMAP>() as i32,
+                Some(&raw mut bitmap as *mut _),
+            )
+        } != 0,
+        "GetObjectW failed to get bitmap info: {}",
+        last_error_context()
+    );
+    Ok(bitmap)
+}
+
+/// Converts a color bitmap to RGBA pixels.
+///
+/// 32bpp bitmaps keep their alpha channel unless it is entirely zero, which is how pre-XP icons
+/// without alpha come back from `GetIconInfo`. Shallower bitmaps have no alpha and come out opaque,
+/// with 1/4/8bpp pixels looked up in the bitmap's own color table.
+/// The bitmap must not be selected into a device context.
+/// <https://learn.microsoft.com/en-us/windows/win32/api/wingdi/nf-wingdi-getdibits>
+pub fn hbitmap_to_rgba(hbitmap: HBITMAP) -> eyre::Result<RgbaImage> {
+    let bitmap = hbitmap_info(hbitmap)?;
+    let width = u32::try_from(bitmap.bmWidth)?;
+    let height = u32::try_from(bitmap.bmHeight)?;
+    ensure!(width > 0, "Bitmap width must not be zero");
+    ensure!(height > 0, "Bitmap height must not be zero");
+
+    let screen_device_context = ReleaseDCGuard(unsafe { GetDC(None) });
+    if bitmap.bmBitsPixel <= 8 {
+        return palettized_hbitmap_to_rgba(
+            *screen_device_context,
+            hbitmap,
+            width,
+            height,
+            bitmap.bmBitsPixel,
+        );
+    }
+
+    let mut bitmap_info = BITMAPINFO::default();
+    bitmap_info.bmiHeader.biSize = std::mem::size_of::<BITMAPINFOHEADER>() as u32;
+    bitmap_info.bmiHeader.biWidth = width as i32;
+    bitmap_info.bmiHeader.biHeight = -(height as i32); // top-down
+    bitmap_info.bmiHeader.biPlanes = 1;
+    bitmap_info.bmiHeader.biBitCount = 32;
+    bitmap_info.bmiHeader.biCompression = BI_RGB.0;
+
+    let mut image_data = vec![0u8; (width * height * 4) as usize];
+    ensure!(
+        unsafe {
+            GetDIBits(
+                *screen_device_context,
+                hbitmap,
+                0,
+                height,
+                Some(image_data.as_mut_ptr() as *mut _),
+                &mut bitmap_info,
+                DIB_RGB_COLORS,
+            ) != 0
+        },
+        "GetDIBits failed to get bitmap bits: {}",
+        last_error_context()
+    );
+
+    let has_alpha =
+        bitmap.bmBitsPixel == 32 && image_data.chunks_exact(4).any(|pixel| pixel[3] != 0);
+    for pixel in image_data.chunks_exact_mut(4) {
+        pixel.swap(0, 2); // BGRA to RGBA
+        if !has_alpha {
+            pixel[3] = 255;
+        }
+    }
+
+    RgbaImage::from_raw(width, height, image_data).ok_or_else(|| {
+        eyre!(
+            "Failed to create RgbaImage from raw data with width {} and height {}",
+            width,
+            height
+        )
+    })
+}
+
+/// A `BITMAPINFO` with room for the largest color table, which `GetDIBits` fills in for 1/4/8bpp requests.
+#[repr(C)]
+pub(crate) struct PalettizedBitmapInfo {
+    pub(crate) header: BITMAPINFOHEADER,
+    pub(crate) colors: [RGBQUAD; 256],
+}
+
+/// Reads a 1/4/8bpp bitmap's palette indices at their native depth and resolves them through its color table.
+fn palettized_hbitmap_to_rgba(
+    device_context: HDC,
+    hbitmap: HBITMAP,
+    width: u32,
+    height: u32,
+    bits_per_pixel: u16,
+) -> eyre::Result<RgbaImage> {
+    let mut bitmap_info = PalettizedBitmapInfo {
+        header: BITMAPINFOHEADER {
+            biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+            biWidth: width as i32,
+            biHeight: -(height as i32), // top-down
+            biPlanes: 1,
+            biBitCount: bits_per_pixel,
+            biCompression: BI_RGB.0,
+            ..Default::default()
+        },
+        colors: [RGBQUAD::default(); 256],
+    };
+
+    // Rows of a DIB are DWORD aligned
+    let row_size_bytes = (width * u32::from(bits_per_pixel)).div_ceil(32) * 4;
+    let mut index_data = vec![0u8; (row_size_bytes * height) as usize];
+    ensure!(
+        unsafe {
+            GetDIBits(
+                device_context,
+                hbitmap,
+                0,
+                height,
+                Some(index_data.as_mut_ptr() as *mut _),
+                &raw mut bitmap_info as *mut BITMAPINFO,
+                DIB_RGB_COLORS,
+            ) != 0
+        },
+        "GetDIBits failed to get palettized bitmap bits: {}",
+        last_error_context()
+    );
+
+    let palette = bitmap_info.colors;
+    let pixels_per_byte = 8 / u32::from(bits_per_pixel);
+    let index_mask = (1u16 << bits_per_pixel) - 1;
+    Ok(RgbaImage::from_fn(width, height, |x, y| {
+        let byte = index_data[(y * row_size_bytes + x / pixels_per_byte) as usize];
+        // The leftmost pixel is in the most significant bits
+        let shift = (pixels_per_byte - 1 - x % pixels_per_byte) * u32::from(bits_per_pixel);
+        let index = (u16::from(byte) >> shift) & index_mask;
+        let color = palette[index as usize];
+        Rgba([color.rgbRed, color.rgbGreen, color.rgbBlue, 255])
+    }))
+}
+
+#[cfg(test)]
+mod test {
+    use super::PalettizedBitmapInfo;
+    use super::hbitmap_to_rgba;
+    use crate::hicon::OwnedHbitmap;
+    use crate::hicon::OwnedHicon;
+    use crate::hicon::hicon_to_rgba;
+    use std::ffi::c_void;
+    use windows::Win32::Foundation::TRUE;
+    use windows::Win32::Graphics::Gdi::BI_RGB;
+    use windows::Win32::Graphics::Gdi::BITMAPINFO;
+    use windows::Win32::Graphics::Gdi::BITMAPINFOHEADER;
+    use windows::Win32::Graphics::Gdi::CreateBitmap;
+    use windows::Win32::Graphics::Gdi::CreateDIBSection;
+    use windows::Win32::Graphics::Gdi::DIB_RGB_COLORS;
+    use windows::Win32::Graphics::Gdi::RGBQUAD;
+    use windows::Win32::UI::WindowsAndMessaging::CreateIconIndirect;
+    use windows::Win32::UI::WindowsAndMessaging::ICONINFO;
+
+    const BLACK: [u8; 4] = [0, 0, 0, 255];
+    const RED: [u8; 4] = [255, 0, 0, 255];
+    const GREEN: [u8; 4] = [0, 255, 0, 255];
+    const BLUE: [u8; 4] = [0, 0, 255, 255];
+
+    /// A 4x2 16-color bitmap like a Win9x-era icon, with pixel indices
+    /// `[1, 2, 3, 0]` and `[0, 0, 0, 1]` into a palette of black, red, green and blue.
+    fn four_bpp_bitmap() -> eyre::Result<OwnedHbitmap> {
+        let mut colors = [RGBQUAD::default(); 256];
+        colors[1].rgbRed = 255;
+        colors[2].rgbGreen = 255;
+        colors[3].rgbBlue = 255;
+        let bitmap_info = PalettizedBitmapInfo {
+            header: BITMAPINFOHEADER {
+                biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+                biWidth: 4,
+                biHeight: -2, // top-down
+                biPlanes: 1,
+                biBitCount: 4,
+                biCompression: BI_RGB.0,
+                biClrUsed: 16,
+                ..Default::default()
+            },
+            colors,
+        };
+        let mut bits: *mut c_void = std::ptr::null_mut();
+        let raw = unsafe {
+            CreateDIBSection(
+                None,
+                &raw const bitmap_info as *const BITMAPINFO,
+                DIB_RGB_COLORS,
+                &mut bits,
+                None,
+                0,
+            )
+        }?;
+        let hbitmap = unsafe { OwnedHbitmap::new(raw) };
+        // Two pixels per byte, rows DWORD aligned
+        let rows: [u8; 8] = [0x12, 0x30, 0, 0, 0x00, 0x01, 0, 0];
+        unsafe { std::ptr::copy_nonoverlapping(rows.as_ptr(), bits as *mut u8, rows.len()) };
+        Ok(hbitmap)
+    }
+
+    #[test]
+    fn converts_palettized_bitmap() -> eyre::Result<()> {
+        let hbitmap = four_bpp_bitmap()?;
+        let image = hbitmap_to_rgba(hbitmap.as_raw())?;
+        assert_eq!(image.dimensions(), (4, 2));
+        let row = |y| (0..4).map(|x| image.get_pixel(x, y).0).collect::<Vec<_>>();
+        assert_eq!(row(0), [RED, GREEN, BLUE, BLACK]);
+        assert_eq!(row(1), [BLACK, BLACK, BLACK, RED]);
+        Ok(())
+    }
+
+    #[test]
+    fn converts_palettized_icon() -> eyre::Result<()> {
+        let color = four_bpp_bitmap()?;
+        // AND mask with WORD-aligned rows, making the top-right pixel transparent
+        let mask_bits: [u8; 4] = [0x10, 0, 0, 0];
+        let mask = unsafe { CreateBitmap(4, 2, 1, 1, Some(mask_bits.as_ptr() as *const _)) };
+        let mask = unsafe { OwnedHbitmap::new(mask) };
+
+        let icon_info = ICONINFO {
+            fIcon: TRUE,
+            hbmMask: mask.as_raw(),
+            hbmColor: color.as_raw(),
+            ..Default::default()
+        };
+        let hicon = unsafe { CreateIconIndirect(&icon_info) }?;
+        let hicon = unsafe { OwnedHicon::new(hicon) };
+
+        let image = unsafe { hicon_to_rgba(hicon.as_raw()) }?;
+        assert_eq!(image.get_pixel(0, 0).0, RED);
+        assert_eq!(image.get_pixel(2, 0).0, BLUE);
+        assert_eq!(image.get_pixel(3, 0).0[3], 0);
+        assert_eq!(image.get_pixel(3, 1).0, RED);
+        Ok(())
+    }
+
+    #[test]
+    fn converts_32bpp_bitmap() -> eyre::Result<()> {
+        // BGRA, one row of two pixels
+        let bits: [u8; 8] = [255, 0, 0, 255, 0, 0, 255, 128];
+        let raw = unsafe { CreateBitmap(2, 1, 1, 32, Some(bits.as_ptr() as *const _)) };
+        let hbitmap = unsafe { OwnedHbitmap::new(raw) };
+        assert!(!hbitmap.is_invalid());
+
+        let image = hbitmap_to_rgba(hbitmap.as_raw())?;
+        assert_eq!(image.dimensions(), (2, 1));
+        assert_eq!(image.get_pixel(0, 0).0, [0, 0, 255, 255]);
+        assert_eq!(image.get_pixel(1, 0).0, [255, 0, 0, 128]);
+        Ok(())
+    }
+}
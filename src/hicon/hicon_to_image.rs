@@ -1,9 +1,12 @@
+use crate::error::last_error_context;
+use crate::hicon::OwnedHbitmap;
+use crate::hicon::PalettizedBitmapInfo;
+use crate::hicon::hbitmap_info;
+use crate::hicon::hbitmap_to_rgba;
 use eyre::ensure;
-use eyre::eyre;
 use image::RgbaImage;
 use std::ops::Deref;
 use windows::Win32::Graphics::Gdi::BI_RGB;
-use windows::Win32::Graphics::Gdi::BITMAP;
 use windows::Win32::Graphics::Gdi::BITMAPINFO;
 use windows::Win32::Graphics::Gdi::BITMAPINFOHEADER;
 use windows::Win32::Graphics::Gdi::CreateCompatibleDC;
@@ -11,15 +14,15 @@ use windows::Win32::Graphics::Gdi::DIB_RGB_COLORS;
 use windows::Win32::Graphics::Gdi::DeleteDC;
 use windows::Win32::Graphics::Gdi::GetDC;
 use windows::Win32::Graphics::Gdi::GetDIBits;
-use windows::Win32::Graphics::Gdi::GetObjectW;
+use windows::Win32::Graphics::Gdi::HBITMAP;
 use windows::Win32::Graphics::Gdi::HDC;
 use windows::Win32::Graphics::Gdi::HGDIOBJ;
+use windows::Win32::Graphics::Gdi::RGBQUAD;
 use windows::Win32::Graphics::Gdi::ReleaseDC;
 use windows::Win32::Graphics::Gdi::SelectObject;
 use windows::Win32::UI::WindowsAndMessaging::GetIconInfo;
 use windows::Win32::UI::WindowsAndMessaging::HICON;
 use windows::Win32::UI::WindowsAndMessaging::ICONINFO;
-use windows::core::Owned;
 
 /// # Safety
 ///
@@ -37,137 +40,141 @@ pub unsafe fn hicon_to_rgba(hicon: HICON) -> eyre::Result<RgbaImage> {
     } = icon_info;
 
     // Move into RAII guards for automatic cleanup
-    let hbm_mask = unsafe { Owned::new(hbmMask) };
-    let hbm_color = unsafe { Owned::new(hbmColor) };
+    let hbm_mask = unsafe { OwnedHbitmap::new(hbmMask) };
+    let hbm_color = unsafe { OwnedHbitmap::new(hbmColor) };
 
-    // Get bitmap info for hbmColor
-    let mut bitmap = BITMAP::default();
+    // Monochrome icons and cursors have no color bitmap
+    if hbm_color.is_invalid() {
+        return monochrome_mask_to_rgba(hbm_mask.as_raw());
+    }
+
+    let mut image = hbitmap_to_rgba(hbm_color.as_raw())?;
+    if hbm_mask.is_invalid() || hbm_mask.as_raw() == hbm_color.as_raw() {
+        return Ok(image);
+    }
+
+    // Apply the AND mask. A set bit means transparent; a clear bit keeps the color bitmap's alpha,
+    // which hbitmap_to_rgba already made opaque for bitmaps without an alpha channel.
+    let (width, height) = image.dimensions();
+    let screen_device_context = ReleaseDCGuard(unsafe { GetDC(None) });
+    // GetDIBits writes the mask's two-color table after the header
+    let mut mask_bitmap_info = PalettizedBitmapInfo {
+        header: BITMAPINFOHEADER {
+            biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+            biWidth: width as i32,
+            biHeight: -(height as i32), // top-down
+            biPlanes: 1,
+            biBitCount: 1, // 1-bit mask/per pixel
+            biCompression: BI_RGB.0,
+            ..Default::default()
+        },
+        colors: [RGBQUAD::default(); 256],
+    };
+
+    // Rows of a 1bpp DIB are DWORD aligned
+    let row_size_bytes = width.div_ceil(32) * 4;
+    let mut mask_pixel_data = vec![0u8; (row_size_bytes * height) as usize];
     ensure!(
         unsafe {
-            GetObjectW(
-                HGDIOBJ::from(*hbm_color),
-                std::mem::size_of::<BITMAP>() as i32,
-                Some(&raw mut bitmap as *mut _),
-            )
-        } != 0,
-        "GetObjectW failed to get bitmap info"
+            GetDIBits(
+                *screen_device_context,
+                hbm_mask.as_raw(),
+                0,
+                height,
+                Some(mask_pixel_data.as_mut_ptr() as *mut _),
+                &raw mut mask_bitmap_info as *mut BITMAPINFO,
+                DIB_RGB_COLORS,
+            ) != 0
+        },
+        "GetDIBits failed to get mask bitmap bits: {}",
+        last_error_context()
     );
 
-    // Determine width and height
+    for (x, y, pixel) in image.enumerate_pixels_mut() {
+        let byte_index = (y * row_size_bytes + x / 8) as usize;
+        let bit_index = 7 - (x % 8); // Bits are packed from MSB to LSB
+        if (mask_pixel_data[byte_index] >> bit_index) & 1 == 1 {
+            pixel.0[3] = 0;
+        }
+    }
+    Ok(image)
+}
+
+/// Reconstructs a monochrome icon from its double-height mask: the top half is the AND mask, the bottom half the XOR mask.
+///
+/// | AND | XOR | result |
+/// | --- | --- | --- |
+/// | 0 | 0 | opaque black |
+/// | 0 | 1 | opaque white |
+/// | 1 | 0 | transparent |
+/// | 1 | 1 | inverts the screen, approximated as opaque black |
+///
+/// <https://learn.microsoft.com/en-us/windows/win32/api/winuser/ns-winuser-iconinfo>
+fn monochrome_mask_to_rgba(hbm_mask: HBITMAP) -> eyre::Result<RgbaImage> {
+    let bitmap = hbitmap_info(hbm_mask)?;
+
     let width = u32::try_from(bitmap.bmWidth)?;
-    let height = u32::try_from(bitmap.bmHeight)?;
+    let mask_height = u32::try_from(bitmap.bmHeight)?;
     ensure!(width > 0, "Bitmap width must not be zero");
-    ensure!(height > 0, "Bitmap height must not be zero");
+    ensure!(
+        mask_height > 0 && mask_height % 2 == 0,
+        "Monochrome mask height must be a non-zero even number, got {}",
+        mask_height
+    );
+    let height = mask_height / 2;
 
-    // Create a compatible DC
     let screen_device_context = ReleaseDCGuard(unsafe { GetDC(None) });
-
     let memory_device_context =
         DeleteDCGuard(unsafe { CreateCompatibleDC(Some(*screen_device_context)) });
 
-    let old_bitmap = unsafe { SelectObject(*memory_device_context, HGDIOBJ::from(*hbm_color)) };
-
-    let _old_bitmap_guard = SelectObjectGuard(*memory_device_context, old_bitmap);
-
-    let mut bitmap_info = BITMAPINFO::default();
-    bitmap_info.bmiHeader.biSize = std::mem::size_of::<BITMAPINFOHEADER>() as u32;
-    bitmap_info.bmiHeader.biWidth = width as i32;
-    bitmap_info.bmiHeader.biHeight = -(height as i32); // top-down
-    bitmap_info.bmiHeader.biPlanes = 1;
-    bitmap_info.bmiHeader.biBitCount = 32; // RGBA
-    bitmap_info.bmiHeader.biCompression = BI_RGB.0;
+    let mut mask_bitmap_info = PalettizedBitmapInfo {
+        header: BITMAPINFOHEADER {
+            biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+            biWidth: width as i32,
+            biHeight: -(mask_height as i32), // top-down
+            biPlanes: 1,
+            biBitCount: 1,
+            biCompression: BI_RGB.0,
+            ..Default::default()
+        },
+        colors: [RGBQUAD::default(); 256],
+    };
 
-    let mut image_data = vec![0u8; (width * height * 4) as usize];
+    // Rows of a 1bpp DIB are DWORD aligned
+    let row_size_bytes = width.div_ceil(32) * 4;
+    let mut mask_pixel_data = vec![0u8; (row_size_bytes * mask_height) as usize];
     ensure!(
         unsafe {
             GetDIBits(
                 *memory_device_context,
-                *hbm_color,
+                hbm_mask,
                 0,
-                height,
-                Some(image_data.as_mut_ptr() as *mut _),
-                &mut bitmap_info,
+                mask_height,
+                Some(mask_pixel_data.as_mut_ptr() as *mut _),
+                &raw mut mask_bitmap_info as *mut BITMAPINFO,
                 DIB_RGB_COLORS,
             ) != 0
         },
-        "GetDIBits failed to get bitmap bits"
+        "GetDIBits failed to get monochrome mask bits: {}",
+        last_error_context()
     );
 
-    if !hbm_mask.is_invalid() && hbm_mask != hbm_color {
-        // conversion is necessary
-
-        let mut mask_bitmap_info = BITMAPINFO::default();
-        mask_bitmap_info.bmiHeader.biSize = std::mem::size_of::<BITMAPINFOHEADER>() as u32;
-        mask_bitmap_info.bmiHeader.biWidth = width as i32;
-        mask_bitmap_info.bmiHeader.biHeight = -(height as i32); // top-down
-        mask_bitmap_info.bmiHeader.biPlanes = 1;
-        mask_bitmap_info.bmiHeader.biBitCount = 1; // 1-bit mask/per pixel
-        mask_bitmap_info.bmiHeader.biCompression = BI_RGB.0;
-
-        let mut mask_pixel_data = vec![0u8; (width * height) as usize]; // 1bpp mask data
-        ensure!(
-            unsafe {
-                GetDIBits(
-                    *memory_device_context,
-                    *hbm_mask,
-                    0,
-                    height,
-                    Some(mask_pixel_data.as_mut_ptr() as *mut _),
-                    &mut mask_bitmap_info,
-                    DIB_RGB_COLORS,
-                ) != 0
-            },
-            "GetDIBits failed to get mask bitmap bits"
-        );
-
-        // Apply the mask to the RGBA data
-        // Iterate over each pixel. The mask_pixel_data is packed 8 pixels per byte.
-        // The DIB is top-down, so scanlines are in order.
-        // Row size for 1bpp DIB must be DWORD aligned.
-        let row_size_bytes = width.div_ceil(32) * 4;
-
-        for y in 0..height {
-            for x in 0..width {
-                let byte_index = (y * row_size_bytes + x / 8) as usize;
-                let bit_index = 7 - (x % 8); // Bits are packed from MSB to LSB
-                let mask_bit = (mask_pixel_data[byte_index] >> bit_index) & 1;
-
-                let pixel_idx_rgba = ((y * width + x) * 4) as usize;
-                if mask_bit == 1 {
-                    // Mask bit 1 means transparent (for ICONINFO mask)
-                    image_data[pixel_idx_rgba + 3] = 0; // Set alpha to transparent
-                } else {
-                    // Mask bit 0 means opaque
-                    if bitmap.bmBitsPixel != 32 {
-                        // If original wasn't 32bpp, ensure opaque
-                        image_data[pixel_idx_rgba + 3] = 255;
-                    }
-                    // If original was 32bpp, its alpha is already in image_data[pixel_idx_rgba + 3]
-                    // and this mask bit being 0 means that alpha should be preserved.
-                }
-            }
-        }
-    } else {
-        // No separate mask bitmap; if original wasn't 32bpp, set alpha to opaque
-        if bitmap.bmBitsPixel != 32 {
-            for i in 0..(width * height) as usize {
-                image_data[i * 4 + 3] = 255;
-            }
-        }
-    }
+    let bit_at = |x: u32, y: u32| {
+        let byte_index = (y * row_size_bytes + x / 8) as usize;
+        (mask_pixel_data[byte_index] >> (7 - (x % 8))) & 1
+    };
 
-    for i in 0..(width * height) as usize {
-        let pixel_idx = i * 4;
-        image_data.swap(pixel_idx, pixel_idx + 2); // BGRA to RGBA
+    let mut image = RgbaImage::new(width, height);
+    for (x, y, pixel) in image.enumerate_pixels_mut() {
+        let and_bit = bit_at(x, y);
+        let xor_bit = bit_at(x, y + height);
+        pixel.0 = match (and_bit, xor_bit) {
+            (0, 1) => [255, 255, 255, 255],
+            (1, 0) => [0, 0, 0, 0],
+            _ => [0, 0, 0, 255],
+        };
     }
-
-    RgbaImage::from_raw(width, height, image_data).ok_or_else(|| {
-        eyre!(
-            "Failed to create RgbaImage from raw data with width {} and height {}",
-            width,
-            height
-        )
-    })
+    Ok(image)
 }
 
 /// Release on drop
@@ -211,3 +218,46 @@ impl Drop for SelectObjectGuard {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::hicon_to_rgba;
+    use crate::hicon::OwnedHbitmap;
+    use crate::hicon::OwnedHicon;
+    use windows::Win32::Foundation::TRUE;
+    use windows::Win32::Graphics::Gdi::CreateBitmap;
+    use windows::Win32::Graphics::Gdi::HBITMAP;
+    use windows::Win32::UI::WindowsAndMessaging::CreateIconIndirect;
+    use windows::Win32::UI::WindowsAndMessaging::ICONINFO;
+
+    #[test]
+    fn converts_monochrome_icon() -> eyre::Result<()> {
+        // 16x16 icon, rows are one WORD. Top half of the mask is AND, bottom half is XOR.
+        let mut bits = Vec::new();
+        for y in 0..16 {
+            bits.extend(if y < 8 { [0xFF, 0xFF] } else { [0x00, 0x00] });
+        }
+        for _ in 0..16 {
+            bits.extend([0xFF, 0x00]);
+        }
+        let mask = unsafe { CreateBitmap(16, 32, 1, 1, Some(bits.as_ptr() as *const _)) };
+        let mask = unsafe { OwnedHbitmap::new(mask) };
+
+        let icon_info = ICONINFO {
+            fIcon: TRUE,
+            hbmMask: mask.as_raw(),
+            hbmColor: HBITMAP::default(),
+            ..Default::default()
+        };
+        let hicon = unsafe { CreateIconIndirect(&icon_info) }?;
+        let hicon = unsafe { OwnedHicon::new(hicon) };
+
+        let image = unsafe { hicon_to_rgba(hicon.as_raw()) }?;
+        assert_eq!(image.dimensions(), (16, 16));
+        assert_eq!(image.get_pixel(0, 0).0, [0, 0, 0, 255]); // inverted
+        assert_eq!(image.get_pixel(15, 0).0, [0, 0, 0, 0]); // transparent
+        assert_eq!(image.get_pixel(0, 15).0, [255, 255, 255, 255]); // white
+        assert_eq!(image.get_pixel(15, 15).0, [0, 0, 0, 255]); // black
+        Ok(())
+    }
+}
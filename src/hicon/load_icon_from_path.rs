@@ -1,30 +1,63 @@
 use crate::audio::TeamyImmDeviceIcon;
+use crate::hicon::extract_icon_rgba;
+use crate::hicon::get_icon_from_module;
 use crate::hicon::hicon_to_rgba;
 use crate::string::EasyPCWSTR;
-use eyre::bail;
+use crate::string::expand_environment_strings;
+use eyre::Context;
 use eyre::ensure;
-use windows::Win32::Foundation::HINSTANCE;
-use windows::Win32::System::LibraryLoader::LoadLibraryW;
+use std::path::Path;
 use windows::Win32::UI::WindowsAndMessaging::HICON;
 use windows::Win32::UI::WindowsAndMessaging::IMAGE_ICON;
 use windows::Win32::UI::WindowsAndMessaging::LR_DEFAULTSIZE;
 use windows::Win32::UI::WindowsAndMessaging::LR_LOADFROMFILE;
 use windows::Win32::UI::WindowsAndMessaging::LR_SHARED;
 use windows::Win32::UI::WindowsAndMessaging::LoadImageW;
-use windows::core::Owned;
+use windows::core::PCWSTR;
 
-pub fn load_icon_from_path(path: &str) -> eyre::Result<TeamyImmDeviceIcon> {
-    // may be a problem with this lol
-    // 7 months ago the code I am rewriting this from had this as the commit message
-    // > fallback mic icon logic partially working, it's grabbing the line in icon instead of the mic icon
+/// Pixel size icons are loaded at when the location doesn't pick one.
+const DEFAULT_ICON_SIZE: u32 = 32;
+
+/// Where an icon lives, as written in registry values and device properties.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IconLocation {
+    /// The exe, dll or ico file, with environment variables expanded.
+    pub path: String,
+    /// Negative values are resource IDs, non-negative values are zero-based icon indices.
+    pub index: Option<i32>,
+}
 
-    match path.split(",-").collect::<Vec<_>>().as_slice() {
-        [path] if path.to_ascii_lowercase().ends_with(".ico") => {
+impl IconLocation {
+    /// Parses `path`, `path,index` or `path,-resource_id`, with an optional leading `@`
+    /// and `%VAR%` references such as `%SystemRoot%\system32\mmres.dll,-3011`.
+    pub fn parse(location: &str) -> eyre::Result<Self> {
+        let location = location.trim();
+        let location = location.strip_prefix('@').unwrap_or(location);
+        let (path, index) = match location.rsplit_once(',') {
+            Some((path, index)) => match index.trim().parse::<i32>() {
+                Ok(index) => (path, Some(index)),
+                // A comma that isn't followed by a number is part of the path
+                Err(_) => (location, None),
+            },
+            None => (location, None),
+        };
+        let path = path.trim().trim_matches('"');
+        ensure!(!path.is_empty(), "Icon location {location:?} has no path");
+        let path = expand_environment_strings(path)
+            .wrap_err_with(|| format!("Failed to expand icon location {location:?}"))?;
+        Ok(Self { path, index })
+    }
+}
+
+pub fn load_icon_from_path(path: &str) -> eyre::Result<TeamyImmDeviceIcon> {
+    let location = IconLocation::parse(path)?;
+    match location.index {
+        None if location.path.to_ascii_lowercase().ends_with(".ico") => {
             // Load the image handle
             let handle = unsafe {
                 LoadImageW(
                     None,
-                    path.easy_pcwstr()?.as_ref(),
+                    location.path.easy_pcwstr()?.as_ref(),
                     IMAGE_ICON,
                     0,
                     0,
@@ -36,42 +69,64 @@ pub fn load_icon_from_path(path: &str) -> eyre::Result<TeamyImmDeviceIcon> {
             // Convert the image
             unsafe { hicon_to_rgba(HICON(handle.0)).map(TeamyImmDeviceIcon::new) }
         }
-        [path, index_str] => {
-            let path = path.strip_prefix("@").unwrap_or(path);
-            let index: u16 = index_str.parse()?;
-
-            // Load the module
-            let hmodule = unsafe { LoadLibraryW(path.easy_pcwstr()?.as_ref()) }?;
-            ensure!(!hmodule.is_invalid());
-            let hmodule = unsafe { Owned::new(hmodule) };
-
+        Some(index) if index < 0 => {
             // Somewhere it is mentioned that macros are out of scope of the windows-rs project
             #[allow(non_snake_case)]
-            pub fn MAKEINTRESOURCEW(i: u16) -> windows::core::PCWSTR {
-                windows::core::PCWSTR(i as usize as *const u16)
+            pub fn MAKEINTRESOURCEW(i: u16) -> PCWSTR {
+                PCWSTR(i as usize as *const u16)
             }
 
-            // Load the image handle
-            let image_handle = unsafe {
-                LoadImageW(
-                    Some(HINSTANCE::from(*hmodule)),
-                    MAKEINTRESOURCEW(index),
-                    IMAGE_ICON,
-                    0,
-                    0,
-                    LR_DEFAULTSIZE | LR_SHARED,
-                )
-            }?;
-            ensure!(!image_handle.is_invalid());
-
+            let resource_id = u16::try_from(index.unsigned_abs())
+                .wrap_err_with(|| format!("Icon resource ID {index} is out of range"))?;
+            let icon =
+                get_icon_from_module(Path::new(&location.path), MAKEINTRESOURCEW(resource_id), 0)?;
             // Convert the image
-            unsafe { hicon_to_rgba(HICON(image_handle.0)).map(TeamyImmDeviceIcon::new) }
-        }
-        _ => {
-            bail!(
-                "Invalid icon path format: expected 'path.ico' or 'path,-index', got '{}'",
-                path
-            );
+            unsafe { hicon_to_rgba(icon.as_raw()).map(TeamyImmDeviceIcon::new) }
         }
+        index => extract_icon_rgba(
+            Path::new(&location.path),
+            index.unwrap_or(0) as u32,
+            DEFAULT_ICON_SIZE,
+        )
+        .map(TeamyImmDeviceIcon::new),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::IconLocation;
+    use super::load_icon_from_path;
+
+    #[test]
+    fn parses_icon_locations() -> eyre::Result<()> {
+        let system_root = std::env::var("SystemRoot")?;
+        assert_eq!(
+            IconLocation::parse(r"@%SystemRoot%\system32\mmres.dll,-3011")?,
+            IconLocation {
+                path: format!(r"{system_root}\system32\mmres.dll"),
+                index: Some(-3011),
+            }
+        );
+        assert_eq!(
+            IconLocation::parse(r"C:\icons\mic.ico")?,
+            IconLocation {
+                path: r"C:\icons\mic.ico".to_string(),
+                index: None,
+            }
+        );
+        assert_eq!(
+            IconLocation::parse(r"C:\a,b\shell32.dll, 4")?,
+            IconLocation {
+                path: r"C:\a,b\shell32.dll".to_string(),
+                index: Some(4),
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn loads_unexpanded_resource_icon() -> eyre::Result<()> {
+        load_icon_from_path(r"%SystemRoot%\system32\mmres.dll,-3011")?;
+        Ok(())
     }
 }
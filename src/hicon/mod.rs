@@ -1,8 +1,18 @@
 pub mod application_icon;
 mod embedded_resource;
+mod extract_icon;
+mod hbitmap_to_image;
 mod hicon_to_image;
 mod load_icon_from_path;
+mod owned_hbitmap;
+mod owned_hicon;
+mod rgba_to_hicon;
 
 pub use embedded_resource::*;
+pub use extract_icon::*;
+pub use hbitmap_to_image::*;
 pub use hicon_to_image::*;
 pub use load_icon_from_path::*;
+pub use owned_hbitmap::*;
+pub use owned_hicon::*;
+pub use rgba_to_hicon::*;
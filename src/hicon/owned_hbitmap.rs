@@ -0,0 +1,42 @@
+use windows::Win32::Graphics::Gdi::DeleteObject;
+use windows::Win32::Graphics::Gdi::HBITMAP;
+use windows::Win32::Graphics::Gdi::HGDIOBJ;
+
+/// A bitmap handle that is deleted with `DeleteObject` on drop.
+///
+/// Only wrap bitmaps the caller owns, such as those from `CreateBitmap`, `CreateDIBSection`
+/// or the `hbmColor`/`hbmMask` returned by `GetIconInfo`.
+#[derive(Debug)]
+pub struct OwnedHbitmap(HBITMAP);
+
+impl OwnedHbitmap {
+    /// # Safety
+    ///
+    /// The caller must own `hbitmap` and not delete it elsewhere.
+    pub unsafe fn new(hbitmap: HBITMAP) -> Self {
+        Self(hbitmap)
+    }
+
+    pub fn as_raw(&self) -> HBITMAP {
+        self.0
+    }
+
+    pub fn is_invalid(&self) -> bool {
+        self.0.is_invalid()
+    }
+
+    /// Releases ownership without deleting the bitmap.
+    pub fn into_raw(self) -> HBITMAP {
+        let hbitmap = self.0;
+        std::mem::forget(self);
+        hbitmap
+    }
+}
+
+impl Drop for OwnedHbitmap {
+    fn drop(&mut self) {
+        if !self.0.is_invalid() {
+            _ = unsafe { DeleteObject(HGDIOBJ::from(self.0)) };
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use windows::Win32::UI::WindowsAndMessaging::DestroyIcon;
+use windows::Win32::UI::WindowsAndMessaging::HICON;
+
+/// An icon handle that is destroyed with `DestroyIcon` on drop.
+///
+/// Only wrap icons created by `CreateIcon*`, `LoadImageW` or `ExtractIconExW`;
+/// shared icons from `LoadIconW` must not be destroyed.
+#[derive(Debug)]
+pub struct OwnedHicon(HICON);
+
+impl OwnedHicon {
+    /// # Safety
+    ///
+    /// The caller must own `hicon` and not destroy it elsewhere.
+    pub unsafe fn new(hicon: HICON) -> Self {
+        Self(hicon)
+    }
+
+    pub fn as_raw(&self) -> HICON {
+        self.0
+    }
+
+    /// Releases ownership without destroying the icon.
+    pub fn into_raw(self) -> HICON {
+        let hicon = self.0;
+        std::mem::forget(self);
+        hicon
+    }
+}
+
+impl Drop for OwnedHicon {
+    fn drop(&mut self) {
+        if !self.0.is_invalid() {
+            _ = unsafe { DestroyIcon(self.0) };
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::hicon::rgba_to_hicon;
+    use image::Rgba;
+    use image::RgbaImage;
+    use windows::Win32::UI::WindowsAndMessaging::DestroyIcon;
+
+    #[test]
+    fn into_raw_releases_ownership() -> eyre::Result<()> {
+        let img = RgbaImage::from_pixel(16, 16, Rgba([0, 0, 0, 255]));
+        let hicon = rgba_to_hicon(&img)?.into_raw();
+
+        // Still valid because the guard didn't destroy it
+        unsafe { DestroyIcon(hicon) }?;
+        Ok(())
+    }
+}
@@ -0,0 +1,105 @@
+use crate::hicon::OwnedHbitmap;
+use crate::hicon::OwnedHicon;
+use eyre::Context;
+use eyre::ensure;
+use image::RgbaImage;
+use std::ffi::c_void;
+use windows::Win32::Foundation::TRUE;
+use windows::Win32::Graphics::Gdi::BI_RGB;
+use windows::Win32::Graphics::Gdi::BITMAPINFO;
+use windows::Win32::Graphics::Gdi::BITMAPINFOHEADER;
+use windows::Win32::Graphics::Gdi::CreateBitmap;
+use windows::Win32::Graphics::Gdi::CreateDIBSection;
+use windows::Win32::Graphics::Gdi::DIB_RGB_COLORS;
+use windows::Win32::UI::WindowsAndMessaging::CreateIconIndirect;
+use windows::Win32::UI::WindowsAndMessaging::ICONINFO;
+
+/// Creates an icon from RGBA pixels, the inverse of [`hicon_to_rgba`](crate::hicon::hicon_to_rgba).
+///
+/// The color bitmap is a 32bpp top-down DIB section holding the pixels as BGRA,
+/// and the AND mask marks fully transparent pixels so the icon also draws correctly on legacy paths.
+/// <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-createiconindirect>
+pub fn rgba_to_hicon(img: &RgbaImage) -> eyre::Result<OwnedHicon> {
+    let (width, height) = img.dimensions();
+    ensure!(width > 0, "Image width must not be zero");
+    ensure!(height > 0, "Image height must not be zero");
+    let width_i32 = i32::try_from(width)?;
+    let height_i32 = i32::try_from(height)?;
+
+    // Color bitmap
+    let mut bitmap_info = BITMAPINFO::default();
+    bitmap_info.bmiHeader.biSize = std::mem::size_of::<BITMAPINFOHEADER>() as u32;
+    bitmap_info.bmiHeader.biWidth = width_i32;
+    bitmap_info.bmiHeader.biHeight = -height_i32; // top-down
+    bitmap_info.bmiHeader.biPlanes = 1;
+    bitmap_info.bmiHeader.biBitCount = 32;
+    bitmap_info.bmiHeader.biCompression = BI_RGB.0;
+
+    let mut bits: *mut c_void = std::ptr::null_mut();
+    let raw_color =
+        unsafe { CreateDIBSection(None, &bitmap_info, DIB_RGB_COLORS, &mut bits, None, 0) }
+            .wrap_err("Failed to create DIB section for icon color bitmap")?;
+    let hbm_color = unsafe { OwnedHbitmap::new(raw_color) };
+    ensure!(!bits.is_null(), "CreateDIBSection returned no pixel buffer");
+
+    let pixel_bytes = (width as usize) * (height as usize) * 4;
+    let color_data = unsafe { std::slice::from_raw_parts_mut(bits as *mut u8, pixel_bytes) };
+    for (dst, src) in color_data.chunks_exact_mut(4).zip(img.pixels()) {
+        let [r, g, b, a] = src.0;
+        dst.copy_from_slice(&[b, g, r, a]); // RGBA to BGRA
+    }
+
+    // AND mask, 1bpp with WORD-aligned rows as required by CreateBitmap. Bit set means transparent.
+    let row_size_bytes = width.div_ceil(16) as usize * 2;
+    let mut mask_data = vec![0u8; row_size_bytes * height as usize];
+    for (x, y, pixel) in img.enumerate_pixels() {
+        if pixel.0[3] == 0 {
+            let byte_index = y as usize * row_size_bytes + (x / 8) as usize;
+            mask_data[byte_index] |= 0x80 >> (x % 8);
+        }
+    }
+    let raw_mask = unsafe {
+        CreateBitmap(
+            width_i32,
+            height_i32,
+            1,
+            1,
+            Some(mask_data.as_ptr() as *const c_void),
+        )
+    };
+    ensure!(!raw_mask.is_invalid(), "Failed to create icon mask bitmap");
+    let hbm_mask = unsafe { OwnedHbitmap::new(raw_mask) };
+
+    // CreateIconIndirect copies the bitmaps, so the guards above can free ours
+    let icon_info = ICONINFO {
+        fIcon: TRUE,
+        xHotspot: 0,
+        yHotspot: 0,
+        hbmMask: hbm_mask.as_raw(),
+        hbmColor: hbm_color.as_raw(),
+    };
+    let hicon = unsafe { CreateIconIndirect(&icon_info) }.wrap_err("Failed to create icon")?;
+    Ok(unsafe { OwnedHicon::new(hicon) })
+}
+
+#[cfg(test)]
+mod test {
+    use super::rgba_to_hicon;
+    use crate::hicon::hicon_to_rgba;
+    use image::Rgba;
+    use image::RgbaImage;
+
+    #[test]
+    fn round_trips_through_hicon_to_rgba() -> eyre::Result<()> {
+        let img = RgbaImage::from_fn(19, 5, |x, y| match (x + y) % 3 {
+            0 => Rgba([255, 0, 0, 255]),
+            1 => Rgba([0, 128, 255, 128]),
+            _ => Rgba([10, 20, 30, 0]),
+        });
+
+        let hicon = rgba_to_hicon(&img)?;
+        let round_tripped = unsafe { hicon_to_rgba(hicon.as_raw()) }?;
+        assert_eq!(round_tripped, img);
+        Ok(())
+    }
+}
@@ -0,0 +1,202 @@
+use crate::invocation::to_args::Invocable;
+use std::ffi::OsStr;
+use std::ffi::OsString;
+use std::os::windows::ffi::OsStrExt;
+use std::os::windows::ffi::OsStringExt;
+use std::path::PathBuf;
+
+const SPACE: u16 = b' ' as u16;
+const TAB: u16 = b'\t' as u16;
+const NEWLINE: u16 = b'\n' as u16;
+const VERTICAL_TAB: u16 = 0x0B;
+const QUOTE: u16 = b'"' as u16;
+const BACKSLASH: u16 = b'\\' as u16;
+
+/// Builder for a Windows command line string, quoting each argument so that `CommandLineToArgvW` recovers it exactly.
+///
+/// Use [`CommandLine::to_os_string`] for the `lpCommandLine` of `CreateProcessW`,
+/// and [`CommandLine::args_to_os_string`] for the `lpParameters` of `ShellExecuteW`.
+/// <https://learn.microsoft.com/en-us/windows/win32/api/shellapi/nf-shellapi-commandlinetoargvw>
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandLine {
+    program: PathBuf,
+    args: Vec<OsString>,
+}
+
+impl CommandLine {
+    pub fn new(program: impl Into<PathBuf>) -> Self {
+        Self {
+            program: program.into(),
+            args: Vec::new(),
+        }
+    }
+
+    pub fn from_invocable(invocable: &impl Invocable) -> Self {
+        Self::new(invocable.executable()).args(invocable.args())
+    }
+
+    pub fn arg(mut self, arg: impl Into<OsString>) -> Self {
+        self.args.push(arg.into());
+        self
+    }
+
+    pub fn args<I, S>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<OsString>,
+    {
+        self.args.extend(args.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn get_program(&self) -> &PathBuf {
+        &self.program
+    }
+
+    pub fn get_args(&self) -> &[OsString] {
+        &self.args
+    }
+
+    /// The full command line, program first, suitable for `CreateProcessW`.
+    pub fn to_os_string(&self) -> OsString {
+        let mut wide = Vec::new();
+        push_program(&mut wide, self.program.as_os_str());
+        for arg in &self.args {
+            wide.push(SPACE);
+            push_arg(&mut wide, arg);
+        }
+        OsString::from_wide(&wide)
+    }
+
+    /// Only the quoted arguments, suitable for `ShellExecuteW` where the program is passed separately.
+    pub fn args_to_os_string(&self) -> OsString {
+        let mut wide = Vec::new();
+        for (i, arg) in self.args.iter().enumerate() {
+            if i > 0 {
+                wide.push(SPACE);
+            }
+            push_arg(&mut wide, arg);
+        }
+        OsString::from_wide(&wide)
+    }
+}
+
+/// The program name is parsed without backslash escapes, so it can only be wrapped in quotes.
+fn push_program(out: &mut Vec<u16>, program: &OsStr) {
+    let wide: Vec<u16> = program.encode_wide().collect();
+    let needs_quotes = wide.is_empty() || wide.iter().any(|&c| c == SPACE || c == TAB);
+    if needs_quotes {
+        out.push(QUOTE);
+    }
+    out.extend(wide.iter().filter(|&&c| c != QUOTE));
+    if needs_quotes {
+        out.push(QUOTE);
+    }
+}
+
+/// Backslashes are only special when they precede a quote, so runs of them are doubled before quotes and before the closing quote.
+fn push_arg(out: &mut Vec<u16>, arg: &OsStr) {
+    let wide: Vec<u16> = arg.encode_wide().collect();
+    let needs_quotes = wide.is_empty()
+        || wide
+            .iter()
+            .any(|&c| matches!(c, SPACE | TAB | NEWLINE | VERTICAL_TAB | QUOTE));
+    if !needs_quotes {
+        out.extend_from_slice(&wide);
+        return;
+    }
+
+    out.push(QUOTE);
+    let mut backslashes = 0;
+    for &c in &wide {
+        match c {
+            BACKSLASH => backslashes += 1,
+            QUOTE => {
+                out.extend(std::iter::repeat_n(BACKSLASH, backslashes * 2 + 1));
+                out.push(QUOTE);
+                backslashes = 0;
+            }
+            _ => {
+                out.extend(std::iter::repeat_n(BACKSLASH, backslashes));
+                out.push(c);
+                backslashes = 0;
+            }
+        }
+    }
+    out.extend(std::iter::repeat_n(BACKSLASH, backslashes * 2));
+    out.push(QUOTE);
+}
+
+#[cfg(test)]
+mod test {
+    use super::CommandLine;
+    use crate::string::EasyPCWSTR;
+    use std::ffi::OsString;
+    use std::os::windows::ffi::OsStringExt;
+    use windows::Win32::Foundation::HLOCAL;
+    use windows::Win32::Foundation::LocalFree;
+    use windows::Win32::UI::Shell::CommandLineToArgvW;
+
+    fn parse(command_line: &OsString) -> eyre::Result<Vec<OsString>> {
+        let command_line = command_line.easy_pcwstr()?;
+        let mut count = 0;
+        let argv = unsafe { CommandLineToArgvW(command_line.as_ref(), &mut count) };
+        if argv.is_null() {
+            return Err(windows::core::Error::from_thread().into());
+        }
+        let mut rtn = Vec::with_capacity(count as usize);
+        for i in 0..count as usize {
+            let arg_ptr = unsafe { argv.add(i) };
+            let arg = unsafe { *arg_ptr };
+            let arg = unsafe { arg.as_wide() };
+            rtn.push(OsString::from_wide(arg));
+        }
+        _ = unsafe { LocalFree(Some(HLOCAL(argv.cast()))) };
+        Ok(rtn)
+    }
+
+    #[test]
+    fn round_trips_through_command_line_to_argv() -> eyre::Result<()> {
+        let args = [
+            "plain",
+            "",
+            "with space",
+            "tab\there",
+            r#"quote"inside"#,
+            r#"\"leading backslash quote"#,
+            r"trailing backslash\",
+            r"trailing backslashes with space\\",
+            r"C:\Program Files\thing\",
+            r"a\\b",
+            r#"\\"\\"#,
+            "\"",
+            "--flag=value with \"quotes\" and \\\\ slashes\\",
+        ];
+        let program = r"C:\Program Files\Teamy\teamy.exe";
+        let command_line = CommandLine::new(program).args(args);
+
+        let parsed = parse(&command_line.to_os_string())?;
+        let mut expected = vec![OsString::from(program)];
+        expected.extend(args.iter().map(OsString::from));
+        assert_eq!(parsed, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn args_string_omits_program() -> eyre::Result<()> {
+        let command_line = CommandLine::new("teamy.exe").arg("a b").arg(r"c\");
+        assert_eq!(
+            command_line.args_to_os_string(),
+            OsString::from(r#""a b" c\"#)
+        );
+
+        // Prefix a dummy program so the first argument isn't parsed with program-name rules
+        let mut with_program = OsString::from("x ");
+        with_program.push(command_line.args_to_os_string());
+        assert_eq!(
+            parse(&with_program)?[1..],
+            [OsString::from("a b"), OsString::from(r"c\")]
+        );
+        Ok(())
+    }
+}
@@ -1,7 +1,9 @@
+pub mod command_line;
 pub mod same_invocation;
 pub mod same_invocation_same_console;
 pub mod to_args;
 
+pub use command_line::*;
 pub use same_invocation::*;
 pub use same_invocation_same_console::*;
 pub use to_args::*;
@@ -1,11 +1,12 @@
 pub mod audio;
-pub mod daemon;
 #[cfg(feature = "cli")]
 pub mod cli;
 pub mod clipboard;
 pub mod com;
 pub mod console;
+pub mod daemon;
 pub mod elevation;
+pub mod error;
 pub mod event_loop;
 pub mod handle;
 pub mod hicon;
@@ -15,7 +16,9 @@ pub mod log;
 pub mod module;
 pub mod network;
 pub mod paths;
+pub mod process;
 pub mod shell;
+pub mod singleton;
 pub mod storage;
 pub mod string;
 pub mod tray;
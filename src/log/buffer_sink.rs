@@ -32,15 +32,103 @@ pub struct BufferSink {
     buffer: Arc<Mutex<Vec<u8>>>,
 }
 impl BufferSink {
+    /// Writes the buffered logs exactly as they were captured.
     pub fn replay(&self, writer: &mut impl Write) -> eyre::Result<()> {
+        self.replay_with_style(writer, ReplayStyle::Colored)
+    }
+
+    /// Writes the buffered logs, keeping or stripping ANSI escape codes depending on `style`.
+    ///
+    /// Capture with ANSI enabled on the formatting layer so both styles are available.
+    pub fn replay_with_style(
+        &self,
+        writer: &mut impl Write,
+        style: ReplayStyle,
+    ) -> eyre::Result<()> {
         let buffer = self.lock().unwrap();
         writeln!(writer, "=== Previous Logs ===")?;
-        writer
-            .write_all(&buffer)
-            .map_err(|e| eyre::eyre!("Failed to write log buffer to writer: {}", e))?;
+        let result = match style {
+            ReplayStyle::Colored => writer.write_all(&buffer),
+            ReplayStyle::Plain => writer.write_all(&strip_ansi_escapes(&buffer)),
+        };
+        result.map_err(|e| eyre::eyre!("Failed to write log buffer to writer: {}", e))?;
         writeln!(writer, "=== End of Previous Logs ===")?;
         Ok(())
     }
+
+    /// The buffered logs as text with ANSI escapes stripped, e.g. for copying to the clipboard.
+    pub fn snapshot(&self) -> String {
+        let buffer = self.lock().unwrap();
+        String::from_utf8_lossy(&strip_ansi_escapes(&buffer)).into_owned()
+    }
+
+    /// Discards the buffered logs; later [`replay`](BufferSink::replay)s only include what is written afterwards.
+    pub fn clear(&self) {
+        self.lock().unwrap().clear();
+    }
+}
+
+/// Whether replayed logs keep their ANSI colors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplayStyle {
+    /// Keep the escape codes, for terminals that render VT sequences.
+    Colored,
+    /// Strip the escape codes, for files, pipes and legacy consoles.
+    Plain,
+}
+
+impl ReplayStyle {
+    /// [`Colored`](ReplayStyle::Colored) if stdout is a console with VT processing enabled, otherwise [`Plain`](ReplayStyle::Plain).
+    pub fn for_stdout() -> Self {
+        if crate::console::is_ansi_enabled() {
+            ReplayStyle::Colored
+        } else {
+            ReplayStyle::Plain
+        }
+    }
+}
+
+/// Removes ANSI escape sequences: CSI sequences like colors (`ESC [ ... m`), OSC sequences like
+/// hyperlinks (`ESC ] ... BEL` or `ESC ] ... ESC \`), and two-byte escapes.
+pub fn strip_ansi_escapes(bytes: &[u8]) -> Vec<u8> {
+    const ESC: u8 = 0x1b;
+    const BEL: u8 = 0x07;
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] != ESC {
+            out.push(bytes[i]);
+            i += 1;
+            continue;
+        }
+        match bytes.get(i + 1) {
+            Some(b'[') => {
+                // Parameters and intermediates, then a final byte in 0x40..=0x7E
+                i += 2;
+                while i < bytes.len() && !(0x40..=0x7e).contains(&bytes[i]) {
+                    i += 1;
+                }
+                i += 1;
+            }
+            Some(b']') => {
+                i += 2;
+                while i < bytes.len() {
+                    if bytes[i] == BEL {
+                        i += 1;
+                        break;
+                    }
+                    if bytes[i] == ESC && bytes.get(i + 1) == Some(&b'\\') {
+                        i += 2;
+                        break;
+                    }
+                    i += 1;
+                }
+            }
+            Some(_) => i += 2,
+            None => i += 1,
+        }
+    }
+    out
 }
 impl Deref for BufferSink {
     type Target = Arc<Mutex<Vec<u8>>>;
@@ -72,3 +160,52 @@ impl<'a> MakeWriter<'a> for BufferSink {
         self.clone()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::BufferSink;
+    use super::ReplayStyle;
+    use super::strip_ansi_escapes;
+    use std::io::Write;
+
+    #[test]
+    fn strips_color_and_hyperlink_sequences() {
+        let colored = b"\x1b[2m2025-01-01\x1b[0m \x1b[32m INFO\x1b[0m \x1b]8;;https://example.com\x07link\x1b]8;;\x1b\\ done";
+        assert_eq!(
+            strip_ansi_escapes(colored),
+            b"2025-01-01  INFO link done".to_vec()
+        );
+        assert_eq!(strip_ansi_escapes(b"plain\n"), b"plain\n".to_vec());
+    }
+
+    #[test]
+    fn replays_in_either_style() -> eyre::Result<()> {
+        let mut sink = BufferSink::default();
+        sink.write_all(b"\x1b[31mERROR\x1b[0m boom\n")?;
+
+        let mut colored = Vec::new();
+        sink.replay_with_style(&mut colored, ReplayStyle::Colored)?;
+        assert!(String::from_utf8(colored)?.contains("\x1b[31mERROR\x1b[0m boom"));
+
+        let mut plain = Vec::new();
+        sink.replay_with_style(&mut plain, ReplayStyle::Plain)?;
+        let plain = String::from_utf8(plain)?;
+        assert!(plain.contains("ERROR boom"));
+        assert!(!plain.contains('\x1b'));
+        Ok(())
+    }
+
+    #[test]
+    fn snapshot_and_clear() -> eyre::Result<()> {
+        let mut sink = BufferSink::default();
+        sink.write_all(b"\x1b[32m INFO\x1b[0m first\n")?;
+        let copy = sink.clone();
+        assert_eq!(copy.snapshot(), " INFO first\n");
+
+        copy.clear();
+        assert_eq!(sink.snapshot(), "");
+        sink.write_all(b"second\n")?;
+        assert_eq!(copy.snapshot(), "second\n");
+        Ok(())
+    }
+}
@@ -0,0 +1,104 @@
+use crate::string::EasyPCWSTR;
+use eyre::bail;
+use eyre::ensure;
+use std::ffi::c_void;
+use std::path::Path;
+use windows::Win32::Storage::FileSystem::GetFileVersionInfoSizeW;
+use windows::Win32::Storage::FileSystem::GetFileVersionInfoW;
+use windows::Win32::Storage::FileSystem::VS_FIXEDFILEINFO;
+use windows::Win32::Storage::FileSystem::VerQueryValueW;
+use windows::core::w;
+
+/// `VS_FIXEDFILEINFO::dwSignature` for a valid version resource.
+const VS_FFI_SIGNATURE: u32 = 0xFEEF04BD;
+
+/// The numeric file version from a `VS_FIXEDFILEINFO`, e.g. `10.0.19041.1`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Version {
+    pub major: u16,
+    pub minor: u16,
+    pub build: u16,
+    pub revision: u16,
+}
+
+impl std::fmt::Display for Version {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}.{}.{}.{}",
+            self.major, self.minor, self.build, self.revision
+        )
+    }
+}
+
+/// Reads the file version from the version resource embedded in an exe or dll.
+/// <https://learn.microsoft.com/en-us/windows/win32/api/winver/nf-winver-verqueryvaluew>
+pub fn get_file_version(path: impl AsRef<Path>) -> eyre::Result<Version> {
+    let path = path.as_ref();
+    let path_wide = path.easy_pcwstr()?;
+
+    let size = unsafe { GetFileVersionInfoSizeW(path_wide.as_ref(), None) };
+    if size == 0 {
+        return Err(windows::core::Error::from_thread().into());
+    }
+
+    let mut data = vec![0u8; size as usize];
+    unsafe {
+        GetFileVersionInfoW(
+            path_wide.as_ref(),
+            None,
+            size,
+            data.as_mut_ptr() as *mut c_void,
+        )
+    }?;
+
+    let mut info: *mut c_void = std::ptr::null_mut();
+    let mut info_len = 0u32;
+    let found = unsafe {
+        VerQueryValueW(
+            data.as_ptr() as *const c_void,
+            w!("\\"),
+            &mut info,
+            &mut info_len,
+        )
+    };
+    if !found.as_bool() || info.is_null() {
+        bail!("No fixed version info in {}", path.display());
+    }
+    ensure!(
+        info_len as usize >= std::mem::size_of::<VS_FIXEDFILEINFO>(),
+        "Fixed version info in {} is too small",
+        path.display()
+    );
+
+    // Points into `data`, which outlives this read
+    let info = unsafe { *(info as *const VS_FIXEDFILEINFO) };
+    ensure!(
+        info.dwSignature == VS_FFI_SIGNATURE,
+        "Invalid version info signature in {}",
+        path.display()
+    );
+
+    Ok(Version {
+        major: (info.dwFileVersionMS >> 16) as u16,
+        minor: info.dwFileVersionMS as u16,
+        build: (info.dwFileVersionLS >> 16) as u16,
+        revision: info.dwFileVersionLS as u16,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::get_file_version;
+
+    #[test]
+    fn reads_kernel32_version() -> eyre::Result<()> {
+        let system_root = std::env::var("SystemRoot").unwrap_or_else(|_| "C:\\Windows".to_string());
+        let path = std::path::PathBuf::from(system_root)
+            .join("System32")
+            .join("kernel32.dll");
+        let version = get_file_version(&path)?;
+        assert!(version.major >= 6, "unexpected kernel32 version {version}");
+        Ok(())
+    }
+}
@@ -1,6 +1,17 @@
+mod file_version;
+
+pub use file_version::*;
+use std::ffi::OsString;
+use std::ffi::c_void;
+use std::os::windows::ffi::OsStringExt;
+use std::path::PathBuf;
 use tracing::debug;
 use windows::Win32::Foundation::HMODULE;
+use windows::Win32::System::LibraryLoader::GET_MODULE_HANDLE_EX_FLAG_FROM_ADDRESS;
+use windows::Win32::System::LibraryLoader::GET_MODULE_HANDLE_EX_FLAG_UNCHANGED_REFCOUNT;
+use windows::Win32::System::LibraryLoader::GetModuleFileNameW;
 use windows::Win32::System::LibraryLoader::GetModuleHandleExW;
+use windows::core::PCWSTR;
 
 pub fn get_current_module() -> eyre::Result<HMODULE> {
     unsafe {
@@ -14,6 +25,46 @@ pub fn get_current_module() -> eyre::Result<HMODULE> {
     }
 }
 
+/// Gets the module (exe or dll) containing `address`, such as a function or static defined in that module.
+///
+/// Unlike [`get_current_module`], this resolves to the dll when called from code compiled into a dll.
+/// The module's reference count is not incremented, so the handle must not be freed.
+/// <https://learn.microsoft.com/en-us/windows/win32/api/libloaderapi/nf-libloaderapi-getmodulehandleexw>
+pub fn get_module_from_address(address: *const c_void) -> eyre::Result<HMODULE> {
+    let mut out = HMODULE::default();
+    unsafe {
+        GetModuleHandleExW(
+            GET_MODULE_HANDLE_EX_FLAG_FROM_ADDRESS | GET_MODULE_HANDLE_EX_FLAG_UNCHANGED_REFCOUNT,
+            PCWSTR(address as *const u16),
+            &mut out,
+        )
+    }?;
+    debug!(handle = ?out, ?address, "Got module from address");
+    Ok(out)
+}
+
+/// Gets the path of the running executable, canonicalized without the `\\?\` prefix where possible.
+pub fn current_exe_path() -> eyre::Result<PathBuf> {
+    let path = std::env::current_exe()?;
+    Ok(dunce::canonicalize(path)?)
+}
+
+/// Gets the full path of the file a module was loaded from.
+pub fn get_module_path(module: HMODULE) -> eyre::Result<PathBuf> {
+    let mut buffer = vec![0u16; 260];
+    loop {
+        let len = unsafe { GetModuleFileNameW(Some(module), &mut buffer) } as usize;
+        if len == 0 {
+            return Err(windows::core::Error::from_thread().into());
+        }
+        // The path was truncated if it filled the whole buffer
+        if len < buffer.len() {
+            return Ok(PathBuf::from(OsString::from_wide(&buffer[..len])));
+        }
+        buffer.resize(buffer.len() * 2, 0);
+    }
+}
+
 #[cfg(test)]
 mod test {
     #[test]
@@ -22,4 +73,23 @@ mod test {
         println!("Current module handle: {:?}", module);
         Ok(())
     }
+
+    #[test]
+    fn resolves_module_from_address() -> eyre::Result<()> {
+        // The test binary links this crate statically, so its functions live in the exe
+        let module = super::get_module_from_address(super::get_module_path as *const _)?;
+        assert_eq!(module, super::get_current_module()?);
+
+        let path = super::get_module_path(module)?;
+        assert_eq!(path, std::env::current_exe()?);
+        Ok(())
+    }
+
+    #[test]
+    fn current_exe_path_has_no_verbatim_prefix() -> eyre::Result<()> {
+        let path = super::current_exe_path()?;
+        assert!(path.is_absolute());
+        assert!(!path.to_string_lossy().starts_with(r"\\?\"));
+        Ok(())
+    }
 }
@@ -1,11 +1,19 @@
 mod network_adapter_extensions;
 mod network_adapters;
+mod network_interface_events;
 mod network_interface_id;
 mod network_interface_monitor;
 mod operstatus_extensions;
+mod ping;
+mod socket_address_extensions;
+mod wifi;
 
 pub use network_adapter_extensions::*;
 pub use network_adapters::*;
+pub use network_interface_events::*;
 pub use network_interface_id::*;
 pub use network_interface_monitor::*;
 pub use operstatus_extensions::*;
+pub use ping::*;
+pub use socket_address_extensions::*;
+pub use wifi::*;
@@ -1,12 +1,18 @@
 use crate::network::NetworkInterfaceId;
 use crate::network::NetworkInterfaceMonitor;
+use crate::network::OperStatusExt;
+use crate::network::SocketAddressExt;
 use std::borrow::Cow;
+use std::net::IpAddr;
 use windows::Win32::NetworkManagement::IpHelper::IP_ADAPTER_ADDRESSES_LH;
 
 pub trait NetworkAdapterExt {
     fn id(&self) -> NetworkInterfaceId;
     fn monitor(&self) -> eyre::Result<NetworkInterfaceMonitor>;
     fn display_name(&self) -> Cow<'_, str>;
+    fn is_up(&self) -> bool;
+    fn unicast_addresses(&self) -> Vec<IpAddr>;
+    fn gateway_addresses(&self) -> Vec<IpAddr>;
 }
 impl NetworkAdapterExt for IP_ADAPTER_ADDRESSES_LH {
     fn id(&self) -> NetworkInterfaceId {
@@ -22,4 +28,27 @@ impl NetworkAdapterExt for IP_ADAPTER_ADDRESSES_LH {
             Cow::Owned(unsafe { self.FriendlyName.display() }.to_string())
         }
     }
+    fn is_up(&self) -> bool {
+        self.OperStatus.is_up()
+    }
+    fn unicast_addresses(&self) -> Vec<IpAddr> {
+        let mut addresses = Vec::new();
+        let mut next = self.FirstUnicastAddress;
+        while !next.is_null() {
+            let entry = unsafe { &*next };
+            addresses.extend(entry.Address.ip_addr());
+            next = entry.Next;
+        }
+        addresses
+    }
+    fn gateway_addresses(&self) -> Vec<IpAddr> {
+        let mut addresses = Vec::new();
+        let mut next = self.FirstGatewayAddress;
+        while !next.is_null() {
+            let entry = unsafe { &*next };
+            addresses.extend(entry.Address.ip_addr());
+            next = entry.Next;
+        }
+        addresses
+    }
 }
@@ -1,3 +1,4 @@
+use crate::network::NetworkAdapterExt;
 use eyre::bail;
 use std::marker::PhantomData;
 use windows::Win32::Foundation::ERROR_ADDRESS_NOT_ASSOCIATED;
@@ -8,6 +9,7 @@ use windows::Win32::Foundation::ERROR_NOT_ENOUGH_MEMORY;
 use windows::Win32::Foundation::NO_ERROR;
 use windows::Win32::Foundation::WIN32_ERROR;
 use windows::Win32::NetworkManagement::IpHelper::GAA_FLAG_INCLUDE_ALL_INTERFACES;
+use windows::Win32::NetworkManagement::IpHelper::GAA_FLAG_INCLUDE_GATEWAYS;
 use windows::Win32::NetworkManagement::IpHelper::GetAdaptersAddresses;
 use windows::Win32::NetworkManagement::IpHelper::IP_ADAPTER_ADDRESSES_LH;
 use windows::Win32::Networking::WinSock::AF_UNSPEC;
@@ -55,7 +57,7 @@ impl NetworkAdapters {
             let status = unsafe {
                 GetAdaptersAddresses(
                     AF_UNSPEC.0 as u32,
-                    GAA_FLAG_INCLUDE_ALL_INTERFACES,
+                    GAA_FLAG_INCLUDE_ALL_INTERFACES | GAA_FLAG_INCLUDE_GATEWAYS,
                     None,
                     Some(adapter_ptr_mut),
                     &mut buffer_size,
@@ -114,6 +116,11 @@ impl NetworkAdapters {
         }
     }
 
+    /// Iterates only the adapters whose operational status is `IfOperStatusUp`.
+    pub fn iter_up(&self) -> impl Iterator<Item = &IP_ADAPTER_ADDRESSES_LH> {
+        self.iter().filter(|adapter| adapter.is_up())
+    }
+
     pub fn is_empty(&self) -> bool {
         self.buffer.is_empty()
     }
@@ -175,6 +182,7 @@ mod test {
         println!("Adapters: {:#?}", adapter_display);
         println!("Enumerated {count} adapters");
         assert!(count > 0, "expected at least one adapter");
+        assert!(adapters.iter_up().count() <= count);
         adapters.refresh()?;
         Ok(())
     }
@@ -0,0 +1,160 @@
+use crate::network::NetworkAdapterExt;
+use crate::network::NetworkAdapters;
+use crate::network::NetworkInterfaceId;
+use std::net::IpAddr;
+use windows::Win32::NetworkManagement::Ndis::IF_OPER_STATUS;
+
+/// A change between two successive snapshots of the network interfaces.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InterfaceEvent {
+    Added(NetworkInterfaceSnapshot),
+    Removed(NetworkInterfaceSnapshot),
+    StatusChanged {
+        interface: NetworkInterfaceSnapshot,
+        from: IF_OPER_STATUS,
+        to: IF_OPER_STATUS,
+    },
+    AddressChanged {
+        interface: NetworkInterfaceSnapshot,
+        from: Vec<IpAddr>,
+        to: Vec<IpAddr>,
+    },
+}
+
+/// Owned summary of an adapter, comparable across `NetworkAdapters` refreshes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetworkInterfaceSnapshot {
+    pub id: NetworkInterfaceId,
+    pub name: String,
+    pub oper_status: IF_OPER_STATUS,
+    pub addresses: Vec<IpAddr>,
+}
+
+impl NetworkInterfaceSnapshot {
+    pub fn capture(adapters: &NetworkAdapters) -> Vec<Self> {
+        adapters
+            .iter()
+            .map(|adapter| Self {
+                id: adapter.id(),
+                name: adapter.display_name().into_owned(),
+                oper_status: adapter.OperStatus,
+                addresses: adapter.unicast_addresses(),
+            })
+            .collect()
+    }
+}
+
+/// Computes the events that turn `previous` into `current`, matching interfaces by id.
+pub fn diff_interfaces(
+    previous: &[NetworkInterfaceSnapshot],
+    current: &[NetworkInterfaceSnapshot],
+) -> Vec<InterfaceEvent> {
+    let mut events = Vec::new();
+
+    for before in previous {
+        if !current.iter().any(|after| after.id == before.id) {
+            events.push(InterfaceEvent::Removed(before.clone()));
+        }
+    }
+
+    for after in current {
+        let Some(before) = previous.iter().find(|before| before.id == after.id) else {
+            events.push(InterfaceEvent::Added(after.clone()));
+            continue;
+        };
+        if before.oper_status != after.oper_status {
+            events.push(InterfaceEvent::StatusChanged {
+                interface: after.clone(),
+                from: before.oper_status,
+                to: after.oper_status,
+            });
+        }
+        if before.addresses != after.addresses {
+            events.push(InterfaceEvent::AddressChanged {
+                interface: after.clone(),
+                from: before.addresses.clone(),
+                to: after.addresses.clone(),
+            });
+        }
+    }
+
+    events
+}
+
+/// Polls the adapter list and reports typed changes since the previous poll.
+#[derive(Debug)]
+pub struct NetworkInterfaceWatcher {
+    adapters: NetworkAdapters,
+    previous: Vec<NetworkInterfaceSnapshot>,
+}
+
+impl NetworkInterfaceWatcher {
+    /// Takes the initial snapshot; the first `poll` reports changes relative to it.
+    pub fn new() -> eyre::Result<Self> {
+        let adapters = NetworkAdapters::new()?;
+        let previous = NetworkInterfaceSnapshot::capture(&adapters);
+        Ok(Self { adapters, previous })
+    }
+
+    pub fn poll(&mut self) -> eyre::Result<Vec<InterfaceEvent>> {
+        self.adapters.refresh()?;
+        let current = NetworkInterfaceSnapshot::capture(&self.adapters);
+        let events = diff_interfaces(&self.previous, &current);
+        self.previous = current;
+        Ok(events)
+    }
+
+    pub fn interfaces(&self) -> &[NetworkInterfaceSnapshot] {
+        &self.previous
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::InterfaceEvent;
+    use super::NetworkInterfaceSnapshot;
+    use super::diff_interfaces;
+    use crate::network::NetworkInterfaceId;
+    use std::net::IpAddr;
+    use std::net::Ipv4Addr;
+    use windows::Win32::NetworkManagement::Ndis::IfOperStatusDown;
+    use windows::Win32::NetworkManagement::Ndis::IfOperStatusUp;
+
+    fn snapshot(index: u32, addresses: Vec<IpAddr>) -> NetworkInterfaceSnapshot {
+        NetworkInterfaceSnapshot {
+            id: NetworkInterfaceId::Index(index),
+            name: format!("Adapter {index}"),
+            oper_status: IfOperStatusUp,
+            addresses,
+        }
+    }
+
+    #[test]
+    fn diffs_snapshots() {
+        let address = IpAddr::V4(Ipv4Addr::new(192, 168, 1, 10));
+        let previous = vec![snapshot(1, vec![]), snapshot(2, vec![])];
+        let mut changed = snapshot(2, vec![address]);
+        changed.oper_status = IfOperStatusDown;
+        let current = vec![changed.clone(), snapshot(3, vec![])];
+
+        let events = diff_interfaces(&previous, &current);
+        assert_eq!(
+            events,
+            vec![
+                InterfaceEvent::Removed(snapshot(1, vec![])),
+                InterfaceEvent::StatusChanged {
+                    interface: changed.clone(),
+                    from: IfOperStatusUp,
+                    to: IfOperStatusDown,
+                },
+                InterfaceEvent::AddressChanged {
+                    interface: changed,
+                    from: vec![],
+                    to: vec![address],
+                },
+                InterfaceEvent::Added(snapshot(3, vec![])),
+            ]
+        );
+        assert!(diff_interfaces(&current, &current).is_empty());
+    }
+}
@@ -1,8 +1,10 @@
 use std::borrow::Cow;
 use windows::Win32::NetworkManagement::Ndis::IF_OPER_STATUS;
+use windows::Win32::NetworkManagement::Ndis::IfOperStatusUp;
 
 pub trait OperStatusExt {
     fn display(&self) -> Cow<'_, str>;
+    fn is_up(&self) -> bool;
 }
 impl OperStatusExt for IF_OPER_STATUS {
     fn display(&self) -> Cow<'_, str> {
@@ -17,4 +19,7 @@ impl OperStatusExt for IF_OPER_STATUS {
             x => Cow::Owned(format!("InvalidStatus({x})")),
         }
     }
+    fn is_up(&self) -> bool {
+        *self == IfOperStatusUp
+    }
 }
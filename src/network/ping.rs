@@ -0,0 +1,204 @@
+use eyre::Context;
+use std::fmt;
+use std::net::IpAddr;
+use std::net::Ipv4Addr;
+use std::net::Ipv6Addr;
+use std::time::Duration;
+use windows::Win32::Foundation::HANDLE;
+use windows::Win32::Foundation::WIN32_ERROR;
+use windows::Win32::NetworkManagement::IpHelper::ICMP_ECHO_REPLY;
+use windows::Win32::NetworkManagement::IpHelper::ICMPV6_ECHO_REPLY_LH;
+use windows::Win32::NetworkManagement::IpHelper::IP_REQ_TIMED_OUT;
+use windows::Win32::NetworkManagement::IpHelper::IP_SUCCESS;
+use windows::Win32::NetworkManagement::IpHelper::Icmp6CreateFile;
+use windows::Win32::NetworkManagement::IpHelper::Icmp6SendEcho2;
+use windows::Win32::NetworkManagement::IpHelper::IcmpCloseHandle;
+use windows::Win32::NetworkManagement::IpHelper::IcmpCreateFile;
+use windows::Win32::NetworkManagement::IpHelper::IcmpSendEcho;
+use windows::Win32::Networking::WinSock::AF_INET6;
+use windows::Win32::Networking::WinSock::IN6_ADDR;
+use windows::Win32::Networking::WinSock::IN6_ADDR_0;
+use windows::Win32::Networking::WinSock::SOCKADDR_IN6;
+use windows::Win32::System::IO::IO_STATUS_BLOCK;
+
+const PING_PAYLOAD: &[u8; 32] = b"teamy-windows ping payload......";
+
+/// A successful ICMP echo reply.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PingResult {
+    pub rtt: Duration,
+    /// Time-to-live of the reply. Only reported for IPv4.
+    pub ttl: Option<u8>,
+}
+
+/// Why a ping did not get a reply. Recover it from the returned report with `downcast_ref::<PingError>()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PingError {
+    /// No reply within the timeout. The host may be down, or ICMP may be blocked along the way.
+    TimedOut,
+    /// A router or the host answered that the destination can't be reached.
+    /// `status` is the `IP_STATUS` code, e.g. `IP_DEST_HOST_UNREACHABLE`.
+    Unreachable { status: u32 },
+}
+
+impl fmt::Display for PingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PingError::TimedOut => write!(f, "Ping timed out"),
+            PingError::Unreachable { status } => {
+                write!(f, "Destination unreachable (IP_STATUS {status})")
+            }
+        }
+    }
+}
+
+impl std::error::Error for PingError {}
+
+/// Sends a single ICMP echo request and waits up to `timeout` for the reply.
+/// <https://learn.microsoft.com/en-us/windows/win32/api/icmpapi/nf-icmpapi-icmpsendecho>
+pub fn ping(addr: IpAddr, timeout: Duration) -> eyre::Result<PingResult> {
+    let timeout_ms = timeout.as_millis().clamp(1, u32::MAX as u128) as u32;
+    match addr {
+        IpAddr::V4(addr) => ping_v4(addr, timeout_ms),
+        IpAddr::V6(addr) => ping_v6(addr, timeout_ms),
+    }
+}
+
+fn ping_v4(addr: Ipv4Addr, timeout_ms: u32) -> eyre::Result<PingResult> {
+    let handle = IcmpHandle(unsafe { IcmpCreateFile() }.wrap_err("Failed to open ICMP handle")?);
+
+    // Room for one reply, the echoed payload and an ICMP error message, 8-byte aligned for the reply struct
+    let mut reply_buffer =
+        vec![0u64; (size_of::<ICMP_ECHO_REPLY>() + PING_PAYLOAD.len() + 8).div_ceil(8)];
+    let reply_count = unsafe {
+        IcmpSendEcho(
+            handle.0,
+            u32::from_ne_bytes(addr.octets()),
+            PING_PAYLOAD.as_ptr().cast(),
+            PING_PAYLOAD.len() as u16,
+            None,
+            reply_buffer.as_mut_ptr().cast(),
+            (reply_buffer.len() * 8) as u32,
+            timeout_ms,
+        )
+    };
+    if reply_count == 0 {
+        return Err(no_reply_error(addr.into()));
+    }
+
+    let reply = unsafe { *(reply_buffer.as_ptr() as *const ICMP_ECHO_REPLY) };
+    check_status(reply.Status)?;
+    Ok(PingResult {
+        rtt: Duration::from_millis(reply.RoundTripTime as u64),
+        ttl: Some(reply.Options.Ttl),
+    })
+}
+
+fn ping_v6(addr: Ipv6Addr, timeout_ms: u32) -> eyre::Result<PingResult> {
+    let handle = IcmpHandle(unsafe { Icmp6CreateFile() }.wrap_err("Failed to open ICMPv6 handle")?);
+
+    let source = SOCKADDR_IN6 {
+        sin6_family: AF_INET6,
+        ..Default::default()
+    };
+    let destination = SOCKADDR_IN6 {
+        sin6_family: AF_INET6,
+        sin6_addr: IN6_ADDR {
+            u: IN6_ADDR_0 {
+                Byte: addr.octets(),
+            },
+        },
+        ..Default::default()
+    };
+
+    // Icmp6SendEcho2 needs room for the reply, the echoed data, 8 bytes of ICMP error and an IO_STATUS_BLOCK
+    let reply_size =
+        size_of::<ICMPV6_ECHO_REPLY_LH>() + PING_PAYLOAD.len() + 8 + size_of::<IO_STATUS_BLOCK>();
+    let mut reply_buffer = vec![0u64; reply_size.div_ceil(8)];
+    let reply_count = unsafe {
+        Icmp6SendEcho2(
+            handle.0,
+            None,
+            None,
+            None,
+            &source,
+            &destination,
+            PING_PAYLOAD.as_ptr().cast(),
+            PING_PAYLOAD.len() as u16,
+            None,
+            reply_buffer.as_mut_ptr().cast(),
+            (reply_buffer.len() * 8) as u32,
+            timeout_ms,
+        )
+    };
+    if reply_count == 0 {
+        return Err(no_reply_error(addr.into()));
+    }
+
+    let reply = unsafe { *(reply_buffer.as_ptr() as *const ICMPV6_ECHO_REPLY_LH) };
+    check_status(reply.Status)?;
+    Ok(PingResult {
+        rtt: Duration::from_millis(reply.RoundTripTime as u64),
+        ttl: None,
+    })
+}
+
+/// Classifies the last error after a send that produced no replies.
+fn no_reply_error(addr: IpAddr) -> eyre::Report {
+    let error = windows::core::Error::from_thread();
+    let status = WIN32_ERROR::from_error(&error).map(|e| e.0);
+    match status {
+        Some(IP_REQ_TIMED_OUT) => eyre::Report::new(PingError::TimedOut),
+        Some(status) if is_ip_status(status) => {
+            eyre::Report::new(PingError::Unreachable { status })
+        }
+        _ => eyre::Report::new(error),
+    }
+    .wrap_err(format!("Failed to ping {addr}"))
+}
+
+fn check_status(status: u32) -> Result<(), PingError> {
+    match status {
+        IP_SUCCESS => Ok(()),
+        IP_REQ_TIMED_OUT => Err(PingError::TimedOut),
+        status => Err(PingError::Unreachable { status }),
+    }
+}
+
+/// `IP_STATUS` codes live in the 11000 range, distinct from ordinary Win32 errors.
+fn is_ip_status(code: u32) -> bool {
+    (11000..12000).contains(&code)
+}
+
+struct IcmpHandle(HANDLE);
+
+impl Drop for IcmpHandle {
+    fn drop(&mut self) {
+        _ = unsafe { IcmpCloseHandle(self.0) };
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ping;
+    use std::net::IpAddr;
+    use std::net::Ipv4Addr;
+    use std::net::Ipv6Addr;
+    use std::time::Duration;
+
+    #[test]
+    fn pings_localhost() -> eyre::Result<()> {
+        let result = ping(IpAddr::V4(Ipv4Addr::LOCALHOST), Duration::from_secs(1))?;
+        println!("Ping result: {result:?}");
+        assert!(result.ttl.is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn pings_ipv6_localhost() -> eyre::Result<()> {
+        let result = ping(IpAddr::V6(Ipv6Addr::LOCALHOST), Duration::from_secs(1))?;
+        println!("Ping result: {result:?}");
+        assert!(result.ttl.is_none());
+        Ok(())
+    }
+}
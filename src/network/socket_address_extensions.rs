@@ -0,0 +1,38 @@
+use std::net::IpAddr;
+use std::net::Ipv4Addr;
+use std::net::Ipv6Addr;
+use windows::Win32::Networking::WinSock::AF_INET;
+use windows::Win32::Networking::WinSock::AF_INET6;
+use windows::Win32::Networking::WinSock::SOCKADDR_IN;
+use windows::Win32::Networking::WinSock::SOCKADDR_IN6;
+use windows::Win32::Networking::WinSock::SOCKET_ADDRESS;
+
+pub trait SocketAddressExt {
+    /// Converts an IPv4 or IPv6 socket address to an `IpAddr`, returning `None` for other families.
+    fn ip_addr(&self) -> Option<IpAddr>;
+}
+impl SocketAddressExt for SOCKET_ADDRESS {
+    fn ip_addr(&self) -> Option<IpAddr> {
+        if self.lpSockaddr.is_null() {
+            return None;
+        }
+        let family = unsafe { (*self.lpSockaddr).sa_family };
+        let length = self.iSockaddrLength as usize;
+        if family == AF_INET && length >= size_of::<SOCKADDR_IN>() {
+            let addr = unsafe { *(self.lpSockaddr as *const SOCKADDR_IN) };
+            let octets = unsafe { addr.sin_addr.S_un.S_un_b };
+            Some(IpAddr::V4(Ipv4Addr::new(
+                octets.s_b1,
+                octets.s_b2,
+                octets.s_b3,
+                octets.s_b4,
+            )))
+        } else if family == AF_INET6 && length >= size_of::<SOCKADDR_IN6>() {
+            let addr = unsafe { *(self.lpSockaddr as *const SOCKADDR_IN6) };
+            let octets = unsafe { addr.sin6_addr.u.Byte };
+            Some(IpAddr::V6(Ipv6Addr::from(octets)))
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,193 @@
+use eyre::bail;
+use std::ops::Deref;
+use windows::Win32::Foundation::ERROR_SERVICE_NOT_ACTIVE;
+use windows::Win32::Foundation::HANDLE;
+use windows::Win32::Foundation::NO_ERROR;
+use windows::Win32::Foundation::WIN32_ERROR;
+use windows::Win32::NetworkManagement::WiFi::WLAN_CONNECTION_ATTRIBUTES;
+use windows::Win32::NetworkManagement::WiFi::WLAN_INTERFACE_INFO;
+use windows::Win32::NetworkManagement::WiFi::WLAN_INTERFACE_INFO_LIST;
+use windows::Win32::NetworkManagement::WiFi::WLAN_INTF_OPCODE;
+use windows::Win32::NetworkManagement::WiFi::WlanCloseHandle;
+use windows::Win32::NetworkManagement::WiFi::WlanEnumInterfaces;
+use windows::Win32::NetworkManagement::WiFi::WlanFreeMemory;
+use windows::Win32::NetworkManagement::WiFi::WlanOpenHandle;
+use windows::Win32::NetworkManagement::WiFi::WlanQueryInterface;
+use windows::Win32::NetworkManagement::WiFi::wlan_interface_state_connected;
+use windows::Win32::NetworkManagement::WiFi::wlan_intf_opcode_channel_number;
+use windows::Win32::NetworkManagement::WiFi::wlan_intf_opcode_current_connection;
+use windows::core::GUID;
+
+/// WLAN API version for Windows Vista and later.
+const WLAN_CLIENT_VERSION: u32 = 2;
+
+/// The wireless network the machine is currently connected to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WifiInfo {
+    pub ssid: String,
+    /// Access point MAC address formatted as `aa:bb:cc:dd:ee:ff`.
+    pub bssid: String,
+    /// Signal quality from 0 to 100.
+    pub signal_percent: u32,
+    /// The channel number, if the driver reports it.
+    pub channel: Option<u32>,
+}
+
+/// Returns the first connected wireless interface's network details.
+///
+/// Returns `Ok(None)` when there is no wireless adapter, the WLAN service isn't running, or nothing is connected.
+/// <https://learn.microsoft.com/en-us/windows/win32/api/wlanapi/nf-wlanapi-wlanqueryinterface>
+pub fn current_wifi() -> eyre::Result<Option<WifiInfo>> {
+    let Some(client) = WlanClient::open()? else {
+        return Ok(None);
+    };
+
+    let interfaces = client.enum_interfaces()?;
+    for interface in interfaces.interfaces() {
+        if interface.isState != wlan_interface_state_connected {
+            continue;
+        }
+
+        let connection = client.query_interface::<WLAN_CONNECTION_ATTRIBUTES>(
+            &interface.InterfaceGuid,
+            wlan_intf_opcode_current_connection,
+        )?;
+        let association = &connection.wlanAssociationAttributes;
+        let ssid_len = (association.dot11Ssid.uSSIDLength as usize).min(32);
+        let ssid = String::from_utf8_lossy(&association.dot11Ssid.ucSSID[..ssid_len]).into_owned();
+        let bssid = association
+            .dot11Bssid
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect::<Vec<_>>()
+            .join(":");
+
+        // Not all drivers report the channel, treat that as missing rather than failing
+        let channel = client
+            .query_interface::<u32>(&interface.InterfaceGuid, wlan_intf_opcode_channel_number)
+            .ok()
+            .map(|channel| *channel);
+
+        return Ok(Some(WifiInfo {
+            ssid,
+            bssid,
+            signal_percent: association.wlanSignalQuality,
+            channel,
+        }));
+    }
+
+    Ok(None)
+}
+
+/// Owns a WLAN client handle, closed on drop.
+struct WlanClient(HANDLE);
+
+impl WlanClient {
+    fn open() -> eyre::Result<Option<Self>> {
+        let mut negotiated_version = 0u32;
+        let mut handle = HANDLE::default();
+        let status = unsafe {
+            WlanOpenHandle(
+                WLAN_CLIENT_VERSION,
+                None,
+                &mut negotiated_version,
+                &mut handle,
+            )
+        };
+        match WIN32_ERROR(status) {
+            NO_ERROR => Ok(Some(Self(handle))),
+            // The WLAN AutoConfig service only runs on machines with wireless hardware
+            ERROR_SERVICE_NOT_ACTIVE => Ok(None),
+            other => {
+                let message = other.to_hresult().message();
+                bail!("WlanOpenHandle failed: {message}");
+            }
+        }
+    }
+
+    fn enum_interfaces(&self) -> eyre::Result<WlanMemory<WLAN_INTERFACE_INFO_LIST>> {
+        let mut list = std::ptr::null_mut();
+        let status = unsafe { WlanEnumInterfaces(self.0, None, &mut list) };
+        if WIN32_ERROR(status) != NO_ERROR {
+            let message = WIN32_ERROR(status).to_hresult().message();
+            bail!("WlanEnumInterfaces failed: {message}");
+        }
+        Ok(WlanMemory(list))
+    }
+
+    fn query_interface<T>(
+        &self,
+        interface: &GUID,
+        opcode: WLAN_INTF_OPCODE,
+    ) -> eyre::Result<WlanMemory<T>> {
+        let mut data_size = 0u32;
+        let mut data = std::ptr::null_mut();
+        let status = unsafe {
+            WlanQueryInterface(
+                self.0,
+                interface,
+                opcode,
+                None,
+                &mut data_size,
+                &mut data,
+                None,
+            )
+        };
+        if WIN32_ERROR(status) != NO_ERROR {
+            let message = WIN32_ERROR(status).to_hresult().message();
+            bail!("WlanQueryInterface({}) failed: {message}", opcode.0);
+        }
+        if (data_size as usize) < size_of::<T>() {
+            drop(WlanMemory(data));
+            bail!(
+                "WlanQueryInterface({}) returned {data_size} bytes, expected at least {}",
+                opcode.0,
+                size_of::<T>()
+            );
+        }
+        Ok(WlanMemory(data as *mut T))
+    }
+}
+
+impl Drop for WlanClient {
+    fn drop(&mut self) {
+        unsafe { WlanCloseHandle(self.0, None) };
+    }
+}
+
+/// Memory allocated by the WLAN API, released with `WlanFreeMemory` on drop.
+struct WlanMemory<T>(*mut T);
+
+impl WlanMemory<WLAN_INTERFACE_INFO_LIST> {
+    fn interfaces(&self) -> &[WLAN_INTERFACE_INFO] {
+        let count = self.dwNumberOfItems as usize;
+        // InterfaceInfo is a variable length array with dwNumberOfItems entries
+        unsafe { std::slice::from_raw_parts(self.InterfaceInfo.as_ptr(), count) }
+    }
+}
+
+impl<T> Deref for WlanMemory<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { &*self.0 }
+    }
+}
+
+impl<T> Drop for WlanMemory<T> {
+    fn drop(&mut self) {
+        if !self.0.is_null() {
+            unsafe { WlanFreeMemory(self.0 as *const _) };
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn current_wifi_does_not_fail() -> eyre::Result<()> {
+        let wifi = super::current_wifi()?;
+        println!("Current Wi-Fi: {wifi:#?}");
+        Ok(())
+    }
+}
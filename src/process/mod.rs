@@ -0,0 +1,3 @@
+mod process_parameters;
+
+pub use process_parameters::*;
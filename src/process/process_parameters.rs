@@ -0,0 +1,224 @@
+use crate::error::describe_win32_error;
+use eyre::Context;
+use eyre::bail;
+use eyre::ensure;
+use std::mem::MaybeUninit;
+use std::mem::offset_of;
+use windows::Wdk::System::Threading::NtQueryInformationProcess;
+use windows::Wdk::System::Threading::ProcessBasicInformation;
+use windows::Win32::Foundation::ERROR_ACCESS_DENIED;
+use windows::Win32::Foundation::ERROR_PARTIAL_COPY;
+use windows::Win32::Foundation::HANDLE;
+use windows::Win32::Foundation::UNICODE_STRING;
+use windows::Win32::System::Diagnostics::Debug::ReadProcessMemory;
+use windows::Win32::System::Threading::OpenProcess;
+use windows::Win32::System::Threading::PEB;
+use windows::Win32::System::Threading::PROCESS_BASIC_INFORMATION;
+use windows::Win32::System::Threading::PROCESS_QUERY_INFORMATION;
+use windows::Win32::System::Threading::PROCESS_VM_READ;
+use windows::Win32::System::Threading::RTL_USER_PROCESS_PARAMETERS;
+use windows::core::Owned;
+
+/// `Environment` directly follows `CommandLine`, past the end of the public `RTL_USER_PROCESS_PARAMETERS`.
+const ENVIRONMENT_OFFSET: usize =
+    offset_of!(RTL_USER_PROCESS_PARAMETERS, CommandLine) + size_of::<UNICODE_STRING>();
+
+/// Offset of `EnvironmentSize` in `RTL_USER_PROCESS_PARAMETERS`, present since Windows Vista.
+#[cfg(target_pointer_width = "64")]
+const ENVIRONMENT_SIZE_OFFSET: usize = 0x3F0;
+#[cfg(target_pointer_width = "32")]
+const ENVIRONMENT_SIZE_OFFSET: usize = 0x290;
+
+/// Caps how much of another process we are willing to copy for its environment block.
+const MAX_ENVIRONMENT_BYTES: usize = 16 * 1024 * 1024;
+
+/// Reads the command line another process was started with, e.g. to tell apart several instances of the same exe.
+///
+/// This reads the target's PEB with `NtQueryInformationProcess` and `ReadProcessMemory`, which needs
+/// `PROCESS_QUERY_INFORMATION | PROCESS_VM_READ`. Processes of other users, elevated processes when we aren't,
+/// and protected processes refuse that access, and the error says so.
+/// The target must have the same bitness as this process, or be a 32-bit process read from a 64-bit one.
+/// <https://learn.microsoft.com/en-us/windows/win32/api/winternl/nf-winternl-ntqueryinformationprocess>
+pub fn get_process_command_line(pid: u32) -> eyre::Result<String> {
+    let process = open_for_read(pid)?;
+    let parameters = read_process_parameters_address(&process, pid)?;
+    let command_line: UNICODE_STRING = read_struct(
+        &process,
+        parameters + offset_of!(RTL_USER_PROCESS_PARAMETERS, CommandLine),
+    )
+    .wrap_err_with(|| format!("Failed to read command line descriptor of process {pid}"))?;
+    let bytes = read_bytes(
+        &process,
+        command_line.Buffer.0 as usize,
+        command_line.Length as usize,
+    )
+    .wrap_err_with(|| format!("Failed to read command line of process {pid}"))?;
+    Ok(utf16_bytes_to_string(&bytes))
+}
+
+/// Reads the environment variables of another process, as `(name, value)` pairs in block order.
+///
+/// Requires the same access as [`get_process_command_line`]. Entries the process keeps for drive
+/// working directories, such as `=C:=C:\Users`, are included as they appear.
+pub fn get_process_environment(pid: u32) -> eyre::Result<Vec<(String, String)>> {
+    let process = open_for_read(pid)?;
+    let parameters = read_process_parameters_address(&process, pid)?;
+    let environment: usize = read_struct(&process, parameters + ENVIRONMENT_OFFSET)
+        .wrap_err_with(|| format!("Failed to read environment address of process {pid}"))?;
+    let size: usize = read_struct(&process, parameters + ENVIRONMENT_SIZE_OFFSET)
+        .wrap_err_with(|| format!("Failed to read environment size of process {pid}"))?;
+    ensure!(
+        size <= MAX_ENVIRONMENT_BYTES,
+        "Environment of process {pid} claims {size} bytes, more than the {MAX_ENVIRONMENT_BYTES} byte limit"
+    );
+    let bytes = read_bytes(&process, environment, size)
+        .wrap_err_with(|| format!("Failed to read environment of process {pid}"))?;
+    Ok(parse_environment_block(&utf16_bytes_to_string(&bytes)))
+}
+
+/// Splits a `NAME=value\0NAME=value\0\0` environment block.
+fn parse_environment_block(block: &str) -> Vec<(String, String)> {
+    block
+        .split('\0')
+        .take_while(|entry| !entry.is_empty())
+        .map(|entry| {
+            // Skip the first character so hidden `=C:` style names keep their leading `=`
+            let split = entry
+                .char_indices()
+                .skip(1)
+                .find(|(_, c)| *c == '=')
+                .map(|(i, _)| i);
+            match split {
+                Some(i) => (entry[..i].to_string(), entry[i + 1..].to_string()),
+                None => (entry.to_string(), String::new()),
+            }
+        })
+        .collect()
+}
+
+fn open_for_read(pid: u32) -> eyre::Result<Owned<HANDLE>> {
+    match unsafe { OpenProcess(PROCESS_QUERY_INFORMATION | PROCESS_VM_READ, false, pid) } {
+        Ok(handle) => Ok(unsafe { Owned::new(handle) }),
+        Err(e) if e.code() == ERROR_ACCESS_DENIED.to_hresult() => bail!(
+            "Access denied opening process {pid} for reading; processes of other users, elevated processes \
+             and protected processes can only be read when running elevated, and protected ones not at all"
+        ),
+        Err(e) => Err(e).wrap_err_with(|| format!("Failed to open process {pid}")),
+    }
+}
+
+fn read_process_parameters_address(process: &Owned<HANDLE>, pid: u32) -> eyre::Result<usize> {
+    let mut info = PROCESS_BASIC_INFORMATION::default();
+    unsafe {
+        NtQueryInformationProcess(
+            **process,
+            ProcessBasicInformation,
+            &mut info as *mut _ as *mut _,
+            size_of::<PROCESS_BASIC_INFORMATION>() as u32,
+            std::ptr::null_mut(),
+        )
+    }
+    .ok()
+    .wrap_err_with(|| format!("Failed to query basic information of process {pid}"))?;
+    ensure!(
+        !info.PebBaseAddress.is_null(),
+        "Process {pid} has no PEB, it may be a minimal or exiting process"
+    );
+    let parameters: usize = read_struct(
+        process,
+        info.PebBaseAddress as usize + offset_of!(PEB, ProcessParameters),
+    )
+    .wrap_err_with(|| format!("Failed to read PEB of process {pid}"))?;
+    ensure!(
+        parameters != 0,
+        "Process {pid} has no process parameters yet"
+    );
+    Ok(parameters)
+}
+
+fn read_struct<T: Copy>(process: &Owned<HANDLE>, address: usize) -> eyre::Result<T> {
+    let mut value = MaybeUninit::<T>::uninit();
+    read_into(process, address, value.as_mut_ptr().cast(), size_of::<T>())?;
+    Ok(unsafe { value.assume_init() })
+}
+
+fn read_bytes(process: &Owned<HANDLE>, address: usize, len: usize) -> eyre::Result<Vec<u8>> {
+    let mut buffer = vec![0u8; len];
+    if len > 0 {
+        read_into(process, address, buffer.as_mut_ptr(), len)?;
+    }
+    Ok(buffer)
+}
+
+fn read_into(
+    process: &Owned<HANDLE>,
+    address: usize,
+    buffer: *mut u8,
+    len: usize,
+) -> eyre::Result<()> {
+    let mut read = 0;
+    let result = unsafe {
+        ReadProcessMemory(
+            **process,
+            address as *const _,
+            buffer.cast(),
+            len,
+            Some(&mut read),
+        )
+    };
+    match result {
+        Ok(()) if read == len => Ok(()),
+        Ok(()) => bail!("Read only {read} of {len} bytes at {address:#x}"),
+        Err(e) if e.code() == ERROR_PARTIAL_COPY.to_hresult() => bail!(
+            "{} reading {len} bytes at {address:#x}; the process may have exited or has a different bitness",
+            describe_win32_error(ERROR_PARTIAL_COPY)
+        ),
+        Err(e) => Err(e).wrap_err_with(|| format!("Failed to read {len} bytes at {address:#x}")),
+    }
+}
+
+fn utf16_bytes_to_string(bytes: &[u8]) -> String {
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .collect();
+    String::from_utf16_lossy(&units)
+}
+
+#[cfg(test)]
+mod test {
+    use super::get_process_command_line;
+    use super::get_process_environment;
+    use super::parse_environment_block;
+
+    #[test]
+    fn parses_environment_block() {
+        let block = "=C:=C:\\Users\0PATH=C:\\Windows;C:\\Tools\0EMPTY=\0\0";
+        assert_eq!(
+            parse_environment_block(block),
+            vec![
+                ("=C:".to_string(), "C:\\Users".to_string()),
+                ("PATH".to_string(), "C:\\Windows;C:\\Tools".to_string()),
+                ("EMPTY".to_string(), String::new()),
+            ]
+        );
+    }
+
+    #[test]
+    fn reads_own_command_line_and_environment() -> eyre::Result<()> {
+        let pid = std::process::id();
+        let command_line = get_process_command_line(pid)?;
+        let exe = std::env::current_exe()?;
+        let exe_name = exe.file_stem().unwrap().to_string_lossy();
+        assert!(command_line.contains(exe_name.as_ref()), "{command_line}");
+
+        let environment = get_process_environment(pid)?;
+        let path = std::env::var("PATH")?;
+        assert!(
+            environment
+                .iter()
+                .any(|(name, value)| name.eq_ignore_ascii_case("PATH") && *value == path)
+        );
+        Ok(())
+    }
+}
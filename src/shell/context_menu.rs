@@ -1,9 +1,13 @@
+use crate::com::co_task_mem::CoTaskMem;
 use crate::com::com_guard::ComGuard;
+use crate::error::last_error_context;
 use crate::shell::path_extensions::PathExtensions;
 use crate::string::EasyPCWSTR;
 use eyre::Result;
 use eyre::bail;
+use facet::Facet;
 use std::path::Path;
+use tracing::warn;
 use windows::Win32::Foundation::*;
 use windows::Win32::System::Com::*;
 use windows::Win32::UI::Shell::Common::*;
@@ -11,11 +15,13 @@ use windows::Win32::UI::Shell::*;
 use windows::Win32::UI::WindowsAndMessaging::*;
 use windows::core::*;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Facet)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct ContextMenuEntry {
     pub id: u32,
     pub label: String,
     pub verb: String,
+    #[facet(recursive_type)]
     pub sub_items: Vec<ContextMenuEntry>,
     pub is_separator: bool,
 }
@@ -24,6 +30,22 @@ pub struct ContextMenuEntry {
 ///
 /// This function calls unsafe Windows APIs.
 pub unsafe fn get_context_menu_entries(path: impl AsRef<Path>) -> Result<Vec<ContextMenuEntry>> {
+    unsafe { get_context_menu_entries_with_flags(path, CMF_NORMAL) }
+}
+
+/// Like [`get_context_menu_entries`], but passes `flags` to `IContextMenu::QueryContextMenu`.
+///
+/// Use `CMF_NORMAL | CMF_EXTENDEDVERBS` to include the verbs only shown on Shift+right-click,
+/// such as "Copy as path".
+/// <https://learn.microsoft.com/en-us/windows/win32/api/shobjidl_core/nf-shobjidl_core-icontextmenu-querycontextmenu>
+///
+/// # Safety
+///
+/// This function calls unsafe Windows APIs.
+pub unsafe fn get_context_menu_entries_with_flags(
+    path: impl AsRef<Path>,
+    flags: u32,
+) -> Result<Vec<ContextMenuEntry>> {
     // Canonicalize path, SHParseDisplayName doesn't always like the verbatim prefix \\?\
     let path = path.as_ref().unc_canonicalize()?;
 
@@ -48,15 +70,16 @@ pub unsafe fn get_context_menu_entries(path: impl AsRef<Path>) -> Result<Vec<Con
         )
     }?;
 
-    if pidl.is_null() {
+    let Some(pidl) = (unsafe { CoTaskMem::from_raw(pidl) }) else {
         bail!("Failed to get PIDL for path: {}", path.display());
-    }
+    };
 
     // 3. Bind to the Parent Folder
     // We need the IShellFolder of the parent, and the relative PIDL of the child
     let mut child_pidl: *mut ITEMIDLIST = std::ptr::null_mut();
 
-    let parent_folder: IShellFolder = unsafe { SHBindToParent(pidl, Some(&mut child_pidl)) }?;
+    let parent_folder: IShellFolder =
+        unsafe { SHBindToParent(pidl.as_ptr(), Some(&mut child_pidl)) }?;
 
     // 4. Get the IContextMenu Interface
     // We ask the parent folder for the Context Menu handler for the child item
@@ -67,24 +90,25 @@ pub unsafe fn get_context_menu_entries(path: impl AsRef<Path>) -> Result<Vec<Con
     let hmenu = unsafe { CreatePopupMenu() }?;
 
     // 6. Ask the interface to populate our menu
-    // Flags: CMF_NORMAL (standard right click).
-    // Use CMF_EXTENDEDVERBS if you want "Shift+RightClick" hidden items.
-    unsafe { context_menu.QueryContextMenu(hmenu, 0, 1, 0x7FFF, CMF_NORMAL) }.ok()?;
+    // Flags: CMF_NORMAL (standard right click), optionally with CMF_EXTENDEDVERBS for "Shift+RightClick" hidden items.
+    unsafe { context_menu.QueryContextMenu(hmenu, 0, 1, 0x7FFF, flags) }.ok()?;
 
     // 7. Iterate and Collect
     let entries = unsafe { walk_menu(hmenu, &context_menu) };
 
     // Cleanup
     unsafe { DestroyMenu(hmenu) }?;
-    unsafe { CoTaskMemFree(Some(pidl as _)) };
-    // Note: child_pidl is a pointer *into* pidl (usually), or managed by SHBindToParent logic,
-    // but strict PIDL management is complex. In simple tools, letting OS cleanup on process exit is common.
+    let entries = entries?;
+    // child_pidl points into pidl, which is freed when it drops at the end of this scope.
 
     Ok(entries)
 }
 
-unsafe fn walk_menu(hmenu: HMENU, context_menu: &IContextMenu) -> Vec<ContextMenuEntry> {
+unsafe fn walk_menu(hmenu: HMENU, context_menu: &IContextMenu) -> Result<Vec<ContextMenuEntry>> {
     let count = unsafe { GetMenuItemCount(Some(hmenu)) };
+    if count < 0 {
+        bail!("GetMenuItemCount failed: {}", last_error_context());
+    }
     let mut entries = Vec::new();
 
     for i in 0..count {
@@ -99,40 +123,44 @@ unsafe fn walk_menu(hmenu: HMENU, context_menu: &IContextMenu) -> Vec<ContextMen
         info.dwTypeData = PWSTR(buffer.as_mut_ptr());
         info.cch = 256;
 
-        if unsafe { GetMenuItemInfoW(hmenu, i as u32, true, &mut info) }.is_ok() {
-            // Check for separators
-            if (info.fType & MFT_SEPARATOR) == MFT_SEPARATOR {
-                entries.push(ContextMenuEntry {
-                    id: 0,
-                    label: "----------------".to_string(),
-                    verb: "".to_string(),
-                    sub_items: vec![],
-                    is_separator: true,
-                });
-                continue;
-            }
-
-            let label = String::from_utf16_lossy(&buffer[..info.cch as usize]);
-
-            // Try to get the "Verb" (Programmatic Name)
-            let verb = unsafe { get_verb(context_menu, info.wID) };
-
-            let mut sub_items = Vec::new();
-            // Recursion for submenus (Expandos)
-            if !info.hSubMenu.is_invalid() {
-                sub_items = unsafe { walk_menu(info.hSubMenu, context_menu) };
-            }
+        // One misbehaving shell extension shouldn't hide the rest of the menu
+        if let Err(e) = unsafe { GetMenuItemInfoW(hmenu, i as u32, true, &mut info) } {
+            warn!("GetMenuItemInfoW failed for menu item {}: {}", i, e);
+            continue;
+        }
 
+        // Check for separators
+        if (info.fType & MFT_SEPARATOR) == MFT_SEPARATOR {
             entries.push(ContextMenuEntry {
-                id: info.wID,
-                label,
-                verb,
-                sub_items,
-                is_separator: false,
+                id: 0,
+                label: "----------------".to_string(),
+                verb: "".to_string(),
+                sub_items: vec![],
+                is_separator: true,
             });
+            continue;
+        }
+
+        let label = String::from_utf16_lossy(&buffer[..info.cch as usize]);
+
+        // Try to get the "Verb" (Programmatic Name)
+        let verb = unsafe { get_verb(context_menu, info.wID) };
+
+        let mut sub_items = Vec::new();
+        // Recursion for submenus (Expandos)
+        if !info.hSubMenu.is_invalid() {
+            sub_items = unsafe { walk_menu(info.hSubMenu, context_menu) }?;
         }
+
+        entries.push(ContextMenuEntry {
+            id: info.wID,
+            label,
+            verb,
+            sub_items,
+            is_separator: false,
+        });
     }
-    entries
+    Ok(entries)
 }
 
 // Helper to try and get the verb string (e.g. "copy", "paste", "transcribe")
@@ -194,4 +222,18 @@ mod test {
         }
         Ok(())
     }
+
+    #[test]
+    fn extended_verbs_add_entries() -> eyre::Result<()> {
+        use windows::Win32::UI::Shell::CMF_EXTENDEDVERBS;
+        use windows::Win32::UI::Shell::CMF_NORMAL;
+
+        let path = file!();
+        let normal = unsafe { super::get_context_menu_entries_with_flags(path, CMF_NORMAL)? };
+        let extended = unsafe {
+            super::get_context_menu_entries_with_flags(path, CMF_NORMAL | CMF_EXTENDEDVERBS)?
+        };
+        assert!(extended.len() >= normal.len());
+        Ok(())
+    }
 }
@@ -1,4 +1,5 @@
 use crate::shell::pidl::Pidl;
+use std::ffi::OsString;
 use std::path::Path;
 use std::path::PathBuf;
 
@@ -9,6 +10,48 @@ pub trait PathExtensions {
     fn unc_simplified(&self) -> &Path {
         dunce::simplified(self.as_path())
     }
+    /// Absolute form of the path with the `\\?\` (or `\\?\UNC\`) prefix, which lifts the `MAX_PATH` limit for Win32 file APIs.
+    ///
+    /// The path is made absolute and normalized first, since the prefix disables `/` and `..` handling.
+    /// Paths that already start with `\\?\` or `\\.\` are returned unchanged.
+    /// <https://learn.microsoft.com/en-us/windows/win32/fileio/maximum-file-path-limitation>
+    fn long_path(&self) -> PathBuf {
+        let path = self.as_path();
+        let raw = path.as_os_str().to_string_lossy();
+        if raw.starts_with(r"\\?\") || raw.starts_with(r"\\.\") {
+            return path.to_path_buf();
+        }
+        let Ok(absolute) = std::path::absolute(path) else {
+            return path.to_path_buf();
+        };
+        let mut rtn = OsString::new();
+        match absolute.as_os_str().to_string_lossy().strip_prefix(r"\\") {
+            Some(unc) => {
+                rtn.push(r"\\?\UNC\");
+                rtn.push(unc);
+            }
+            None => {
+                rtn.push(r"\\?\");
+                rtn.push(absolute.as_os_str());
+            }
+        }
+        PathBuf::from(rtn)
+    }
+    /// Inverse of [`long_path`](PathExtensions::long_path): drops the `\\?\` (or `\\?\UNC\`) prefix when the
+    /// plain form names the same file, so `long_path` followed by `short_path` round-trips.
+    ///
+    /// Paths that only work verbatim are returned unchanged: those reaching `MAX_PATH`, with components ending
+    /// in `.` or space, with reserved device names like `NUL`, or with `/` in a component.
+    /// Disk paths are delegated to [`dunce::simplified`].
+    fn short_path(&self) -> PathBuf {
+        let path = self.as_path();
+        let raw = path.as_os_str().to_string_lossy();
+        match raw.strip_prefix(r"\\?\UNC\") {
+            Some(unc) if is_safe_unc(unc) => PathBuf::from(format!(r"\\{unc}")),
+            Some(_) => path.to_path_buf(),
+            None => dunce::simplified(path).to_path_buf(),
+        }
+    }
     fn to_pidl(&self) -> eyre::Result<Pidl> {
         Pidl::try_new(self.as_path())
     }
@@ -23,3 +66,115 @@ where
         self.as_ref()
     }
 }
+
+/// Whether `\\{unc}` means the same thing as `\\?\UNC\{unc}`, following the same rules `dunce` uses for disk paths.
+fn is_safe_unc(unc: &str) -> bool {
+    const MAX_PATH: usize = 260;
+    const RESERVED: &[&str] = &["CON", "PRN", "AUX", "NUL", "CONIN$", "CONOUT$"];
+    if unc.encode_utf16().count() + 2 >= MAX_PATH || unc.contains('/') {
+        return false;
+    }
+    let mut components = unc.split('\\').peekable();
+    while let Some(component) = components.next() {
+        let is_last = components.peek().is_none();
+        // A trailing separator leaves one empty component, which is fine
+        if component.is_empty() {
+            if is_last {
+                break;
+            }
+            return false;
+        }
+        if component == "." || component == ".." || component.ends_with(['.', ' ']) {
+            return false;
+        }
+        let stem = component.split('.').next().unwrap_or_default().trim_end();
+        let upper = stem.to_ascii_uppercase();
+        let is_numbered_device = (upper.starts_with("COM") || upper.starts_with("LPT"))
+            && upper.len() == 4
+            && upper.as_bytes()[3].is_ascii_digit();
+        if RESERVED.contains(&upper.as_str()) || is_numbered_device {
+            return false;
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod test {
+    use super::PathExtensions;
+    use std::path::PathBuf;
+
+    #[test]
+    fn long_path_adds_prefix() {
+        assert_eq!(r"C:/a/b/../c".long_path(), PathBuf::from(r"\\?\C:\a\c"));
+        assert_eq!(
+            r"\\server\share\dir".long_path(),
+            PathBuf::from(r"\\?\UNC\server\share\dir")
+        );
+        assert_eq!(
+            r"\\?\C:\already".long_path(),
+            PathBuf::from(r"\\?\C:\already")
+        );
+    }
+
+    #[test]
+    fn short_path_round_trips_long_path() {
+        for path in [
+            r"C:\Program Files\My App\settings.json",
+            r"C:\Users\dön\Документы\日本語.txt",
+            r"\\server\share\dir with spaces\file.txt",
+            r"\\server\share\ünïcode\file.txt",
+        ] {
+            let long = path.long_path();
+            assert!(long.as_os_str().to_string_lossy().starts_with(r"\\?\"));
+            assert_eq!(long.short_path(), PathBuf::from(path), "{path}");
+        }
+    }
+
+    #[test]
+    fn short_path_keeps_paths_that_need_verbatim() {
+        let too_long = format!(r"\\?\UNC\server\share\{}", "a".repeat(300));
+        for path in [
+            too_long.as_str(),
+            r"\\?\UNC\server\share\trailing dot.",
+            r"\\?\UNC\server\share\nul.txt",
+            r"\\?\UNC\server\share\COM1",
+            r"\\?\C:\trailing space ",
+        ] {
+            assert_eq!(path.short_path(), PathBuf::from(path), "{path}");
+        }
+        assert_eq!(
+            r"\\?\UNC\server\share\".short_path(),
+            PathBuf::from(r"\\server\share\")
+        );
+    }
+
+    #[test]
+    fn file_round_trips_through_long_and_verbatim_paths() -> eyre::Result<()> {
+        use crate::storage::read_with_progress;
+        use crate::storage::write_atomic;
+
+        let dir = std::env::temp_dir().join(format!(
+            "teamy path round trip {} ünïcødé",
+            std::process::id()
+        ));
+        // Nest deep enough that the file path exceeds MAX_PATH
+        let nested = (0..12).fold(dir.clone(), |path, i| {
+            path.join(format!("segment {i:02} with some padding"))
+        });
+        std::fs::create_dir_all(nested.long_path())?;
+        let path = nested.join("données.txt");
+        assert!(path.as_os_str().len() > 260);
+
+        write_atomic(&path, b"first")?;
+        assert_eq!(read_with_progress(&path, |_, _| {})?, b"first");
+
+        // The verbatim form names the same file
+        write_atomic(&path.long_path(), b"second")?;
+        assert_eq!(read_with_progress(&path, |_, _| {})?, b"second");
+        assert_eq!(read_with_progress(path.long_path(), |_, _| {})?, b"second");
+
+        std::fs::remove_dir_all(dir.long_path())?;
+        Ok(())
+    }
+}
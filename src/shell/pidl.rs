@@ -1,11 +1,15 @@
 use crate::shell::path_extensions::PathExtensions;
 use crate::string::EasyPCWSTR;
+use eyre::Context;
 use std::path::Path;
 use windows::Win32::System::Com::CoTaskMemFree;
 use windows::Win32::UI::Shell::Common::ITEMIDLIST;
 use windows::Win32::UI::Shell::IShellFolder;
+use windows::Win32::UI::Shell::KF_FLAG_DEFAULT;
 use windows::Win32::UI::Shell::SHBindToParent;
+use windows::Win32::UI::Shell::SHGetKnownFolderIDList;
 use windows::Win32::UI::Shell::SHParseDisplayName;
+use windows::core::GUID;
 
 /// RAII wrapper for an owned PIDL (pointer to ITEMIDLIST) that automatically frees with CoTaskMemFree.
 pub struct Pidl(pub *mut ITEMIDLIST);
@@ -25,6 +29,14 @@ impl Pidl {
         Ok(Self(pidl))
     }
 
+    /// Gets the PIDL of a known folder, including virtual folders with no file-system path
+    /// such as `FOLDERID_RecycleBinFolder` or `FOLDERID_ComputerFolder` (This PC).
+    pub fn from_known_folder(folder_id: &GUID) -> eyre::Result<Self> {
+        let pidl = unsafe { SHGetKnownFolderIDList(folder_id, KF_FLAG_DEFAULT.0 as u32, None) }
+            .wrap_err_with(|| format!("Failed to get PIDL for known folder {folder_id:?}"))?;
+        Ok(Self(pidl))
+    }
+
     /// Takes ownership of a raw PIDL pointer.
     ///
     /// # Safety
@@ -0,0 +1,91 @@
+use crate::singleton::SingleInstanceGuard;
+use crate::singleton::try_single_instance;
+use crate::string::EasyPCWSTR;
+use eyre::Context;
+use eyre::bail;
+use tracing::debug;
+use tracing::info;
+use windows::Win32::Foundation::HWND;
+use windows::Win32::Foundation::LPARAM;
+use windows::Win32::Foundation::WPARAM;
+use windows::Win32::UI::WindowsAndMessaging::ASFW_ANY;
+use windows::Win32::UI::WindowsAndMessaging::AllowSetForegroundWindow;
+use windows::Win32::UI::WindowsAndMessaging::ChangeWindowMessageFilterEx;
+use windows::Win32::UI::WindowsAndMessaging::HWND_BROADCAST;
+use windows::Win32::UI::WindowsAndMessaging::MSGFLT_ALLOW;
+use windows::Win32::UI::WindowsAndMessaging::PostMessageW;
+use windows::Win32::UI::WindowsAndMessaging::RegisterWindowMessageW;
+
+/// Returns the registered message a second launch broadcasts to ask the instance named `name` to show itself.
+///
+/// Every process registering the same name gets the same message ID for the current session.
+/// <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-registerwindowmessagew>
+pub fn activation_message(name: &str) -> eyre::Result<u32> {
+    let message_name = format!("teamy-activate-instance:{name}");
+    let message = unsafe { RegisterWindowMessageW(message_name.easy_pcwstr()?.as_ref()) };
+    if message == 0 {
+        return Err(windows::core::Error::from_thread())
+            .wrap_err_with(|| format!("Failed to register activation message {message_name:?}"));
+    }
+    Ok(message)
+}
+
+/// Broadcasts the [`activation_message`] for `name` to the top-level windows of the session.
+///
+/// The receiving instance is allowed to take the foreground, which it otherwise could not do
+/// since the user's input went to the process sending the message.
+pub fn signal_existing_instance(name: &str) -> eyre::Result<()> {
+    let message = activation_message(name)?;
+    if let Err(e) = unsafe { AllowSetForegroundWindow(ASFW_ANY) } {
+        debug!(
+            "Failed to allow the running instance to take the foreground: {}",
+            e
+        );
+    }
+    unsafe { PostMessageW(Some(HWND_BROADCAST), message, WPARAM(0), LPARAM(0)) }
+        .wrap_err_with(|| format!("Failed to broadcast activation message for {name:?}"))?;
+    debug!(name, message, "Signalled running instance");
+    Ok(())
+}
+
+/// Claims the single instance mutex `name`, or signals the instance holding it to show itself.
+///
+/// Returns `None` when another instance was signalled, in which case the caller should exit.
+pub fn single_instance_or_activate(name: &str) -> eyre::Result<Option<SingleInstanceGuard>> {
+    if let Some(guard) = try_single_instance(name)? {
+        return Ok(Some(guard));
+    }
+    info!(
+        name,
+        "Another instance is already running, asking it to show itself"
+    );
+    signal_existing_instance(name)?;
+    Ok(None)
+}
+
+/// Lets `hwnd` receive the [`activation_message`] for `name`, returning the message ID for its wndproc to match on.
+///
+/// The message is allowed through UIPI so a non-elevated launch can still reach an elevated instance.
+/// The window must be top-level to receive the broadcast, which rules out message-only windows.
+pub fn receive_activation_message(hwnd: HWND, name: &str) -> eyre::Result<u32> {
+    if hwnd.is_invalid() {
+        bail!("Cannot receive activation messages on a null window");
+    }
+    let message = activation_message(name)?;
+    unsafe { ChangeWindowMessageFilterEx(hwnd, message, MSGFLT_ALLOW, None) }
+        .wrap_err("Failed to allow the activation message through the message filter")?;
+    Ok(message)
+}
+
+#[cfg(test)]
+mod test {
+    use super::activation_message;
+
+    #[test]
+    fn activation_message_is_stable_per_name() -> eyre::Result<()> {
+        let first = activation_message("teamy-activation-test")?;
+        assert_eq!(first, activation_message("teamy-activation-test")?);
+        assert_ne!(first, activation_message("teamy-activation-test-other")?);
+        Ok(())
+    }
+}
@@ -0,0 +1,5 @@
+mod activate_existing_instance;
+mod single_instance;
+
+pub use activate_existing_instance::*;
+pub use single_instance::*;
@@ -0,0 +1,129 @@
+use crate::string::EasyPCWSTR;
+use eyre::Context;
+use eyre::bail;
+use std::time::Duration;
+use tracing::debug;
+use windows::Win32::Foundation::ERROR_ALREADY_EXISTS;
+use windows::Win32::Foundation::GetLastError;
+use windows::Win32::Foundation::HANDLE;
+use windows::Win32::Foundation::WAIT_ABANDONED;
+use windows::Win32::Foundation::WAIT_OBJECT_0;
+use windows::Win32::Foundation::WAIT_TIMEOUT;
+use windows::Win32::System::Threading::CreateMutexW;
+use windows::Win32::System::Threading::INFINITE;
+use windows::Win32::System::Threading::ReleaseMutex;
+use windows::Win32::System::Threading::WaitForSingleObject;
+use windows::core::Owned;
+
+/// Holds the named mutex that marks this process as the running instance. Released on drop.
+pub struct SingleInstanceGuard {
+    mutex: Owned<HANDLE>,
+    name: String,
+}
+
+impl SingleInstanceGuard {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl Drop for SingleInstanceGuard {
+    fn drop(&mut self) {
+        // Fails if dropped on a different thread than the one that acquired it,
+        // in which case closing the handle abandons the mutex, which waiters also accept
+        _ = unsafe { ReleaseMutex(*self.mutex) };
+        debug!(name = %self.name, "Released single instance mutex");
+    }
+}
+
+/// Claims the named mutex `name`, erroring if another instance already holds it.
+///
+/// Prefix the name with `Local\` to scope it to the current session (the default), or `Global\` for the whole machine.
+/// <https://learn.microsoft.com/en-us/windows/win32/api/synchapi/nf-synchapi-createmutexw>
+pub fn single_instance(name: &str) -> eyre::Result<SingleInstanceGuard> {
+    match try_single_instance(name)? {
+        Some(guard) => Ok(guard),
+        None => bail!("Another instance is already running (mutex {name:?} exists)"),
+    }
+}
+
+/// Like [`single_instance`], but returns `None` when another instance already holds the mutex.
+pub fn try_single_instance(name: &str) -> eyre::Result<Option<SingleInstanceGuard>> {
+    let mutex = unsafe { CreateMutexW(None, true, name.easy_pcwstr()?.as_ref()) }
+        .wrap_err_with(|| format!("Failed to create single instance mutex {name:?}"))?;
+    // Capture the error before anything else can overwrite it
+    let already_exists = unsafe { GetLastError() } == ERROR_ALREADY_EXISTS;
+    let mutex = unsafe { Owned::new(mutex) };
+    if already_exists {
+        debug!(name, "Single instance mutex is held by another instance");
+        return Ok(None);
+    }
+    debug!(name, "Acquired single instance mutex");
+    Ok(Some(SingleInstanceGuard {
+        mutex,
+        name: name.to_string(),
+    }))
+}
+
+/// Like [`single_instance`], but waits up to `timeout` for the running instance to exit instead of erroring.
+///
+/// `None` waits forever.
+pub fn wait_for_single_instance(
+    name: &str,
+    timeout: Option<Duration>,
+) -> eyre::Result<SingleInstanceGuard> {
+    let mutex = unsafe { CreateMutexW(None, false, name.easy_pcwstr()?.as_ref()) }
+        .wrap_err_with(|| format!("Failed to open single instance mutex {name:?}"))?;
+    let mutex = unsafe { Owned::new(mutex) };
+    let timeout_ms = timeout.map_or(INFINITE, |timeout| {
+        timeout.as_millis().min(INFINITE as u128 - 1) as u32
+    });
+    let result = unsafe { WaitForSingleObject(*mutex, timeout_ms) };
+    match result {
+        // An abandoned mutex means the previous instance exited without releasing it, which still hands us ownership
+        WAIT_OBJECT_0 | WAIT_ABANDONED => {
+            debug!(name, "Acquired single instance mutex after waiting");
+            Ok(SingleInstanceGuard {
+                mutex,
+                name: name.to_string(),
+            })
+        }
+        WAIT_TIMEOUT => bail!("Timed out waiting for the running instance to release {name:?}"),
+        _ => Err(windows::core::Error::from_thread())
+            .wrap_err_with(|| format!("Failed to wait for single instance mutex {name:?}")),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::single_instance;
+    use super::wait_for_single_instance;
+    use std::time::Duration;
+
+    #[test]
+    fn second_instance_is_rejected() -> eyre::Result<()> {
+        let name = format!(r"Local\teamy-single-instance-test-{}", std::process::id());
+        let first = single_instance(&name)?;
+        assert!(single_instance(&name).is_err());
+        drop(first);
+        let _again = single_instance(&name)?;
+        Ok(())
+    }
+
+    #[test]
+    fn wait_times_out_while_held() -> eyre::Result<()> {
+        let name = format!(
+            r"Local\teamy-single-instance-wait-test-{}",
+            std::process::id()
+        );
+        let _first = single_instance(&name)?;
+        // Mutexes are re-entrant for the owning thread, so contend from another thread
+        let waited = std::thread::spawn(move || {
+            wait_for_single_instance(&name, Some(Duration::from_millis(50))).is_err()
+        })
+        .join()
+        .unwrap();
+        assert!(waited);
+        Ok(())
+    }
+}
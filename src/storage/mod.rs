@@ -1,9 +1,15 @@
 mod drive_letter_pattern;
 mod onedrive;
 mod read;
+mod read_mmap;
+mod read_with_progress;
 mod watch;
+mod write_atomic;
 
 pub use drive_letter_pattern::*;
 pub use onedrive::*;
 pub use read::*;
+pub use read_mmap::*;
+pub use read_with_progress::*;
 pub use watch::*;
+pub use write_atomic::*;
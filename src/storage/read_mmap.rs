@@ -0,0 +1,143 @@
+use crate::string::EasyPCWSTR;
+use eyre::Context;
+use std::ops::Deref;
+use std::path::Path;
+use windows::Win32::Foundation::HANDLE;
+use windows::Win32::Storage::FileSystem::CreateFileW;
+use windows::Win32::Storage::FileSystem::FILE_ATTRIBUTE_NORMAL;
+use windows::Win32::Storage::FileSystem::FILE_GENERIC_READ;
+use windows::Win32::Storage::FileSystem::FILE_SHARE_READ;
+use windows::Win32::Storage::FileSystem::GetFileSizeEx;
+use windows::Win32::Storage::FileSystem::OPEN_EXISTING;
+use windows::Win32::System::Memory::CreateFileMappingW;
+use windows::Win32::System::Memory::FILE_MAP_READ;
+use windows::Win32::System::Memory::MEMORY_MAPPED_VIEW_ADDRESS;
+use windows::Win32::System::Memory::MapViewOfFile;
+use windows::Win32::System::Memory::PAGE_READONLY;
+use windows::Win32::System::Memory::UnmapViewOfFile;
+use windows::core::Owned;
+use windows::core::PCWSTR;
+
+/// A read-only memory-mapped view of a whole file. Unmapped on drop.
+pub struct Mmap {
+    view: Option<MEMORY_MAPPED_VIEW_ADDRESS>,
+    len: usize,
+    _mapping: Option<Owned<HANDLE>>,
+    _file: Owned<HANDLE>,
+}
+
+impl Mmap {
+    pub fn as_slice(&self) -> &[u8] {
+        match self.view {
+            Some(view) => unsafe { std::slice::from_raw_parts(view.Value as *const u8, self.len) },
+            None => &[],
+        }
+    }
+}
+
+impl Deref for Mmap {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        self.as_slice()
+    }
+}
+
+impl AsRef<[u8]> for Mmap {
+    fn as_ref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl std::fmt::Debug for Mmap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Mmap").field("len", &self.len).finish()
+    }
+}
+
+impl Drop for Mmap {
+    fn drop(&mut self) {
+        if let Some(view) = self.view.take() {
+            _ = unsafe { UnmapViewOfFile(view) };
+        }
+    }
+}
+
+/// Maps a file into memory read-only, avoiding a copy of the whole file into a `Vec`.
+///
+/// Other processes may still open the file for reading, but not for writing, while the map is alive.
+/// <https://learn.microsoft.com/en-us/windows/win32/memory/creating-a-file-view>
+pub fn read_mmap(path: impl AsRef<Path>) -> eyre::Result<Mmap> {
+    let path = path.as_ref();
+    let path_wide = path.easy_pcwstr()?;
+    let raw_handle = unsafe {
+        CreateFileW(
+            path_wide.as_ref(),
+            FILE_GENERIC_READ.0,
+            FILE_SHARE_READ,
+            None,
+            OPEN_EXISTING,
+            FILE_ATTRIBUTE_NORMAL,
+            None,
+        )
+    }
+    .wrap_err_with(|| format!("Failed to open file for mapping: {}", path.display()))?;
+    let file = unsafe { Owned::new(raw_handle) };
+
+    let mut size = 0i64;
+    unsafe { GetFileSizeEx(*file, &mut size) }
+        .wrap_err_with(|| format!("Failed to get file size: {}", path.display()))?;
+    let len = usize::try_from(size).wrap_err("File is too large to map")?;
+
+    // Empty files can't be mapped
+    if len == 0 {
+        return Ok(Mmap {
+            view: None,
+            len,
+            _mapping: None,
+            _file: file,
+        });
+    }
+
+    let raw_mapping =
+        unsafe { CreateFileMappingW(*file, None, PAGE_READONLY, 0, 0, PCWSTR::null()) }
+            .wrap_err_with(|| format!("Failed to create file mapping: {}", path.display()))?;
+    let mapping = unsafe { Owned::new(raw_mapping) };
+
+    let view = unsafe { MapViewOfFile(*mapping, FILE_MAP_READ, 0, 0, 0) };
+    if view.Value.is_null() {
+        return Err(windows::core::Error::from_thread())
+            .wrap_err_with(|| format!("Failed to map view of file: {}", path.display()));
+    }
+
+    Ok(Mmap {
+        view: Some(view),
+        len,
+        _mapping: Some(mapping),
+        _file: file,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::read_mmap;
+
+    #[test]
+    fn maps_file_contents() -> eyre::Result<()> {
+        let dir = std::env::temp_dir();
+        let path = dir.join(format!("teamy-read-mmap-{}.bin", std::process::id()));
+        let empty_path = dir.join(format!("teamy-read-mmap-{}-empty.bin", std::process::id()));
+        std::fs::write(&path, b"hello mmap")?;
+        std::fs::write(&empty_path, b"")?;
+
+        {
+            let mmap = read_mmap(&path)?;
+            assert_eq!(&*mmap, b"hello mmap");
+            assert!(read_mmap(&empty_path)?.is_empty());
+        }
+
+        std::fs::remove_file(&path)?;
+        std::fs::remove_file(&empty_path)?;
+        Ok(())
+    }
+}
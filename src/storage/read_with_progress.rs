@@ -0,0 +1,123 @@
+use crate::shell::path_extensions::PathExtensions;
+use crate::string::EasyPCWSTR;
+use eyre::Context;
+use std::path::Path;
+use windows::Win32::Storage::FileSystem::CreateFileW;
+use windows::Win32::Storage::FileSystem::FILE_FLAG_SEQUENTIAL_SCAN;
+use windows::Win32::Storage::FileSystem::FILE_GENERIC_READ;
+use windows::Win32::Storage::FileSystem::FILE_SHARE_READ;
+use windows::Win32::Storage::FileSystem::GetFileSizeEx;
+use windows::Win32::Storage::FileSystem::OPEN_EXISTING;
+use windows::Win32::Storage::FileSystem::ReadFile;
+use windows::core::Owned;
+
+/// How much [`read_chunks_with_progress`] reads between progress reports.
+pub const READ_PROGRESS_CHUNK_SIZE: usize = 4 * 1024 * 1024;
+
+/// Reads a whole file into memory, calling `on_progress(bytes_read, total_bytes)` after every chunk.
+///
+/// For small files prefer [`std::fs::read`]; this is for files large enough that a UI needs feedback.
+pub fn read_with_progress(
+    path: impl AsRef<Path>,
+    on_progress: impl FnMut(u64, u64),
+) -> eyre::Result<Vec<u8>> {
+    let mut contents = Vec::new();
+    read_chunks_with_progress(
+        path,
+        |chunk| {
+            contents.extend_from_slice(chunk);
+            Ok(())
+        },
+        on_progress,
+    )?;
+    Ok(contents)
+}
+
+/// Streams a file in [`READ_PROGRESS_CHUNK_SIZE`] chunks to `on_chunk`, calling `on_progress(bytes_read, total_bytes)` after each.
+///
+/// Use this to copy or hash multi-GB files without holding them in memory.
+/// `total_bytes` is the size when the file was opened; it can be exceeded if the file grows while being read.
+/// Returns the number of bytes read.
+pub fn read_chunks_with_progress(
+    path: impl AsRef<Path>,
+    mut on_chunk: impl FnMut(&[u8]) -> eyre::Result<()>,
+    mut on_progress: impl FnMut(u64, u64),
+) -> eyre::Result<u64> {
+    let path = path.as_ref();
+    let raw_handle = unsafe {
+        CreateFileW(
+            path.long_path().easy_pcwstr()?.as_ref(),
+            FILE_GENERIC_READ.0,
+            FILE_SHARE_READ,
+            None,
+            OPEN_EXISTING,
+            FILE_FLAG_SEQUENTIAL_SCAN,
+            None,
+        )
+    }
+    .wrap_err_with(|| format!("Failed to open file for reading: {}", path.display()))?;
+    let handle = unsafe { Owned::new(raw_handle) };
+
+    let mut size = 0i64;
+    unsafe { GetFileSizeEx(*handle, &mut size) }
+        .wrap_err_with(|| format!("Failed to get file size: {}", path.display()))?;
+    let total = size as u64;
+    on_progress(0, total);
+
+    let mut buf = vec![0u8; READ_PROGRESS_CHUNK_SIZE];
+    let mut read_so_far = 0u64;
+    loop {
+        let mut bytes_read: u32 = 0;
+        unsafe {
+            ReadFile(
+                *handle,
+                Some(buf.as_mut_slice()),
+                Some(&mut bytes_read),
+                None,
+            )
+        }
+        .wrap_err_with(|| format!("Failed to read {}", path.display()))?;
+        if bytes_read == 0 {
+            break;
+        }
+        on_chunk(&buf[..bytes_read as usize])?;
+        read_so_far += u64::from(bytes_read);
+        on_progress(read_so_far, total);
+    }
+    Ok(read_so_far)
+}
+
+#[cfg(test)]
+mod test {
+    use super::READ_PROGRESS_CHUNK_SIZE;
+    use super::read_with_progress;
+
+    #[test]
+    fn reports_progress_per_chunk() -> eyre::Result<()> {
+        let path = std::env::temp_dir().join(format!(
+            "teamy-read-with-progress-{}.bin",
+            std::process::id()
+        ));
+        let expected: Vec<u8> = (0..READ_PROGRESS_CHUNK_SIZE * 2 + 123)
+            .map(|i| i as u8)
+            .collect();
+        std::fs::write(&path, &expected)?;
+
+        let mut reports = Vec::new();
+        let contents = read_with_progress(&path, |read, total| reports.push((read, total)))?;
+        std::fs::remove_file(&path)?;
+
+        let total = expected.len() as u64;
+        assert_eq!(contents, expected);
+        assert_eq!(
+            reports,
+            vec![
+                (0, total),
+                (READ_PROGRESS_CHUNK_SIZE as u64, total),
+                (READ_PROGRESS_CHUNK_SIZE as u64 * 2, total),
+                (total, total),
+            ]
+        );
+        Ok(())
+    }
+}
@@ -1,3 +1,4 @@
+use crate::shell::path_extensions::PathExtensions;
 use crate::string::EasyPCWSTR;
 use crossbeam_channel::Receiver;
 use crossbeam_channel::unbounded;
@@ -45,75 +46,156 @@ impl WatchConfig {
 /// Watch a file for appended content. Returns a channel receiver of newly appended byte chunks (may be variable sized).
 /// Loop ends when the background thread finishes (currently never unless error). On error, channel is closed.
 pub fn watch_file_content(config: WatchConfig) -> eyre::Result<Receiver<Vec<u8>>> {
-    let path = config.path;
-    if !path.is_file() {
-        eyre::bail!("Path is not a file: {}", path.display());
+    if !config.path.is_file() {
+        eyre::bail!("Path is not a file: {}", config.path.display());
     }
-    let path = path.to_path_buf();
     let (tx, rx) = unbounded::<Vec<u8>>();
 
     // Spawn background reader thread
     thread::Builder::new()
         .name("win-file-content-watch".into())
-        .spawn(move || {
-            // Open via Win32 CreateFileW with shared access
-            let raw_handle = unsafe {
-                CreateFileW(
-                    path.as_path().easy_pcwstr()?.as_ref(),
-                    FILE_GENERIC_READ.0,
-                    FILE_SHARE_READ | FILE_SHARE_WRITE | FILE_SHARE_DELETE,
-                    None,
-                    OPEN_EXISTING,
-                    FILE_ATTRIBUTE_NORMAL,
-                    None,
-                )
-            };
-            let raw_handle = raw_handle
-                .with_context(|| format!("Failed to open file for watching: {}", path.display()))?;
-
-            let handle = unsafe { Owned::new(raw_handle) };
-
-            // Determine starting position
-            let _starting_pos: i64 = {
-                let mut pos: i64 = 0;
-                match config.init_behaviour {
-                    WatchInitBehaviour::ReadFromStart => {
-                        unsafe { SetFilePointerEx(*handle, 0, Some(&mut pos), FILE_BEGIN) }?
-                    }
-                    WatchInitBehaviour::ReadFromEnd => {
-                        unsafe { SetFilePointerEx(*handle, 0, Some(&mut pos), FILE_END) }?
-                    }
-                }
-                pos
-            };
-
-            let mut buf = vec![0u8; config.read_chunk_size.get::<byte>()];
-            loop {
-                // Attempt read
-                let mut bytes_read: u32 = 0;
-                let read_res = unsafe {
-                    ReadFile(
-                        *handle,
-                        Some(buf.as_mut_slice()),
-                        Some(&mut bytes_read),
-                        None,
-                    )
-                };
-                read_res.wrap_err_with(|| format!("ReadFile error watching {}", path.display()))?;
-                if bytes_read > 0 {
-                    let chunk = buf[..bytes_read as usize].to_vec();
-                    if tx.send(chunk).is_err() {
-                        break;
-                    }
-                    continue; // attempt immediate next read (burst)
-                } else {
-                    thread::sleep(Duration::from_millis(150));
-                }
-            }
-            // channel closes when tx dropped
-            eyre::Ok(())
-        })
+        .spawn(move || watch_loop(config, |chunk| tx.send(chunk).is_ok(), || false))
         .wrap_err("Failed to spawn win-file-content-watch thread")?;
 
     Ok(rx)
 }
+
+/// Async flavour of [`watch_file_content`] for tokio apps; the receiver works directly in `tokio::select!`.
+///
+/// Reading happens on tokio's blocking pool. Wrap the receiver in
+/// `tokio_stream::wrappers::UnboundedReceiverStream` if you need a `Stream`.
+/// Must be called from within a tokio runtime.
+#[cfg(feature = "tokio")]
+pub fn watch_file_content_async(
+    config: WatchConfig,
+) -> eyre::Result<tokio::sync::mpsc::UnboundedReceiver<Vec<u8>>> {
+    let (rx, _task) = spawn_async_watch(config)?;
+    Ok(rx)
+}
+
+#[cfg(feature = "tokio")]
+fn spawn_async_watch(
+    config: WatchConfig,
+) -> eyre::Result<(
+    tokio::sync::mpsc::UnboundedReceiver<Vec<u8>>,
+    tokio::task::JoinHandle<eyre::Result<()>>,
+)> {
+    if !config.path.is_file() {
+        eyre::bail!("Path is not a file: {}", config.path.display());
+    }
+    let (tx, rx) = tokio::sync::mpsc::unbounded_channel::<Vec<u8>>();
+    let task = tokio::task::spawn_blocking(move || {
+        watch_loop(config, |chunk| tx.send(chunk).is_ok(), || tx.is_closed())
+    });
+    Ok((rx, task))
+}
+
+/// Reads appended content until `send` or `is_closed` reports the receiver is gone, or an error occurs.
+///
+/// `is_closed` is checked while idle, so a watcher of a file that stopped growing still ends.
+fn watch_loop(
+    config: WatchConfig,
+    mut send: impl FnMut(Vec<u8>) -> bool,
+    is_closed: impl Fn() -> bool,
+) -> eyre::Result<()> {
+    let path = config.path;
+
+    // Open via Win32 CreateFileW with shared access
+    let raw_handle = unsafe {
+        CreateFileW(
+            path.long_path().easy_pcwstr()?.as_ref(),
+            FILE_GENERIC_READ.0,
+            FILE_SHARE_READ | FILE_SHARE_WRITE | FILE_SHARE_DELETE,
+            None,
+            OPEN_EXISTING,
+            FILE_ATTRIBUTE_NORMAL,
+            None,
+        )
+    };
+    let raw_handle = raw_handle
+        .with_context(|| format!("Failed to open file for watching: {}", path.display()))?;
+
+    let handle = unsafe { Owned::new(raw_handle) };
+
+    // Determine starting position
+    let _starting_pos: i64 = {
+        let mut pos: i64 = 0;
+        match config.init_behaviour {
+            WatchInitBehaviour::ReadFromStart => {
+                unsafe { SetFilePointerEx(*handle, 0, Some(&mut pos), FILE_BEGIN) }?
+            }
+            WatchInitBehaviour::ReadFromEnd => {
+                unsafe { SetFilePointerEx(*handle, 0, Some(&mut pos), FILE_END) }?
+            }
+        }
+        pos
+    };
+
+    let mut buf = vec![0u8; config.read_chunk_size.get::<byte>()];
+    loop {
+        // Attempt read
+        let mut bytes_read: u32 = 0;
+        let read_res = unsafe {
+            ReadFile(
+                *handle,
+                Some(buf.as_mut_slice()),
+                Some(&mut bytes_read),
+                None,
+            )
+        };
+        read_res.wrap_err_with(|| format!("ReadFile error watching {}", path.display()))?;
+        if bytes_read > 0 {
+            let chunk = buf[..bytes_read as usize].to_vec();
+            if !send(chunk) {
+                break;
+            }
+            continue; // attempt immediate next read (burst)
+        } else if is_closed() {
+            break;
+        } else {
+            thread::sleep(Duration::from_millis(150));
+        }
+    }
+    // channel closes when the sender is dropped
+    Ok(())
+}
+
+#[cfg(all(test, feature = "tokio"))]
+mod test {
+    use super::WatchConfig;
+    use super::WatchInitBehaviour;
+    use super::spawn_async_watch;
+    use std::time::Duration;
+    use std::time::Instant;
+
+    #[test]
+    fn async_watch_ends_when_receiver_dropped() -> eyre::Result<()> {
+        let path =
+            std::env::temp_dir().join(format!("teamy-watch-test-{}.log", std::process::id()));
+        std::fs::write(&path, b"idle")?;
+
+        let runtime = tokio::runtime::Builder::new_current_thread().build()?;
+        let (rx, task) = {
+            let _enter = runtime.enter();
+            spawn_async_watch(WatchConfig {
+                init_behaviour: WatchInitBehaviour::ReadFromEnd,
+                ..WatchConfig::new_from_start(&path)
+            })?
+        };
+        drop(rx);
+
+        // Reading from the end of a file that never grows, so only the idle check can end the loop
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !task.is_finished() && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        let finished = task.is_finished();
+        runtime.shutdown_background();
+        std::fs::remove_file(&path)?;
+        assert!(
+            finished,
+            "watcher kept running after the receiver was dropped"
+        );
+        Ok(())
+    }
+}
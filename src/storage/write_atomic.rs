@@ -0,0 +1,107 @@
+use crate::shell::path_extensions::PathExtensions;
+use crate::string::EasyPCWSTR;
+use eyre::Context;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+use windows::Win32::Storage::FileSystem::MOVEFILE_REPLACE_EXISTING;
+use windows::Win32::Storage::FileSystem::MOVEFILE_WRITE_THROUGH;
+use windows::Win32::Storage::FileSystem::MoveFileExW;
+
+/// Replaces the contents of `path` with `bytes` so that readers see either the old or the new file, never a partial write.
+///
+/// The bytes are written and flushed to a temporary file in the same directory, which is then moved over `path`
+/// using `MoveFileExW` with `MOVEFILE_REPLACE_EXISTING | MOVEFILE_WRITE_THROUGH`.
+/// Long, UNC and `\\?\` verbatim paths are all accepted.
+/// <https://learn.microsoft.com/en-us/windows/win32/api/winbase/nf-winbase-movefileexw>
+pub fn write_atomic(path: &Path, bytes: &[u8]) -> eyre::Result<()> {
+    write_atomic_with(path, |file| {
+        file.write_all(bytes)?;
+        Ok(())
+    })
+}
+
+/// Like [`write_atomic`], but lets `write` stream the contents into the temporary file
+/// instead of holding them in memory first.
+pub fn write_atomic_with(
+    path: &Path,
+    write: impl FnOnce(&mut File) -> eyre::Result<()>,
+) -> eyre::Result<()> {
+    let temp_path = temp_path_for(path)?;
+
+    let result = write_and_replace(&temp_path, path, write);
+    if result.is_err() {
+        _ = std::fs::remove_file(&temp_path);
+    }
+    result
+}
+
+fn write_and_replace(
+    temp_path: &Path,
+    path: &Path,
+    write: impl FnOnce(&mut File) -> eyre::Result<()>,
+) -> eyre::Result<()> {
+    let mut file = File::create_new(temp_path)
+        .wrap_err_with(|| format!("Failed to create temp file {}", temp_path.display()))?;
+    write(&mut file)
+        .wrap_err_with(|| format!("Failed to write temp file {}", temp_path.display()))?;
+    file.sync_all()
+        .wrap_err_with(|| format!("Failed to flush temp file {}", temp_path.display()))?;
+    drop(file);
+
+    // Verbatim paths lift MAX_PATH for MoveFileExW
+    let from = temp_path.long_path().easy_pcwstr()?;
+    let to = path.long_path().easy_pcwstr()?;
+    unsafe {
+        MoveFileExW(
+            &from,
+            &to,
+            MOVEFILE_REPLACE_EXISTING | MOVEFILE_WRITE_THROUGH,
+        )
+    }
+    .wrap_err_with(|| {
+        format!(
+            "Failed to move {} over {}",
+            temp_path.display(),
+            path.display()
+        )
+    })?;
+    Ok(())
+}
+
+/// A unique sibling of `path`, so the final move never crosses volumes.
+fn temp_path_for(path: &Path) -> eyre::Result<PathBuf> {
+    let Some(file_name) = path.file_name() else {
+        eyre::bail!("Path has no file name: {}", path.display());
+    };
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .wrap_err("System clock is before the Unix epoch")?
+        .as_nanos();
+    let mut temp_name = file_name.to_os_string();
+    temp_name.push(format!(".{}.{nanos}.tmp", std::process::id()));
+    Ok(path.with_file_name(temp_name))
+}
+
+#[cfg(test)]
+mod test {
+    use super::write_atomic;
+
+    #[test]
+    fn replaces_existing_file() -> eyre::Result<()> {
+        let dir = std::env::temp_dir().join(format!("teamy-write-atomic-{}", std::process::id()));
+        std::fs::create_dir_all(&dir)?;
+        let path = dir.join("config.json");
+
+        write_atomic(&path, b"first")?;
+        write_atomic(&path, b"second")?;
+        assert_eq!(std::fs::read(&path)?, b"second");
+        assert_eq!(std::fs::read_dir(&dir)?.count(), 1, "temp file left behind");
+
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+}
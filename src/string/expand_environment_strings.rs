@@ -0,0 +1,48 @@
+use crate::error::last_error_context;
+use crate::string::EasyPCWSTR;
+use eyre::bail;
+use windows::Win32::System::Environment::ExpandEnvironmentStringsW;
+
+/// Replaces `%VAR%` references in `input` with the current process's environment values.
+///
+/// Unknown variables are left as-is, matching how the shell treats them.
+/// <https://learn.microsoft.com/en-us/windows/win32/api/processenv/nf-processenv-expandenvironmentstringsw>
+pub fn expand_environment_strings(input: &str) -> eyre::Result<String> {
+    if !input.contains('%') {
+        return Ok(input.to_string());
+    }
+    let source = input.easy_pcwstr()?;
+    // The required length includes the terminating nul
+    let len = unsafe { ExpandEnvironmentStringsW(source.as_ref(), None) };
+    if len == 0 {
+        bail!(
+            "Failed to expand environment strings in {input:?}: {}",
+            last_error_context()
+        );
+    }
+    let mut buffer = vec![0u16; len as usize];
+    let written = unsafe { ExpandEnvironmentStringsW(source.as_ref(), Some(&mut buffer)) };
+    if written == 0 || written > len {
+        bail!("Failed to expand environment strings in {input:?}");
+    }
+    Ok(String::from_utf16_lossy(&buffer[..written as usize - 1]))
+}
+
+#[cfg(test)]
+mod test {
+    use super::expand_environment_strings;
+
+    #[test]
+    fn expands_system_root() -> eyre::Result<()> {
+        let system_root = std::env::var("SystemRoot")?;
+        assert_eq!(
+            expand_environment_strings(r"%SystemRoot%\system32\mmres.dll,-3011")?,
+            format!(r"{system_root}\system32\mmres.dll,-3011")
+        );
+        assert_eq!(
+            expand_environment_strings("%teamy-not-a-real-variable%")?,
+            "%teamy-not-a-real-variable%"
+        );
+        Ok(())
+    }
+}
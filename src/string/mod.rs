@@ -1,7 +1,9 @@
 mod easy_pcwstr;
+mod expand_environment_strings;
 mod pcwstr_guard;
 mod utf8;
 
 pub use easy_pcwstr::*;
+pub use expand_environment_strings::*;
 pub use pcwstr_guard::*;
 pub use utf8::*;
@@ -1,9 +1,11 @@
 use core::ffi::c_void;
 use eyre::Context;
 use std::sync::Mutex;
+use tracing::debug;
 use windows::Win32::Foundation::HWND;
 use windows::Win32::UI::Shell::*;
 use windows::Win32::UI::WindowsAndMessaging::*;
+use windows::core::GUID;
 use windows::core::PCWSTR;
 use windows::core::Param;
 use windows::core::ParamValue;
@@ -18,6 +20,7 @@ struct MinimalTrayState {
     hwnd_bits: isize,
     hicon_bits: isize,
     tip: [u16; 128],
+    guid: Option<GUID>,
 }
 
 static TRAY_STATE: Mutex<Option<MinimalTrayState>> = Mutex::new(None);
@@ -28,6 +31,21 @@ pub fn add_tray_icon(
     hwnd: HWND,
     icon: HICON,
     tooltip: impl Param<PCWSTR>,
+) -> eyre::Result<NOTIFYICONDATAW> {
+    add_tray_icon_with_guid(hwnd, icon, tooltip, None)
+}
+
+/// Like [`add_tray_icon`], but identifies the icon by `guid` when given.
+///
+/// Explorer remembers the position and visibility of GUID icons across restarts, so the icon doesn't fall
+/// back into the overflow area every launch. The GUID must be unique to the app and stable between
+/// versions, and Windows ties it to the path of the executable that first registered it.
+/// <https://learn.microsoft.com/en-us/windows/win32/api/shellapi/ns-shellapi-notifyicondataw>
+pub fn add_tray_icon_with_guid(
+    hwnd: HWND,
+    icon: HICON,
+    tooltip: impl Param<PCWSTR>,
+    guid: Option<GUID>,
 ) -> eyre::Result<NOTIFYICONDATAW> {
     // Create tray icon
     let mut notify_icon_data = NOTIFYICONDATAW {
@@ -40,6 +58,10 @@ pub fn add_tray_icon(
         szTip: [0; 128],
         ..Default::default()
     };
+    if let Some(guid) = guid {
+        notify_icon_data.uFlags |= NIF_GUID;
+        notify_icon_data.guidItem = guid;
+    }
 
     // Set tooltip
     let tooltip: ParamValue<PCWSTR> = unsafe { tooltip.param() };
@@ -48,8 +70,7 @@ pub fn add_tray_icon(
     notify_icon_data.szTip[..tooltip.len()].copy_from_slice(tooltip);
 
     // Add the icon to the system tray
-    unsafe { Shell_NotifyIconW(NIM_ADD, &notify_icon_data).ok() }
-        .wrap_err("Failed to add tray icon")?;
+    add_notify_icon(&notify_icon_data).wrap_err("Failed to add tray icon")?;
 
     // Save state for potential re-add after TaskbarCreated
     {
@@ -58,6 +79,7 @@ pub fn add_tray_icon(
             hwnd_bits: hwnd.0 as isize,
             hicon_bits: icon.0 as isize,
             tip: notify_icon_data.szTip,
+            guid,
         });
     }
 
@@ -65,14 +87,15 @@ pub fn add_tray_icon(
 }
 
 /// Re-add the tray icon using the last known NOTIFYICONDATAW.
-/// Call this when the system broadcasts the TaskbarCreated message.
+/// Call this when the system broadcasts the TaskbarCreated message,
+/// or use [`crate::tray::add_tray_icon_persistent`] to have it done automatically.
 pub fn re_add_tray_icon() -> eyre::Result<()> {
     let saved = {
         let guard = TRAY_STATE.lock().unwrap();
         *guard
     };
     if let Some(state) = saved {
-        let nid = NOTIFYICONDATAW {
+        let mut nid = NOTIFYICONDATAW {
             cbSize: std::mem::size_of::<NOTIFYICONDATAW>() as u32,
             hWnd: HWND(state.hwnd_bits as *mut c_void),
             uID: TRAY_ICON_ID,
@@ -82,9 +105,35 @@ pub fn re_add_tray_icon() -> eyre::Result<()> {
             szTip: state.tip,
             ..Default::default()
         };
-        unsafe { Shell_NotifyIconW(NIM_ADD, &nid).ok() }.wrap_err("Failed to re-add tray icon")?;
+        if let Some(guid) = state.guid {
+            nid.uFlags |= NIF_GUID;
+            nid.guidItem = guid;
+        }
+        add_notify_icon(&nid).wrap_err("Failed to re-add tray icon")?;
         Ok(())
     } else {
         Err(eyre::eyre!("No tray state available to re-add icon"))
     }
 }
+
+/// The GUID the current tray icon was registered with, if any.
+pub(crate) fn saved_tray_guid() -> Option<GUID> {
+    TRAY_STATE.lock().unwrap().and_then(|state| state.guid)
+}
+
+fn add_notify_icon(notify_icon_data: &NOTIFYICONDATAW) -> windows::core::Result<()> {
+    let result = unsafe { Shell_NotifyIconW(NIM_ADD, notify_icon_data).ok() };
+    if result.is_ok() || !notify_icon_data.uFlags.contains(NIF_GUID) {
+        return result;
+    }
+    // A GUID icon left behind by a previous instance that didn't clean up blocks the add until it is removed
+    debug!("Adding tray icon by GUID failed, removing any stale registration and retrying");
+    let stale = NOTIFYICONDATAW {
+        cbSize: std::mem::size_of::<NOTIFYICONDATAW>() as u32,
+        uFlags: NIF_GUID,
+        guidItem: notify_icon_data.guidItem,
+        ..Default::default()
+    };
+    _ = unsafe { Shell_NotifyIconW(NIM_DELETE, &stale) };
+    unsafe { Shell_NotifyIconW(NIM_ADD, notify_icon_data).ok() }
+}
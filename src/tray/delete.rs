@@ -1,17 +1,24 @@
 use crate::tray::TRAY_ICON_ID;
+use crate::tray::saved_tray_guid;
 use eyre::Context;
 use windows::Win32::Foundation::HWND;
+use windows::Win32::UI::Shell::NIF_GUID;
 use windows::Win32::UI::Shell::NIM_DELETE;
 use windows::Win32::UI::Shell::NOTIFYICONDATAW;
 use windows::Win32::UI::Shell::Shell_NotifyIconW;
 
 pub fn delete_tray_icon(hwnd: HWND) -> eyre::Result<()> {
-    let notify_icon_data = NOTIFYICONDATAW {
+    let mut notify_icon_data = NOTIFYICONDATAW {
         cbSize: std::mem::size_of::<NOTIFYICONDATAW>() as u32,
         hWnd: hwnd,
         uID: TRAY_ICON_ID,
         ..Default::default()
     };
+    // Icons added by GUID are identified by it rather than by window and ID
+    if let Some(guid) = saved_tray_guid() {
+        notify_icon_data.uFlags = NIF_GUID;
+        notify_icon_data.guidItem = guid;
+    }
 
     // Remove the icon from the system tray
     unsafe { Shell_NotifyIconW(NIM_DELETE, &notify_icon_data).ok() }
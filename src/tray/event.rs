@@ -0,0 +1,87 @@
+use windows::Win32::Foundation::LPARAM;
+use windows::Win32::Foundation::WPARAM;
+use windows::Win32::UI::WindowsAndMessaging::WM_CONTEXTMENU;
+use windows::Win32::UI::WindowsAndMessaging::WM_LBUTTONDBLCLK;
+use windows::Win32::UI::WindowsAndMessaging::WM_LBUTTONDOWN;
+use windows::Win32::UI::WindowsAndMessaging::WM_LBUTTONUP;
+use windows::Win32::UI::WindowsAndMessaging::WM_MBUTTONDBLCLK;
+use windows::Win32::UI::WindowsAndMessaging::WM_MBUTTONDOWN;
+use windows::Win32::UI::WindowsAndMessaging::WM_MBUTTONUP;
+use windows::Win32::UI::WindowsAndMessaging::WM_MOUSEMOVE;
+use windows::Win32::UI::WindowsAndMessaging::WM_RBUTTONDBLCLK;
+use windows::Win32::UI::WindowsAndMessaging::WM_RBUTTONDOWN;
+use windows::Win32::UI::WindowsAndMessaging::WM_RBUTTONUP;
+
+/// A decoded tray icon callback, as delivered to the wndproc via [`crate::tray::WM_USER_TRAY_CALLBACK`].
+/// <https://learn.microsoft.com/en-us/windows/win32/api/shellapi/ns-shellapi-notifyicondataw>
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrayEvent {
+    LeftButtonDown,
+    LeftButtonUp,
+    LeftDoubleClick,
+    RightButtonDown,
+    RightButtonUp,
+    RightDoubleClick,
+    MiddleButtonDown,
+    MiddleButtonUp,
+    MiddleDoubleClick,
+    ContextMenu,
+    MouseMove,
+    Other(u32),
+}
+
+impl TrayEvent {
+    /// Decodes the `wparam`/`lparam` pair of a tray callback message.
+    ///
+    /// The mouse message is read from the low word of `lparam`, which works both for the legacy
+    /// callback layout and for `NOTIFYICON_VERSION_4` (where `wparam` holds the anchor coordinates instead of the icon id).
+    pub fn from_callback(_wparam: WPARAM, lparam: LPARAM) -> TrayEvent {
+        match (lparam.0 as u32) & 0xFFFF {
+            WM_LBUTTONDOWN => TrayEvent::LeftButtonDown,
+            WM_LBUTTONUP => TrayEvent::LeftButtonUp,
+            WM_LBUTTONDBLCLK => TrayEvent::LeftDoubleClick,
+            WM_RBUTTONDOWN => TrayEvent::RightButtonDown,
+            WM_RBUTTONUP => TrayEvent::RightButtonUp,
+            WM_RBUTTONDBLCLK => TrayEvent::RightDoubleClick,
+            WM_MBUTTONDOWN => TrayEvent::MiddleButtonDown,
+            WM_MBUTTONUP => TrayEvent::MiddleButtonUp,
+            WM_MBUTTONDBLCLK => TrayEvent::MiddleDoubleClick,
+            WM_CONTEXTMENU => TrayEvent::ContextMenu,
+            WM_MOUSEMOVE => TrayEvent::MouseMove,
+            other => TrayEvent::Other(other),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::tray::TRAY_ICON_ID;
+    use crate::tray::TrayEvent;
+    use windows::Win32::Foundation::LPARAM;
+    use windows::Win32::Foundation::WPARAM;
+    use windows::Win32::UI::WindowsAndMessaging::WM_LBUTTONDBLCLK;
+    use windows::Win32::UI::WindowsAndMessaging::WM_RBUTTONUP;
+
+    #[test]
+    fn decodes_legacy_callback() {
+        let wparam = WPARAM(TRAY_ICON_ID as usize);
+        assert_eq!(
+            TrayEvent::from_callback(wparam, LPARAM(WM_RBUTTONUP as isize)),
+            TrayEvent::RightButtonUp
+        );
+        assert_eq!(
+            TrayEvent::from_callback(wparam, LPARAM(WM_LBUTTONDBLCLK as isize)),
+            TrayEvent::LeftDoubleClick
+        );
+    }
+
+    #[test]
+    fn decodes_version_4_callback() {
+        // HIWORD holds the icon id in NOTIFYICON_VERSION_4
+        let lparam = LPARAM(((TRAY_ICON_ID << 16) | WM_RBUTTONUP) as isize);
+        assert_eq!(
+            TrayEvent::from_callback(WPARAM(0), lparam),
+            TrayEvent::RightButtonUp
+        );
+    }
+}
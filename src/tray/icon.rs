@@ -0,0 +1,92 @@
+use crate::hicon::OwnedHicon;
+use crate::hicon::rgba_to_hicon;
+use crate::module::get_current_module;
+use crate::window::load_proc;
+use crate::window::system_dpi;
+use eyre::Context;
+use image::RgbaImage;
+use image::imageops::FilterType;
+use tracing::debug;
+use windows::Win32::Foundation::HINSTANCE;
+use windows::Win32::UI::WindowsAndMessaging::GetSystemMetrics;
+use windows::Win32::UI::WindowsAndMessaging::HICON;
+use windows::Win32::UI::WindowsAndMessaging::IMAGE_ICON;
+use windows::Win32::UI::WindowsAndMessaging::LR_DEFAULTCOLOR;
+use windows::Win32::UI::WindowsAndMessaging::LoadImageW;
+use windows::Win32::UI::WindowsAndMessaging::SM_CXSMICON;
+use windows::Win32::UI::WindowsAndMessaging::SYSTEM_METRICS_INDEX;
+use windows::core::PCWSTR;
+use windows::core::Param;
+use windows::core::s;
+use windows::core::w;
+
+type GetSystemMetricsForDpiFn = unsafe extern "system" fn(SYSTEM_METRICS_INDEX, u32) -> i32;
+
+/// The edge length in pixels the notification area draws icons at, e.g. 16 at 100% scaling and 32 at 200%.
+///
+/// Only reflects display scaling once the process is DPI aware, see [`crate::window::set_process_dpi_aware`].
+/// <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-getsystemmetricsfordpi>
+pub fn tray_icon_size() -> i32 {
+    let metrics_for_dpi = unsafe {
+        load_proc::<GetSystemMetricsForDpiFn>(w!("user32.dll"), s!("GetSystemMetricsForDpi"))
+    };
+    match metrics_for_dpi {
+        Some(metrics_for_dpi) => unsafe { metrics_for_dpi(SM_CXSMICON, system_dpi()) },
+        // Before Windows 10 1607 the plain metric is already scaled to the system DPI
+        None => unsafe { GetSystemMetrics(SM_CXSMICON) },
+    }
+}
+
+/// Loads an icon resource from the current module at [`tray_icon_size`].
+///
+/// Unlike `LoadIconW`, which hands back the 32px image for the tray to shrink, this picks the best matching
+/// image in the icon group so the tray icon stays sharp on scaled displays.
+pub fn get_tray_icon_from_current_module(
+    icon_name: impl Param<PCWSTR>,
+) -> eyre::Result<OwnedHicon> {
+    let module = get_current_module()?;
+    let size = tray_icon_size();
+    debug!(size, "Loading tray icon from current module");
+    let handle = unsafe {
+        LoadImageW(
+            Some(HINSTANCE(module.0)),
+            icon_name,
+            IMAGE_ICON,
+            size,
+            size,
+            LR_DEFAULTCOLOR,
+        )
+    }
+    .wrap_err("Failed to load tray icon from current module")?;
+    Ok(unsafe { OwnedHicon::new(HICON(handle.0)) })
+}
+
+/// Scales `image` down to [`tray_icon_size`] and converts it to an icon.
+///
+/// Supply a large source image, such as 256x256, so it can be resampled cleanly at any scaling.
+pub fn tray_icon_from_image(image: &RgbaImage) -> eyre::Result<OwnedHicon> {
+    let size = tray_icon_size().max(1) as u32;
+    if image.dimensions() == (size, size) {
+        return rgba_to_hicon(image);
+    }
+    let resized = image::imageops::resize(image, size, size, FilterType::Lanczos3);
+    rgba_to_hicon(&resized)
+}
+
+#[cfg(test)]
+mod test {
+    use super::tray_icon_from_image;
+    use super::tray_icon_size;
+    use image::Rgba;
+    use image::RgbaImage;
+
+    #[test]
+    fn large_image_is_scaled_to_tray_size() -> eyre::Result<()> {
+        let image = RgbaImage::from_pixel(256, 256, Rgba([255, 0, 0, 255]));
+        let icon = tray_icon_from_image(&image)?;
+        let rendered = unsafe { crate::hicon::hicon_to_rgba(icon.as_raw()) }?;
+        let size = tray_icon_size() as u32;
+        assert_eq!(rendered.dimensions(), (size, size));
+        Ok(())
+    }
+}
@@ -1,7 +1,13 @@
 mod add;
 mod delete;
+mod event;
+mod icon;
+mod persistent;
 mod taskbar_created;
 
 pub use add::*;
 pub use delete::*;
+pub use event::*;
+pub use icon::*;
+pub use persistent::*;
 pub use taskbar_created::*;
@@ -0,0 +1,43 @@
+use crate::tray::add_tray_icon_with_guid;
+use crate::tray::forward_taskbar_created;
+use crate::tray::re_add_tray_icon;
+use crate::tray::subscribe_taskbar_created;
+use std::sync::Once;
+use tracing::debug;
+use windows::Win32::Foundation::HWND;
+use windows::Win32::UI::Shell::NOTIFYICONDATAW;
+use windows::Win32::UI::WindowsAndMessaging::HICON;
+use windows::core::GUID;
+use windows::core::PCWSTR;
+use windows::core::Param;
+
+static SUBSCRIBE_RE_ADD: Once = Once::new();
+
+/// Adds a tray icon like [`crate::tray::add_tray_icon`], and keeps it alive across Explorer restarts.
+///
+/// The window is set up with [`forward_taskbar_created`], and [`re_add_tray_icon`] is registered as a
+/// `TaskbarCreated` subscriber, so the window's own wndproc does not need to handle the message.
+pub fn add_tray_icon_persistent(
+    hwnd: HWND,
+    icon: HICON,
+    tooltip: impl Param<PCWSTR>,
+) -> eyre::Result<NOTIFYICONDATAW> {
+    add_tray_icon_persistent_with_guid(hwnd, icon, tooltip, None)
+}
+
+/// Like [`add_tray_icon_persistent`], but identifies the icon by `guid`, see [`add_tray_icon_with_guid`].
+pub fn add_tray_icon_persistent_with_guid(
+    hwnd: HWND,
+    icon: HICON,
+    tooltip: impl Param<PCWSTR>,
+    guid: Option<GUID>,
+) -> eyre::Result<NOTIFYICONDATAW> {
+    let notify_icon_data = add_tray_icon_with_guid(hwnd, icon, tooltip, guid)?;
+
+    // The saved tray state is process-wide, so one subscriber re-adds whichever icon was added last
+    SUBSCRIBE_RE_ADD.call_once(|| subscribe_taskbar_created(re_add_tray_icon).detach());
+    forward_taskbar_created(hwnd)?;
+    debug!("Tray icon will be re-added when the taskbar is recreated");
+
+    Ok(notify_icon_data)
+}
@@ -1,5 +1,23 @@
+use eyre::Context;
+use std::sync::Arc;
 use std::sync::LazyLock;
+use std::sync::Mutex;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use tracing::debug;
+use tracing::error;
+use tracing::warn;
+use windows::Win32::Foundation::HWND;
+use windows::Win32::Foundation::LPARAM;
+use windows::Win32::Foundation::LRESULT;
+use windows::Win32::Foundation::WPARAM;
+use windows::Win32::UI::Shell::DefSubclassProc;
+use windows::Win32::UI::Shell::RemoveWindowSubclass;
+use windows::Win32::UI::Shell::SetWindowSubclass;
+use windows::Win32::UI::WindowsAndMessaging::ChangeWindowMessageFilterEx;
+use windows::Win32::UI::WindowsAndMessaging::MSGFLT_ALLOW;
 use windows::Win32::UI::WindowsAndMessaging::RegisterWindowMessageW;
+use windows::Win32::UI::WindowsAndMessaging::WM_NCDESTROY;
 use windows::core::w;
 
 /// Returns the atom/message ID for the "TaskbarCreated" broadcast message.
@@ -7,3 +25,140 @@ use windows::core::w;
 /// Apps should re-add their tray icons when they receive this message.
 pub static WM_TASKBAR_CREATED: LazyLock<u32> =
     LazyLock::new(|| unsafe { RegisterWindowMessageW(w!("TaskbarCreated")) });
+
+const TASKBAR_CREATED_SUBCLASS_ID: usize = 0x7472_6179; // "tray"
+
+type TaskbarCreatedCallback = Arc<dyn Fn() -> eyre::Result<()> + Send + Sync>;
+
+static SUBSCRIBERS: Mutex<Vec<(u64, TaskbarCreatedCallback)>> = Mutex::new(Vec::new());
+static NEXT_SUBSCRIBER_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Runs `callback` every time Explorer restarts, for shell state beyond the tray icon
+/// such as hotkeys, clipboard listeners or appbars.
+///
+/// Callbacks run in subscription order on the thread that received `TaskbarCreated`, see
+/// [`forward_taskbar_created`]. Errors are logged and don't stop the remaining callbacks.
+/// The callback is removed when the returned subscription drops.
+pub fn subscribe_taskbar_created(
+    callback: impl Fn() -> eyre::Result<()> + Send + Sync + 'static,
+) -> TaskbarCreatedSubscription {
+    let id = NEXT_SUBSCRIBER_ID.fetch_add(1, Ordering::Relaxed);
+    SUBSCRIBERS.lock().unwrap().push((id, Arc::new(callback)));
+    TaskbarCreatedSubscription { id }
+}
+
+/// Keeps a [`subscribe_taskbar_created`] callback registered until dropped.
+#[must_use = "the callback is unsubscribed when this is dropped"]
+#[derive(Debug)]
+pub struct TaskbarCreatedSubscription {
+    id: u64,
+}
+
+impl TaskbarCreatedSubscription {
+    /// Keeps the callback registered for the rest of the process.
+    pub fn detach(self) {
+        std::mem::forget(self);
+    }
+}
+
+impl Drop for TaskbarCreatedSubscription {
+    fn drop(&mut self) {
+        SUBSCRIBERS.lock().unwrap().retain(|(id, _)| *id != self.id);
+    }
+}
+
+/// Runs every [`subscribe_taskbar_created`] callback, returning how many ran.
+///
+/// Called automatically by windows set up with [`forward_taskbar_created`]; call it yourself when
+/// handling [`WM_TASKBAR_CREATED`] in your own wndproc.
+pub fn notify_taskbar_created() -> usize {
+    // Cloned so callbacks can subscribe or unsubscribe without deadlocking
+    let subscribers: Vec<TaskbarCreatedCallback> = SUBSCRIBERS
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(_, callback)| callback.clone())
+        .collect();
+    for callback in &subscribers {
+        if let Err(e) = callback() {
+            error!("TaskbarCreated subscriber failed: {:?}", e);
+        }
+    }
+    subscribers.len()
+}
+
+/// Subclasses `hwnd` so the `TaskbarCreated` broadcast calls [`notify_taskbar_created`]
+/// before the message reaches the window's own wndproc.
+///
+/// The broadcast goes to every top-level window, so forward it from a single window per process
+/// or subscribers run once per window. Calling this again for the same window has no extra effect.
+/// The message is also allowed through UIPI so it arrives when running elevated.
+pub fn forward_taskbar_created(hwnd: HWND) -> eyre::Result<()> {
+    // Elevated processes don't receive the TaskbarCreated broadcast unless it is explicitly allowed
+    if let Err(e) =
+        unsafe { ChangeWindowMessageFilterEx(hwnd, *WM_TASKBAR_CREATED, MSGFLT_ALLOW, None) }
+    {
+        warn!(
+            "Failed to allow TaskbarCreated through the message filter: {}",
+            e
+        );
+    }
+
+    unsafe {
+        SetWindowSubclass(
+            hwnd,
+            Some(taskbar_created_subclass_proc),
+            TASKBAR_CREATED_SUBCLASS_ID,
+            0,
+        )
+    }
+    .ok()
+    .wrap_err("Failed to subclass window for TaskbarCreated handling")
+}
+
+unsafe extern "system" fn taskbar_created_subclass_proc(
+    hwnd: HWND,
+    message: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+    subclass_id: usize,
+    _ref_data: usize,
+) -> LRESULT {
+    if message == *WM_TASKBAR_CREATED {
+        debug!("Taskbar recreated, notifying subscribers");
+        notify_taskbar_created();
+    } else if message == WM_NCDESTROY {
+        _ = unsafe { RemoveWindowSubclass(hwnd, Some(taskbar_created_subclass_proc), subclass_id) };
+    }
+    unsafe { DefSubclassProc(hwnd, message, wparam, lparam) }
+}
+
+#[cfg(test)]
+mod test {
+    use super::notify_taskbar_created;
+    use super::subscribe_taskbar_created;
+    use std::sync::Arc;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering;
+
+    #[test]
+    fn subscribers_run_until_dropped() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let subscription = subscribe_taskbar_created({
+            let calls = calls.clone();
+            move || {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            }
+        });
+        let failing = subscribe_taskbar_created(|| eyre::bail!("hotkey already registered"));
+
+        notify_taskbar_created();
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        drop(subscription);
+        drop(failing);
+        notify_taskbar_created();
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
@@ -1,50 +1,34 @@
 use crate::console::set_our_hwnd;
-use crate::module::get_current_module;
+use crate::singleton::receive_activation_message;
+use crate::window::WindowBuilder;
 use tracing::debug;
 use windows::Win32::Foundation::HWND;
 use windows::Win32::UI::WindowsAndMessaging::*;
-use windows::core::w;
 
 /// Creates a window for message pumping for tray icon interactions.
 /// <https://learn.microsoft.com/en-us/windows/win32/winmsg/about-messages-and-message-queues>
 pub fn create_window_for_tray(window_proc: WNDPROC) -> eyre::Result<HWND> {
     debug!("Creating hidden window for tray icon");
-    let instance = get_current_module()?;
-    let class_name = w!("TrayIconWindow");
-
-    let window_class = WNDCLASSEXW {
-        cbSize: std::mem::size_of::<WNDCLASSEXW>() as u32,
-        style: CS_HREDRAW | CS_VREDRAW,
-        lpfnWndProc: window_proc,
-        hInstance: instance.into(),
-        lpszClassName: class_name,
-        ..Default::default()
-    };
-
-    debug!(class_name = ?class_name, "Registering window class");
-    let atom = unsafe { RegisterClassExW(&window_class) };
-    std::debug_assert_ne!(atom, 0);
-
-    let window_title = w!("Tray Icon");
-    debug!(title = ?window_title, "Creating window");
-    let hwnd = unsafe {
-        CreateWindowExW(
-            WINDOW_EX_STYLE::default(),
-            class_name,
-            window_title,
-            WS_OVERLAPPEDWINDOW,
-            CW_USEDEFAULT,
-            CW_USEDEFAULT,
-            CW_USEDEFAULT,
-            CW_USEDEFAULT,
-            None,
-            None,
-            Some(instance.into()),
-            None,
-        )
-    }?;
+    let hwnd = WindowBuilder::new("TrayIconWindow")
+        .title("Tray Icon")
+        .style(WS_OVERLAPPEDWINDOW)
+        .window_proc(window_proc)
+        .build()?;
 
     set_our_hwnd(hwnd);
 
     Ok(hwnd)
 }
+
+/// Like [`create_window_for_tray`], but also receives the activation message sent when another launch
+/// of the single instance `name` runs, see [`crate::singleton::single_instance_or_activate`].
+///
+/// Returns the window and the message ID for `window_proc` to match on.
+pub fn create_window_for_single_instance_tray(
+    window_proc: WNDPROC,
+    name: &str,
+) -> eyre::Result<(HWND, u32)> {
+    let hwnd = create_window_for_tray(window_proc)?;
+    let activation_message = receive_activation_message(hwnd, name)?;
+    Ok((hwnd, activation_message))
+}
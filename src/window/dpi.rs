@@ -0,0 +1,254 @@
+use crate::hicon::ReleaseDCGuard;
+use eyre::Context;
+use tracing::debug;
+use windows::Win32::Foundation::E_ACCESSDENIED;
+use windows::Win32::Foundation::HANDLE;
+use windows::Win32::Graphics::Gdi::GetDC;
+use windows::Win32::Graphics::Gdi::GetDeviceCaps;
+use windows::Win32::Graphics::Gdi::LOGPIXELSX;
+use windows::Win32::System::LibraryLoader::GetProcAddress;
+use windows::Win32::System::LibraryLoader::LoadLibraryW;
+use windows::Win32::UI::HiDpi::DPI_AWARENESS_CONTEXT;
+use windows::Win32::UI::HiDpi::DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE;
+use windows::Win32::UI::HiDpi::DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2;
+use windows::Win32::UI::HiDpi::DPI_AWARENESS_CONTEXT_SYSTEM_AWARE;
+use windows::Win32::UI::HiDpi::DPI_AWARENESS_CONTEXT_UNAWARE;
+use windows::Win32::UI::HiDpi::PROCESS_DPI_AWARENESS;
+use windows::Win32::UI::HiDpi::PROCESS_DPI_UNAWARE;
+use windows::Win32::UI::HiDpi::PROCESS_PER_MONITOR_DPI_AWARE;
+use windows::Win32::UI::HiDpi::PROCESS_SYSTEM_DPI_AWARE;
+use windows::Win32::UI::WindowsAndMessaging::IsProcessDPIAware;
+use windows::Win32::UI::WindowsAndMessaging::SetProcessDPIAware;
+use windows::core::BOOL;
+use windows::core::HRESULT;
+use windows::core::PCSTR;
+use windows::core::PCWSTR;
+use windows::core::s;
+use windows::core::w;
+
+// Everything newer than Vista is looked up at runtime, so the binary still loads where it is missing
+type SetProcessDpiAwarenessContextFn = unsafe extern "system" fn(DPI_AWARENESS_CONTEXT) -> BOOL;
+type GetThreadDpiAwarenessContextFn = unsafe extern "system" fn() -> DPI_AWARENESS_CONTEXT;
+type AreDpiAwarenessContextsEqualFn =
+    unsafe extern "system" fn(DPI_AWARENESS_CONTEXT, DPI_AWARENESS_CONTEXT) -> BOOL;
+type SetProcessDpiAwarenessFn = unsafe extern "system" fn(PROCESS_DPI_AWARENESS) -> HRESULT;
+type GetProcessDpiAwarenessFn =
+    unsafe extern "system" fn(HANDLE, *mut PROCESS_DPI_AWARENESS) -> HRESULT;
+type GetDpiForSystemFn = unsafe extern "system" fn() -> u32;
+
+/// How the process wants window coordinates to relate to physical pixels.
+///
+/// Anything short of per-monitor awareness gets bitmap-stretched and handed virtualized coordinates
+/// on scaled displays, which throws off window rects and monitor math.
+/// <https://learn.microsoft.com/en-us/windows/win32/hidpi/dpi-awareness-context>
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DpiAwareness {
+    Unaware,
+    System,
+    PerMonitor,
+    #[default]
+    PerMonitorV2,
+}
+
+impl DpiAwareness {
+    /// The next weaker mode to try when this one isn't supported by the running Windows version.
+    pub fn fallback(self) -> Option<DpiAwareness> {
+        match self {
+            DpiAwareness::PerMonitorV2 => Some(DpiAwareness::PerMonitor),
+            DpiAwareness::PerMonitor => Some(DpiAwareness::System),
+            DpiAwareness::System | DpiAwareness::Unaware => None,
+        }
+    }
+
+    fn as_context(self) -> DPI_AWARENESS_CONTEXT {
+        match self {
+            DpiAwareness::Unaware => DPI_AWARENESS_CONTEXT_UNAWARE,
+            DpiAwareness::System => DPI_AWARENESS_CONTEXT_SYSTEM_AWARE,
+            DpiAwareness::PerMonitor => DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE,
+            DpiAwareness::PerMonitorV2 => DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2,
+        }
+    }
+
+    fn as_process_awareness(self) -> PROCESS_DPI_AWARENESS {
+        match self {
+            DpiAwareness::Unaware => PROCESS_DPI_UNAWARE,
+            DpiAwareness::System => PROCESS_SYSTEM_DPI_AWARE,
+            DpiAwareness::PerMonitor | DpiAwareness::PerMonitorV2 => PROCESS_PER_MONITOR_DPI_AWARE,
+        }
+    }
+
+    fn from_process_awareness(awareness: PROCESS_DPI_AWARENESS) -> DpiAwareness {
+        match awareness {
+            PROCESS_SYSTEM_DPI_AWARE => DpiAwareness::System,
+            PROCESS_PER_MONITOR_DPI_AWARE => DpiAwareness::PerMonitor,
+            _ => DpiAwareness::Unaware,
+        }
+    }
+}
+
+/// Looks up `name` in `module`, or `None` on Windows versions that predate it.
+///
+/// # Safety
+///
+/// `F` must be the `extern "system"` function pointer type matching the export's signature.
+pub(crate) unsafe fn load_proc<F: Copy>(module: PCWSTR, name: PCSTR) -> Option<F> {
+    assert_eq!(size_of::<F>(), size_of::<usize>());
+    // The handle is never freed, these system DLLs stay loaded for the life of the process anyway
+    let module = unsafe { LoadLibraryW(module) }.ok()?;
+    let proc = unsafe { GetProcAddress(module, name) }?;
+    Some(unsafe { std::mem::transmute_copy(&proc) })
+}
+
+/// The DPI of the primary display as seen by this process, 96 at 100% scaling.
+///
+/// Uses `GetDpiForSystem` on Windows 10 1607+ and the screen's `LOGPIXELSX` before that.
+/// Like both, it reports 96 until the process is DPI aware, see [`set_process_dpi_aware`].
+/// <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-getdpiforsystem>
+pub fn system_dpi() -> u32 {
+    let get_dpi_for_system =
+        unsafe { load_proc::<GetDpiForSystemFn>(w!("user32.dll"), s!("GetDpiForSystem")) };
+    if let Some(get_dpi_for_system) = get_dpi_for_system {
+        return unsafe { get_dpi_for_system() };
+    }
+    let screen_device_context = ReleaseDCGuard(unsafe { GetDC(None) });
+    let dpi = unsafe { GetDeviceCaps(Some(*screen_device_context), LOGPIXELSX) };
+    u32::try_from(dpi).ok().filter(|&dpi| dpi > 0).unwrap_or(96)
+}
+
+/// The DPI awareness the process is actually running with, e.g. as declared by its manifest.
+///
+/// On Windows 10 1607+ this is the calling thread's awareness, which matches the process
+/// unless the thread overrode it.
+pub fn current_dpi_awareness() -> DpiAwareness {
+    let thread_context = unsafe {
+        load_proc::<GetThreadDpiAwarenessContextFn>(
+            w!("user32.dll"),
+            s!("GetThreadDpiAwarenessContext"),
+        )
+    };
+    let contexts_equal = unsafe {
+        load_proc::<AreDpiAwarenessContextsEqualFn>(
+            w!("user32.dll"),
+            s!("AreDpiAwarenessContextsEqual"),
+        )
+    };
+    if let (Some(thread_context), Some(contexts_equal)) = (thread_context, contexts_equal) {
+        let context = unsafe { thread_context() };
+        return [
+            DpiAwareness::PerMonitorV2,
+            DpiAwareness::PerMonitor,
+            DpiAwareness::System,
+        ]
+        .into_iter()
+        .find(|awareness| unsafe { contexts_equal(context, awareness.as_context()) }.as_bool())
+        // Includes the GDI-scaled flavour of unaware
+        .unwrap_or(DpiAwareness::Unaware);
+    }
+
+    if let Some(get_process_awareness) = unsafe {
+        load_proc::<GetProcessDpiAwarenessFn>(w!("shcore.dll"), s!("GetProcessDpiAwareness"))
+    } {
+        let mut awareness = PROCESS_DPI_UNAWARE;
+        if unsafe { get_process_awareness(HANDLE::default(), &mut awareness) }.is_ok() {
+            return DpiAwareness::from_process_awareness(awareness);
+        }
+    }
+
+    if unsafe { IsProcessDPIAware() }.as_bool() {
+        DpiAwareness::System
+    } else {
+        DpiAwareness::Unaware
+    }
+}
+
+/// Sets the DPI awareness of the process, falling back to weaker modes on older Windows versions.
+///
+/// Must run before any window is created. If the awareness was already set, by an earlier call or
+/// the application manifest, that setting is kept and this returns `Ok` with the awareness in effect.
+/// Otherwise returns the mode that was applied.
+/// <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-setprocessdpiawarenesscontext>
+pub fn set_process_dpi_aware(mode: DpiAwareness) -> eyre::Result<DpiAwareness> {
+    let mut last_error = None;
+
+    // Windows 10 1703+ knows every context, earlier Windows 10 builds reject per-monitor v2
+    let set_context = unsafe {
+        load_proc::<SetProcessDpiAwarenessContextFn>(
+            w!("user32.dll"),
+            s!("SetProcessDpiAwarenessContext"),
+        )
+    };
+    if let Some(set_context) = set_context {
+        let mut candidate = Some(mode);
+        while let Some(current) = candidate {
+            if unsafe { set_context(current.as_context()) }.as_bool() {
+                debug!(?current, "Set process DPI awareness context");
+                return Ok(current);
+            }
+            let error = windows::core::Error::from_thread();
+            if error.code() == E_ACCESSDENIED {
+                return Ok(keep_current_dpi_awareness());
+            }
+            last_error = Some(error);
+            candidate = current.fallback();
+        }
+    }
+
+    // Windows 8.1 only has the coarser shcore API
+    let fallback = if mode == DpiAwareness::PerMonitorV2 {
+        DpiAwareness::PerMonitor
+    } else {
+        mode
+    };
+    let set_awareness = unsafe {
+        load_proc::<SetProcessDpiAwarenessFn>(w!("shcore.dll"), s!("SetProcessDpiAwareness"))
+    };
+    if let Some(set_awareness) = set_awareness {
+        match unsafe { set_awareness(fallback.as_process_awareness()) }.ok() {
+            Ok(()) => {
+                debug!(?fallback, "Set process DPI awareness");
+                return Ok(fallback);
+            }
+            Err(e) if e.code() == E_ACCESSDENIED => return Ok(keep_current_dpi_awareness()),
+            Err(e) => last_error = Some(e),
+        }
+    }
+
+    // Vista and later can at least opt into system awareness
+    if mode != DpiAwareness::Unaware && unsafe { SetProcessDPIAware() }.as_bool() {
+        debug!("Set process DPI awareness to system aware");
+        return Ok(DpiAwareness::System);
+    }
+
+    Err(last_error.unwrap_or_else(windows::core::Error::from_thread))
+        .wrap_err_with(|| format!("Failed to set process DPI awareness to {mode:?}"))
+}
+
+fn keep_current_dpi_awareness() -> DpiAwareness {
+    let current = current_dpi_awareness();
+    debug!(
+        ?current,
+        "Process DPI awareness was already set, keeping it"
+    );
+    current
+}
+
+#[cfg(test)]
+mod test {
+    use super::DpiAwareness;
+
+    #[test]
+    fn fallback_weakens_until_system() {
+        let mut chain = vec![DpiAwareness::default()];
+        while let Some(next) = chain.last().unwrap().fallback() {
+            chain.push(next);
+        }
+        assert_eq!(
+            chain,
+            vec![
+                DpiAwareness::PerMonitorV2,
+                DpiAwareness::PerMonitor,
+                DpiAwareness::System
+            ]
+        );
+    }
+}
@@ -1,3 +1,5 @@
+use crate::window::WindowRect;
+use eyre::Context;
 use windows::Win32::Foundation::HWND;
 use windows::Win32::Foundation::LPARAM;
 use windows::Win32::Foundation::RECT;
@@ -29,8 +31,7 @@ pub struct WindowInfo {
     pub title: String,
     pub class_name: String,
     pub exe_path: String,
-    #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_rect"))]
-    pub rect: RECT,
+    pub rect: WindowRect,
     pub process_id: u32,
     pub thread_id: u32,
     pub is_visible: bool,
@@ -45,24 +46,32 @@ where
     serializer.serialize_u64(hwnd.0 as u64)
 }
 
-#[cfg(feature = "serde")]
-fn serialize_rect<S>(rect: &RECT, serializer: S) -> Result<S::Ok, S::Error>
-where
-    S: serde::Serializer,
-{
-    use serde::ser::SerializeStruct;
-    let mut state = serializer.serialize_struct("RECT", 4)?;
-    state.serialize_field("left", &rect.left)?;
-    state.serialize_field("top", &rect.top)?;
-    state.serialize_field("right", &rect.right)?;
-    state.serialize_field("bottom", &rect.bottom)?;
-    state.end()
+/// Whether a visible `hwnd` gets a taskbar button, the same rule Explorer uses:
+/// `WS_EX_APPWINDOW` forces one, otherwise tool windows and owned windows don't get one.
+pub fn is_on_taskbar(hwnd: HWND) -> bool {
+    unsafe { IsWindowVisible(hwnd) }.as_bool() && is_taskbar_style(hwnd)
+}
+
+fn is_taskbar_style(hwnd: HWND) -> bool {
+    let ex_style = unsafe { GetWindowLongW(hwnd, GWL_EXSTYLE) } as u32;
+    let is_app_window = (ex_style & WS_EX_APPWINDOW.0) != 0;
+    let is_tool_window = (ex_style & WS_EX_TOOLWINDOW.0) != 0;
+
+    if is_app_window {
+        true
+    } else if is_tool_window {
+        false
+    } else {
+        let owner = unsafe { GetWindow(hwnd, GW_OWNER) }.unwrap_or_default();
+        owner.0.is_null()
+    }
 }
 
 pub fn enumerate_windows() -> eyre::Result<Vec<WindowInfo>> {
     let mut windows = Vec::new();
     unsafe {
-        EnumWindows(Some(enum_window_proc), LPARAM(&mut windows as *mut _ as _))?;
+        EnumWindows(Some(enum_window_proc), LPARAM(&mut windows as *mut _ as _))
+            .wrap_err("Failed to enumerate top-level windows")?;
     }
     Ok(windows)
 }
@@ -111,28 +120,14 @@ unsafe extern "system" fn enum_window_proc(hwnd: HWND, lparam: LPARAM) -> BOOL {
     let is_visible = unsafe { IsWindowVisible(hwnd) }.as_bool();
 
     // Check if on Taskbar
-    let ex_style = unsafe { GetWindowLongW(hwnd, GWL_EXSTYLE) } as u32;
-    let owner = unsafe { GetWindow(hwnd, GW_OWNER) }.unwrap_or_default();
-
-    let is_app_window = (ex_style & WS_EX_APPWINDOW.0) != 0;
-    let is_tool_window = (ex_style & WS_EX_TOOLWINDOW.0) != 0;
-
-    let is_on_taskbar = if !is_visible {
-        false
-    } else if is_app_window {
-        true
-    } else if is_tool_window {
-        false
-    } else {
-        owner.0.is_null()
-    };
+    let is_on_taskbar = is_visible && is_taskbar_style(hwnd);
 
     windows.push(WindowInfo {
         hwnd,
         title,
         class_name,
         exe_path,
-        rect,
+        rect: rect.into(),
         process_id,
         thread_id,
         is_visible,
@@ -1,14 +1,103 @@
+use eyre::bail;
+use tracing::debug;
 use windows::Win32::Foundation::HWND;
+use windows::Win32::System::Threading::AttachThreadInput;
+use windows::Win32::System::Threading::GetCurrentThreadId;
+use windows::Win32::UI::WindowsAndMessaging::BringWindowToTop;
+use windows::Win32::UI::WindowsAndMessaging::GetForegroundWindow;
+use windows::Win32::UI::WindowsAndMessaging::GetWindowThreadProcessId;
 use windows::Win32::UI::WindowsAndMessaging::IsIconic;
+use windows::Win32::UI::WindowsAndMessaging::SPI_GETFOREGROUNDLOCKTIMEOUT;
+use windows::Win32::UI::WindowsAndMessaging::SPI_SETFOREGROUNDLOCKTIMEOUT;
+use windows::Win32::UI::WindowsAndMessaging::SPIF_SENDCHANGE;
 use windows::Win32::UI::WindowsAndMessaging::SW_RESTORE;
+use windows::Win32::UI::WindowsAndMessaging::SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS;
 use windows::Win32::UI::WindowsAndMessaging::SetForegroundWindow;
 use windows::Win32::UI::WindowsAndMessaging::ShowWindow;
+use windows::Win32::UI::WindowsAndMessaging::SystemParametersInfoW;
 
 pub fn focus_window(hwnd: isize) -> eyre::Result<()> {
+    focus_window_with(hwnd, false)
+}
+
+/// Brings `hwnd` to the foreground, working around focus-stealing prevention.
+///
+/// `SetForegroundWindow` only flashes the taskbar button when the caller isn't the foreground process,
+/// so the input queue is temporarily attached to the foreground window's thread, which lets the call through.
+/// When `override_lock_timeout` is set and that still fails, the system foreground lock timeout is zeroed
+/// for one more attempt and then restored.
+/// Errors if the window still isn't in the foreground afterwards.
+/// <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-setforegroundwindow#remarks>
+pub fn focus_window_with(hwnd: isize, override_lock_timeout: bool) -> eyre::Result<()> {
     let hwnd = HWND(hwnd as _);
     if unsafe { IsIconic(hwnd).as_bool() } {
         let _ = unsafe { ShowWindow(hwnd, SW_RESTORE) };
     }
-    let _ = unsafe { SetForegroundWindow(hwnd) };
-    Ok(())
+    if try_set_foreground(hwnd) {
+        return Ok(());
+    }
+
+    debug!(
+        ?hwnd,
+        "SetForegroundWindow was refused, attaching to the foreground thread"
+    );
+    let foreground_thread = unsafe { GetWindowThreadProcessId(GetForegroundWindow(), None) };
+    let current_thread = unsafe { GetCurrentThreadId() };
+    let attached = foreground_thread != 0
+        && foreground_thread != current_thread
+        && unsafe { AttachThreadInput(current_thread, foreground_thread, true) }.as_bool();
+    let _ = unsafe { BringWindowToTop(hwnd) };
+    let focused = try_set_foreground(hwnd);
+    if attached {
+        let _ = unsafe { AttachThreadInput(current_thread, foreground_thread, false) };
+    }
+    if focused {
+        return Ok(());
+    }
+
+    if override_lock_timeout && focus_with_lock_timeout_override(hwnd) {
+        return Ok(());
+    }
+    bail!(
+        "Window {:?} could not be brought to the foreground, focus-stealing prevention refused the request",
+        hwnd
+    );
+}
+
+fn try_set_foreground(hwnd: HWND) -> bool {
+    unsafe { SetForegroundWindow(hwnd) }.as_bool() && unsafe { GetForegroundWindow() } == hwnd
+}
+
+fn focus_with_lock_timeout_override(hwnd: HWND) -> bool {
+    let mut previous_timeout: u32 = 0;
+    if let Err(e) = unsafe {
+        SystemParametersInfoW(
+            SPI_GETFOREGROUNDLOCKTIMEOUT,
+            0,
+            Some(&mut previous_timeout as *mut u32 as *mut _),
+            SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS(0),
+        )
+    } {
+        debug!("Failed to read the foreground lock timeout: {}", e);
+        return false;
+    }
+    // The new value is passed in the pointer argument itself
+    if let Err(e) =
+        unsafe { SystemParametersInfoW(SPI_SETFOREGROUNDLOCKTIMEOUT, 0, None, SPIF_SENDCHANGE) }
+    {
+        debug!("Failed to clear the foreground lock timeout: {}", e);
+        return false;
+    }
+    let focused = try_set_foreground(hwnd);
+    if let Err(e) = unsafe {
+        SystemParametersInfoW(
+            SPI_SETFOREGROUNDLOCKTIMEOUT,
+            0,
+            Some(previous_timeout as usize as *mut _),
+            SPIF_SENDCHANGE,
+        )
+    } {
+        debug!("Failed to restore the foreground lock timeout: {}", e);
+    }
+    focused
 }
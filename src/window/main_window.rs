@@ -0,0 +1,71 @@
+use crate::window::WindowInfo;
+use crate::window::enumerate_windows;
+use windows::Win32::Foundation::HWND;
+
+/// Picks the window a user would consider the "main" window of process `pid`, or `None` if it has no visible windows.
+///
+/// Candidates are the process's visible top-level windows. Windows with a taskbar button beat those without,
+/// then windows with a non-zero size and a title, and remaining ties go to the most recently active window,
+/// which is the first in z-order.
+pub fn main_window_for_pid(pid: u32) -> eyre::Result<Option<HWND>> {
+    let windows = enumerate_windows()?;
+    Ok(pick_main_window(
+        windows.iter().filter(|window| window.process_id == pid),
+    ))
+}
+
+fn pick_main_window<'a>(windows: impl Iterator<Item = &'a WindowInfo>) -> Option<HWND> {
+    // Windows are enumerated top to bottom in z-order, so the index orders by recent activity
+    windows
+        .enumerate()
+        .filter(|(_, window)| window.is_visible)
+        .min_by_key(|(z_order, window)| {
+            (
+                !window.is_on_taskbar,
+                window.rect.is_empty(),
+                window.title.is_empty(),
+                *z_order,
+            )
+        })
+        .map(|(_, window)| window.hwnd)
+}
+
+#[cfg(test)]
+mod test {
+    use super::pick_main_window;
+    use crate::window::WindowInfo;
+    use crate::window::WindowRect;
+    use windows::Win32::Foundation::HWND;
+
+    fn window(hwnd: usize, title: &str, is_visible: bool, is_on_taskbar: bool) -> WindowInfo {
+        WindowInfo {
+            hwnd: HWND(hwnd as _),
+            title: title.to_string(),
+            class_name: String::new(),
+            exe_path: String::new(),
+            rect: WindowRect {
+                left: 0,
+                top: 0,
+                right: 800,
+                bottom: 600,
+            },
+            process_id: 1,
+            thread_id: 1,
+            is_visible,
+            is_on_taskbar,
+        }
+    }
+
+    #[test]
+    fn prefers_visible_taskbar_windows_then_z_order() {
+        let windows = [
+            window(1, "hidden", false, false),
+            window(2, "tooltip", true, false),
+            window(3, "Main", true, true),
+            window(4, "Second", true, true),
+        ];
+        assert_eq!(pick_main_window(windows.iter()), Some(HWND(3 as _)));
+        assert_eq!(pick_main_window(windows[..2].iter()), Some(HWND(2 as _)));
+        assert_eq!(pick_main_window(windows[..1].iter()), None);
+    }
+}
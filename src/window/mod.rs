@@ -1,11 +1,29 @@
 mod create_window_for_tray;
+mod dpi;
 mod enumerate;
 mod focus;
+mod main_window;
+mod monitor;
 mod open;
+mod screen_capture;
+mod snap;
+mod wait;
+mod window_builder;
+mod window_rect;
+mod window_summary;
 mod window_user_data;
 
 pub use create_window_for_tray::*;
+pub use dpi::*;
 pub use enumerate::*;
 pub use focus::*;
+pub use main_window::*;
+pub use monitor::*;
 pub use open::*;
+pub use screen_capture::*;
+pub use snap::*;
+pub use wait::*;
+pub use window_builder::*;
+pub use window_rect::*;
+pub use window_summary::*;
 pub use window_user_data::*;
@@ -0,0 +1,104 @@
+use crate::error::last_error_context;
+use crate::window::WindowRect;
+use eyre::bail;
+use windows::Win32::Foundation::HWND;
+use windows::Win32::Foundation::LPARAM;
+use windows::Win32::Foundation::RECT;
+use windows::Win32::Graphics::Gdi::EnumDisplayMonitors;
+use windows::Win32::Graphics::Gdi::GetMonitorInfoW;
+use windows::Win32::Graphics::Gdi::HDC;
+use windows::Win32::Graphics::Gdi::HMONITOR;
+use windows::Win32::Graphics::Gdi::MONITOR_DEFAULTTONEAREST;
+use windows::Win32::Graphics::Gdi::MONITORINFO;
+use windows::Win32::Graphics::Gdi::MonitorFromWindow;
+use windows::Win32::UI::WindowsAndMessaging::MONITORINFOF_PRIMARY;
+use windows::core::BOOL;
+
+/// A display monitor and its geometry in virtual-screen coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MonitorInfo {
+    pub handle: HMONITOR,
+    /// The full bounds of the monitor.
+    pub rect: WindowRect,
+    /// The bounds excluding the taskbar and docked app bars.
+    pub work_area: WindowRect,
+    pub is_primary: bool,
+}
+
+impl MonitorInfo {
+    /// <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-getmonitorinfow>
+    pub fn from_handle(handle: HMONITOR) -> eyre::Result<MonitorInfo> {
+        let mut info = MONITORINFO {
+            cbSize: size_of::<MONITORINFO>() as u32,
+            ..Default::default()
+        };
+        if !unsafe { GetMonitorInfoW(handle, &mut info) }.as_bool() {
+            bail!(
+                "Failed to get monitor info for {:?}: {}",
+                handle,
+                last_error_context()
+            );
+        }
+        Ok(MonitorInfo {
+            handle,
+            rect: info.rcMonitor.into(),
+            work_area: info.rcWork.into(),
+            is_primary: info.dwFlags & MONITORINFOF_PRIMARY != 0,
+        })
+    }
+}
+
+/// Returns the monitor containing most of `hwnd`, or the nearest one if it is off-screen.
+/// <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-monitorfromwindow>
+pub fn monitor_for_window(hwnd: HWND) -> eyre::Result<MonitorInfo> {
+    let handle = unsafe { MonitorFromWindow(hwnd, MONITOR_DEFAULTTONEAREST) };
+    MonitorInfo::from_handle(handle)
+}
+
+/// Lists the attached display monitors.
+/// <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-enumdisplaymonitors>
+pub fn list_monitors() -> eyre::Result<Vec<MonitorInfo>> {
+    let mut handles: Vec<HMONITOR> = Vec::new();
+    let ok = unsafe {
+        EnumDisplayMonitors(
+            None,
+            None,
+            Some(enum_monitor_proc),
+            LPARAM(&mut handles as *mut Vec<HMONITOR> as isize),
+        )
+    };
+    if !ok.as_bool() {
+        bail!(
+            "Failed to enumerate display monitors: {}",
+            last_error_context()
+        );
+    }
+    handles.into_iter().map(MonitorInfo::from_handle).collect()
+}
+
+unsafe extern "system" fn enum_monitor_proc(
+    monitor: HMONITOR,
+    _hdc: HDC,
+    _rect: *mut RECT,
+    lparam: LPARAM,
+) -> BOOL {
+    let handles = unsafe { &mut *(lparam.0 as *mut Vec<HMONITOR>) };
+    handles.push(monitor);
+    true.into()
+}
+
+#[cfg(test)]
+mod test {
+    use super::list_monitors;
+
+    #[test]
+    fn work_area_is_within_monitor() -> eyre::Result<()> {
+        for monitor in list_monitors()? {
+            assert!(monitor.work_area.left >= monitor.rect.left);
+            assert!(monitor.work_area.top >= monitor.rect.top);
+            assert!(monitor.work_area.right <= monitor.rect.right);
+            assert!(monitor.work_area.bottom <= monitor.rect.bottom);
+        }
+        Ok(())
+    }
+}
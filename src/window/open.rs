@@ -1,14 +1,27 @@
 use crate::event_loop::run_message_loop;
 use crate::module::get_current_module;
 use crate::string::EasyPCWSTR;
-use eyre::{Result, WrapErr, bail};
+use eyre::Result;
+use eyre::WrapErr;
+use eyre::bail;
 use std::sync::OnceLock;
-use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, WPARAM};
-use windows::Win32::UI::WindowsAndMessaging::{
-    CW_USEDEFAULT, CreateWindowExW, DefWindowProcW, PostQuitMessage, RegisterClassExW,
-    SW_SHOW, ShowWindow, WINDOW_EX_STYLE, WM_CLOSE, WM_DESTROY, WNDCLASSEXW,
-    WS_OVERLAPPEDWINDOW, WS_VISIBLE,
-};
+use windows::Win32::Foundation::HWND;
+use windows::Win32::Foundation::LPARAM;
+use windows::Win32::Foundation::LRESULT;
+use windows::Win32::Foundation::WPARAM;
+use windows::Win32::UI::WindowsAndMessaging::CW_USEDEFAULT;
+use windows::Win32::UI::WindowsAndMessaging::CreateWindowExW;
+use windows::Win32::UI::WindowsAndMessaging::DefWindowProcW;
+use windows::Win32::UI::WindowsAndMessaging::PostQuitMessage;
+use windows::Win32::UI::WindowsAndMessaging::RegisterClassExW;
+use windows::Win32::UI::WindowsAndMessaging::SW_SHOW;
+use windows::Win32::UI::WindowsAndMessaging::ShowWindow;
+use windows::Win32::UI::WindowsAndMessaging::WINDOW_EX_STYLE;
+use windows::Win32::UI::WindowsAndMessaging::WM_CLOSE;
+use windows::Win32::UI::WindowsAndMessaging::WM_DESTROY;
+use windows::Win32::UI::WindowsAndMessaging::WNDCLASSEXW;
+use windows::Win32::UI::WindowsAndMessaging::WS_OVERLAPPEDWINDOW;
+use windows::Win32::UI::WindowsAndMessaging::WS_VISIBLE;
 use windows::core::w;
 
 const BASIC_WINDOW_CLASS_NAME: windows::core::PCWSTR = w!("TeamyWindowsBasicWindow");
@@ -18,11 +31,12 @@ pub fn create_basic_window(title: &str) -> Result<HWND> {
 
     let instance = get_current_module()?;
     let title = title.easy_pcwstr()?;
+    let title_ptr = unsafe { title.as_ptr() };
     let hwnd = unsafe {
         CreateWindowExW(
             WINDOW_EX_STYLE::default(),
             BASIC_WINDOW_CLASS_NAME,
-            title.as_ptr(),
+            title_ptr,
             WS_OVERLAPPEDWINDOW | WS_VISIBLE,
             CW_USEDEFAULT,
             CW_USEDEFAULT,
@@ -67,7 +81,8 @@ fn register_basic_window_class() -> Result<()> {
 
     let atom = unsafe { RegisterClassExW(&window_class) };
     if atom == 0 {
-        return Err(windows::core::Error::from_thread()).wrap_err("Failed to register basic window class");
+        return Err(windows::core::Error::from_thread())
+            .wrap_err("Failed to register basic window class");
     }
 
     Ok(())
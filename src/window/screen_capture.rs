@@ -0,0 +1,190 @@
+use crate::error::last_error_context;
+use crate::hicon::DeleteDCGuard;
+use crate::hicon::OwnedHbitmap;
+use crate::hicon::ReleaseDCGuard;
+use crate::hicon::SelectObjectGuard;
+use crate::window::MonitorInfo;
+use crate::window::WindowRect;
+use eyre::Context;
+use eyre::ensure;
+use image::RgbaImage;
+use std::ffi::c_void;
+use windows::Win32::Graphics::Gdi::BI_RGB;
+use windows::Win32::Graphics::Gdi::BITMAPINFO;
+use windows::Win32::Graphics::Gdi::BITMAPINFOHEADER;
+use windows::Win32::Graphics::Gdi::BitBlt;
+use windows::Win32::Graphics::Gdi::CAPTUREBLT;
+use windows::Win32::Graphics::Gdi::CreateCompatibleDC;
+use windows::Win32::Graphics::Gdi::CreateDIBSection;
+use windows::Win32::Graphics::Gdi::DIB_RGB_COLORS;
+use windows::Win32::Graphics::Gdi::GdiFlush;
+use windows::Win32::Graphics::Gdi::GetDC;
+use windows::Win32::Graphics::Gdi::HMONITOR;
+use windows::Win32::Graphics::Gdi::SRCCOPY;
+use windows::Win32::Graphics::Gdi::SelectObject;
+use windows::Win32::UI::WindowsAndMessaging::GetSystemMetrics;
+use windows::Win32::UI::WindowsAndMessaging::SM_CXVIRTUALSCREEN;
+use windows::Win32::UI::WindowsAndMessaging::SM_CYVIRTUALSCREEN;
+use windows::Win32::UI::WindowsAndMessaging::SM_XVIRTUALSCREEN;
+use windows::Win32::UI::WindowsAndMessaging::SM_YVIRTUALSCREEN;
+
+/// The bounding box of all monitors in virtual-screen coordinates.
+///
+/// Its origin is the top-left of the primary monitor, so `left` and `top` are negative
+/// when a monitor sits to the left of or above it.
+/// <https://learn.microsoft.com/en-us/windows/win32/gdi/the-virtual-screen>
+pub fn virtual_desktop_rect() -> WindowRect {
+    let left = unsafe { GetSystemMetrics(SM_XVIRTUALSCREEN) };
+    let top = unsafe { GetSystemMetrics(SM_YVIRTUALSCREEN) };
+    let width = unsafe { GetSystemMetrics(SM_CXVIRTUALSCREEN) };
+    let height = unsafe { GetSystemMetrics(SM_CYVIRTUALSCREEN) };
+    WindowRect {
+        left,
+        top,
+        right: left + width,
+        bottom: top + height,
+    }
+}
+
+/// Screenshots the whole of one monitor.
+pub fn capture_monitor(monitor: HMONITOR) -> eyre::Result<RgbaImage> {
+    let info = MonitorInfo::from_handle(monitor)?;
+    capture_screen_rect(info.rect)
+}
+
+/// Screenshots every monitor at once, laid out as they are arranged in display settings.
+///
+/// Areas of the bounding box not covered by any monitor come out black.
+pub fn capture_virtual_desktop() -> eyre::Result<RgbaImage> {
+    capture_screen_rect(virtual_desktop_rect())
+}
+
+/// Screenshots `rect`, given in virtual-screen coordinates, by `BitBlt`ing the screen DC into a DIB section.
+///
+/// Layered windows are included. Coordinates are physical pixels only when the process is
+/// per-monitor DPI aware, see [`crate::window::set_process_dpi_aware`]; otherwise Windows scales them.
+/// <https://learn.microsoft.com/en-us/windows/win32/gdi/capturing-an-image>
+pub fn capture_screen_rect(rect: WindowRect) -> eyre::Result<RgbaImage> {
+    let width = rect.width();
+    let height = rect.height();
+    ensure!(
+        width > 0 && height > 0,
+        "Capture area must not be empty, got {}x{}",
+        width,
+        height
+    );
+
+    let screen_device_context = ReleaseDCGuard(unsafe { GetDC(None) });
+    ensure!(
+        !screen_device_context.is_invalid(),
+        "Failed to get the screen device context"
+    );
+    let memory_device_context =
+        DeleteDCGuard(unsafe { CreateCompatibleDC(Some(*screen_device_context)) });
+    ensure!(
+        !memory_device_context.is_invalid(),
+        "Failed to create a memory device context: {}",
+        last_error_context()
+    );
+
+    let mut bitmap_info = BITMAPINFO::default();
+    bitmap_info.bmiHeader.biSize = std::mem::size_of::<BITMAPINFOHEADER>() as u32;
+    bitmap_info.bmiHeader.biWidth = width;
+    bitmap_info.bmiHeader.biHeight = -height; // top-down
+    bitmap_info.bmiHeader.biPlanes = 1;
+    bitmap_info.bmiHeader.biBitCount = 32;
+    bitmap_info.bmiHeader.biCompression = BI_RGB.0;
+
+    let mut bits: *mut c_void = std::ptr::null_mut();
+    let raw_bitmap = unsafe {
+        CreateDIBSection(
+            Some(*memory_device_context),
+            &bitmap_info,
+            DIB_RGB_COLORS,
+            &mut bits,
+            None,
+            0,
+        )
+    }
+    .wrap_err("Failed to create DIB section for screen capture")?;
+    let bitmap = unsafe { OwnedHbitmap::new(raw_bitmap) };
+    ensure!(!bits.is_null(), "CreateDIBSection returned no pixel buffer");
+
+    {
+        let previous = unsafe { SelectObject(*memory_device_context, bitmap.as_raw().into()) };
+        let _restore = SelectObjectGuard(*memory_device_context, previous);
+        unsafe {
+            BitBlt(
+                *memory_device_context,
+                0,
+                0,
+                width,
+                height,
+                Some(*screen_device_context),
+                rect.left,
+                rect.top,
+                SRCCOPY | CAPTUREBLT,
+            )
+        }
+        .wrap_err("Failed to copy screen contents")?;
+    }
+    _ = unsafe { GdiFlush() };
+
+    let pixel_bytes = width as usize * height as usize * 4;
+    let bgra = unsafe { std::slice::from_raw_parts(bits as *const u8, pixel_bytes) };
+    let mut rgba = Vec::with_capacity(pixel_bytes);
+    for pixel in bgra.chunks_exact(4) {
+        // The screen has no alpha channel, so the DIB's alpha byte is undefined
+        rgba.extend_from_slice(&[pixel[2], pixel[1], pixel[0], 255]);
+    }
+    drop(bitmap);
+
+    RgbaImage::from_raw(width as u32, height as u32, rgba)
+        .ok_or_else(|| eyre::eyre!("Captured pixel buffer does not match {width}x{height}"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::capture_monitor;
+    use super::capture_screen_rect;
+    use super::virtual_desktop_rect;
+    use crate::window::WindowRect;
+    use crate::window::list_monitors;
+
+    #[test]
+    fn virtual_desktop_contains_every_monitor() -> eyre::Result<()> {
+        let desktop = virtual_desktop_rect();
+        for monitor in list_monitors()? {
+            assert!(monitor.rect.left >= desktop.left);
+            assert!(monitor.rect.top >= desktop.top);
+            assert!(monitor.rect.right <= desktop.right);
+            assert!(monitor.rect.bottom <= desktop.bottom);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn captures_monitor_at_its_size() -> eyre::Result<()> {
+        let Some(monitor) = list_monitors()?.into_iter().next() else {
+            return Ok(());
+        };
+        let image = capture_monitor(monitor.handle)?;
+        assert_eq!(
+            image.dimensions(),
+            (monitor.rect.width() as u32, monitor.rect.height() as u32)
+        );
+        assert!(image.pixels().all(|pixel| pixel.0[3] == 255));
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_empty_rect() {
+        let empty = WindowRect {
+            left: -10,
+            top: 0,
+            right: -10,
+            bottom: 10,
+        };
+        assert!(capture_screen_rect(empty).is_err());
+    }
+}
@@ -0,0 +1,153 @@
+use crate::window::WindowRect;
+use crate::window::monitor_for_window;
+use eyre::Context;
+use tracing::debug;
+use windows::Win32::Foundation::HWND;
+use windows::Win32::Foundation::RECT;
+use windows::Win32::Graphics::Dwm::DWMWA_EXTENDED_FRAME_BOUNDS;
+use windows::Win32::Graphics::Dwm::DwmGetWindowAttribute;
+use windows::Win32::UI::WindowsAndMessaging::GetWindowRect;
+use windows::Win32::UI::WindowsAndMessaging::IsIconic;
+use windows::Win32::UI::WindowsAndMessaging::IsZoomed;
+use windows::Win32::UI::WindowsAndMessaging::SW_RESTORE;
+use windows::Win32::UI::WindowsAndMessaging::SWP_NOACTIVATE;
+use windows::Win32::UI::WindowsAndMessaging::SWP_NOZORDER;
+use windows::Win32::UI::WindowsAndMessaging::SetWindowPos;
+use windows::Win32::UI::WindowsAndMessaging::ShowWindow;
+
+/// Where [`snap_window`] places a window within its monitor's work area.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapPosition {
+    Left,
+    Right,
+    Top,
+    Bottom,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+    /// Fills the work area without putting the window in the maximized state.
+    Maximize,
+}
+
+impl SnapPosition {
+    /// Computes the target rect for this position within `work_area`.
+    pub fn rect_within(self, work_area: WindowRect) -> WindowRect {
+        let mid_x = work_area.left + work_area.width() / 2;
+        let mid_y = work_area.top + work_area.height() / 2;
+        let WindowRect {
+            left,
+            top,
+            right,
+            bottom,
+        } = work_area;
+        let (left, top, right, bottom) = match self {
+            SnapPosition::Left => (left, top, mid_x, bottom),
+            SnapPosition::Right => (mid_x, top, right, bottom),
+            SnapPosition::Top => (left, top, right, mid_y),
+            SnapPosition::Bottom => (left, mid_y, right, bottom),
+            SnapPosition::TopLeft => (left, top, mid_x, mid_y),
+            SnapPosition::TopRight => (mid_x, top, right, mid_y),
+            SnapPosition::BottomLeft => (left, mid_y, mid_x, bottom),
+            SnapPosition::BottomRight => (mid_x, mid_y, right, bottom),
+            SnapPosition::Maximize => (left, top, right, bottom),
+        };
+        WindowRect {
+            left,
+            top,
+            right,
+            bottom,
+        }
+    }
+}
+
+/// Moves `hwnd` to `position` within the work area of the monitor it is currently on.
+pub fn snap_window(hwnd: HWND, position: SnapPosition) -> eyre::Result<WindowRect> {
+    let monitor = monitor_for_window(hwnd)?;
+    let target = position.rect_within(monitor.work_area);
+    debug!(?position, ?target, "Snapping window");
+    set_window_rect(hwnd, target)?;
+    Ok(target)
+}
+
+/// Moves and resizes `hwnd` so its visible frame covers `rect`, restoring it first if minimized or maximized.
+///
+/// Windows 10+ pads top-level windows with invisible resize borders, so the window rect is grown by the
+/// difference between it and the DWM frame bounds to keep snapped windows flush with each other.
+/// <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-setwindowpos>
+pub fn set_window_rect(hwnd: HWND, rect: WindowRect) -> eyre::Result<()> {
+    let is_minimized = unsafe { IsIconic(hwnd) }.as_bool();
+    let is_maximized = unsafe { IsZoomed(hwnd) }.as_bool();
+    if is_minimized || is_maximized {
+        let _ = unsafe { ShowWindow(hwnd, SW_RESTORE) };
+    }
+
+    let (left, top, right, bottom) = invisible_border(hwnd).unwrap_or_default();
+    unsafe {
+        SetWindowPos(
+            hwnd,
+            None,
+            rect.left - left,
+            rect.top - top,
+            rect.width() + left + right,
+            rect.height() + top + bottom,
+            SWP_NOZORDER | SWP_NOACTIVATE,
+        )
+    }
+    .wrap_err("Failed to set window position")?;
+    Ok(())
+}
+
+/// The thickness of the invisible border on each side of `hwnd`, as (left, top, right, bottom).
+fn invisible_border(hwnd: HWND) -> Option<(i32, i32, i32, i32)> {
+    let mut window_rect = RECT::default();
+    unsafe { GetWindowRect(hwnd, &mut window_rect) }.ok()?;
+    let mut frame_rect = RECT::default();
+    unsafe {
+        DwmGetWindowAttribute(
+            hwnd,
+            DWMWA_EXTENDED_FRAME_BOUNDS,
+            &mut frame_rect as *mut RECT as *mut _,
+            size_of::<RECT>() as u32,
+        )
+    }
+    .ok()?;
+    Some((
+        frame_rect.left - window_rect.left,
+        frame_rect.top - window_rect.top,
+        window_rect.right - frame_rect.right,
+        window_rect.bottom - frame_rect.bottom,
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use super::SnapPosition;
+    use crate::window::WindowRect;
+
+    #[test]
+    fn halves_and_quarters_tile_the_work_area() {
+        // A work area offset from the origin, like a secondary monitor with the taskbar at the top
+        let work_area = WindowRect {
+            left: 1920,
+            top: 40,
+            right: 3840,
+            bottom: 1080,
+        };
+        let left = SnapPosition::Left.rect_within(work_area);
+        let right = SnapPosition::Right.rect_within(work_area);
+        assert_eq!(left.right, right.left);
+        assert_eq!(left.width() + right.width(), work_area.width());
+
+        let top_left = SnapPosition::TopLeft.rect_within(work_area);
+        let bottom_right = SnapPosition::BottomRight.rect_within(work_area);
+        assert_eq!(top_left.left, 1920);
+        assert_eq!(top_left.top, 40);
+        assert_eq!(bottom_right.right, 3840);
+        assert_eq!(bottom_right.bottom, 1080);
+        assert_eq!(top_left.right, bottom_right.left);
+        assert_eq!(top_left.bottom, bottom_right.top);
+
+        assert_eq!(SnapPosition::Maximize.rect_within(work_area), work_area);
+    }
+}
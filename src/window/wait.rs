@@ -0,0 +1,27 @@
+use crate::window::WindowInfo;
+use crate::window::enumerate_windows;
+use std::time::Duration;
+use std::time::Instant;
+
+/// Polls [`enumerate_windows`] every `interval` until a window matches `predicate`.
+///
+/// Returns `None` if `timeout` elapses first; with no timeout, or one too large to represent, this waits indefinitely.
+pub fn wait_for_window(
+    mut predicate: impl FnMut(&WindowInfo) -> bool,
+    timeout: Option<Duration>,
+    interval: Duration,
+) -> eyre::Result<Option<WindowInfo>> {
+    let deadline = timeout.and_then(|timeout| Instant::now().checked_add(timeout));
+    loop {
+        if let Some(window) = enumerate_windows()?.into_iter().find(&mut predicate) {
+            return Ok(Some(window));
+        }
+        let now = Instant::now();
+        let sleep = match deadline {
+            Some(deadline) if now >= deadline => return Ok(None),
+            Some(deadline) => interval.min(deadline - now),
+            None => interval,
+        };
+        std::thread::sleep(sleep);
+    }
+}
@@ -0,0 +1,162 @@
+use crate::module::get_current_module;
+use crate::string::EasyPCWSTR;
+use eyre::Context;
+use tracing::debug;
+use windows::Win32::Foundation::ERROR_CLASS_ALREADY_EXISTS;
+use windows::Win32::Foundation::HWND;
+use windows::Win32::Foundation::LPARAM;
+use windows::Win32::Foundation::LRESULT;
+use windows::Win32::Foundation::WPARAM;
+use windows::Win32::UI::WindowsAndMessaging::CS_HREDRAW;
+use windows::Win32::UI::WindowsAndMessaging::CS_VREDRAW;
+use windows::Win32::UI::WindowsAndMessaging::CW_USEDEFAULT;
+use windows::Win32::UI::WindowsAndMessaging::CreateWindowExW;
+use windows::Win32::UI::WindowsAndMessaging::DefWindowProcW;
+use windows::Win32::UI::WindowsAndMessaging::HWND_MESSAGE;
+use windows::Win32::UI::WindowsAndMessaging::RegisterClassExW;
+use windows::Win32::UI::WindowsAndMessaging::WINDOW_EX_STYLE;
+use windows::Win32::UI::WindowsAndMessaging::WINDOW_STYLE;
+use windows::Win32::UI::WindowsAndMessaging::WNDCLASS_STYLES;
+use windows::Win32::UI::WindowsAndMessaging::WNDCLASSEXW;
+use windows::Win32::UI::WindowsAndMessaging::WNDPROC;
+use windows::Win32::UI::WindowsAndMessaging::WS_OVERLAPPEDWINDOW;
+
+/// Builder for registering a window class and creating a window from it.
+///
+/// Registering a class that already exists is not an error, so the same class name can be reused for multiple windows.
+/// <https://learn.microsoft.com/en-us/windows/win32/api/winuser/nf-winuser-createwindowexw>
+#[derive(Debug, Clone)]
+pub struct WindowBuilder {
+    class_name: String,
+    title: String,
+    class_style: WNDCLASS_STYLES,
+    style: WINDOW_STYLE,
+    ex_style: WINDOW_EX_STYLE,
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+    message_only: bool,
+    window_proc: WNDPROC,
+}
+
+impl WindowBuilder {
+    pub fn new(class_name: impl Into<String>) -> Self {
+        Self {
+            class_name: class_name.into(),
+            title: String::new(),
+            class_style: CS_HREDRAW | CS_VREDRAW,
+            style: WS_OVERLAPPEDWINDOW,
+            ex_style: WINDOW_EX_STYLE::default(),
+            x: CW_USEDEFAULT,
+            y: CW_USEDEFAULT,
+            width: CW_USEDEFAULT,
+            height: CW_USEDEFAULT,
+            message_only: false,
+            window_proc: Some(default_window_proc),
+        }
+    }
+
+    pub fn title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    pub fn class_style(mut self, class_style: WNDCLASS_STYLES) -> Self {
+        self.class_style = class_style;
+        self
+    }
+
+    pub fn style(mut self, style: WINDOW_STYLE) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn ex_style(mut self, ex_style: WINDOW_EX_STYLE) -> Self {
+        self.ex_style = ex_style;
+        self
+    }
+
+    pub fn position(mut self, x: i32, y: i32) -> Self {
+        self.x = x;
+        self.y = y;
+        self
+    }
+
+    pub fn size(mut self, width: i32, height: i32) -> Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    /// Parent the window to `HWND_MESSAGE`, producing an invisible window that only receives messages.
+    /// <https://learn.microsoft.com/en-us/windows/win32/winmsg/window-features#message-only-windows>
+    pub fn message_only(mut self, message_only: bool) -> Self {
+        self.message_only = message_only;
+        self
+    }
+
+    pub fn window_proc(mut self, window_proc: WNDPROC) -> Self {
+        self.window_proc = window_proc;
+        self
+    }
+
+    /// Registers the window class (if needed) and creates the window.
+    pub fn build(self) -> eyre::Result<HWND> {
+        let instance = get_current_module()?;
+        let class_name = self.class_name.as_str().easy_pcwstr()?;
+        let title = self.title.as_str().easy_pcwstr()?;
+
+        let window_class = WNDCLASSEXW {
+            cbSize: std::mem::size_of::<WNDCLASSEXW>() as u32,
+            style: self.class_style,
+            lpfnWndProc: self.window_proc,
+            hInstance: instance.into(),
+            lpszClassName: unsafe { class_name.as_ptr() },
+            ..Default::default()
+        };
+
+        debug!(class_name = %self.class_name, "Registering window class");
+        let atom = unsafe { RegisterClassExW(&window_class) };
+        if atom == 0 {
+            let error = windows::core::Error::from_thread();
+            if error.code() != ERROR_CLASS_ALREADY_EXISTS.to_hresult() {
+                return Err(error).wrap_err_with(|| {
+                    format!("Failed to register window class {}", self.class_name)
+                });
+            }
+            debug!(class_name = %self.class_name, "Window class already registered");
+        }
+
+        let parent = self.message_only.then_some(HWND_MESSAGE);
+        debug!(title = %self.title, message_only = self.message_only, "Creating window");
+        let hwnd = unsafe {
+            CreateWindowExW(
+                self.ex_style,
+                &class_name,
+                &title,
+                self.style,
+                self.x,
+                self.y,
+                self.width,
+                self.height,
+                parent,
+                None,
+                Some(instance.into()),
+                None,
+            )
+        }
+        .wrap_err_with(|| format!("Failed to create window of class {}", self.class_name))?;
+
+        Ok(hwnd)
+    }
+}
+
+unsafe extern "system" fn default_window_proc(
+    hwnd: HWND,
+    message: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    unsafe { DefWindowProcW(hwnd, message, wparam, lparam) }
+}
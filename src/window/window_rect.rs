@@ -0,0 +1,113 @@
+use facet::Facet;
+use windows::Win32::Foundation::POINT;
+use windows::Win32::Foundation::RECT;
+
+/// Window bounds in screen coordinates.
+///
+/// Like `RECT`, `right` and `bottom` are exclusive.
+#[derive(Facet, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct WindowRect {
+    pub left: i32,
+    pub top: i32,
+    pub right: i32,
+    pub bottom: i32,
+}
+
+impl WindowRect {
+    pub fn width(&self) -> i32 {
+        self.right - self.left
+    }
+
+    pub fn height(&self) -> i32 {
+        self.bottom - self.top
+    }
+
+    /// Whether the rect covers no pixels.
+    pub fn is_empty(&self) -> bool {
+        self.width() <= 0 || self.height() <= 0
+    }
+
+    /// The middle of the rect, rounded towards the top-left.
+    pub fn center(&self) -> POINT {
+        POINT {
+            x: self.left + self.width() / 2,
+            y: self.top + self.height() / 2,
+        }
+    }
+
+    pub fn contains(&self, point: POINT) -> bool {
+        (self.left..self.right).contains(&point.x) && (self.top..self.bottom).contains(&point.y)
+    }
+
+    /// The overlap of both rects, `None` when they don't overlap.
+    pub fn intersect(&self, other: &WindowRect) -> Option<WindowRect> {
+        let intersection = WindowRect {
+            left: self.left.max(other.left),
+            top: self.top.max(other.top),
+            right: self.right.min(other.right),
+            bottom: self.bottom.min(other.bottom),
+        };
+        (!intersection.is_empty()).then_some(intersection)
+    }
+}
+
+impl From<RECT> for WindowRect {
+    fn from(rect: RECT) -> Self {
+        Self {
+            left: rect.left,
+            top: rect.top,
+            right: rect.right,
+            bottom: rect.bottom,
+        }
+    }
+}
+
+impl From<WindowRect> for RECT {
+    fn from(rect: WindowRect) -> Self {
+        Self {
+            left: rect.left,
+            top: rect.top,
+            right: rect.right,
+            bottom: rect.bottom,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::WindowRect;
+    use windows::Win32::Foundation::POINT;
+
+    #[test]
+    fn geometry() {
+        let a = WindowRect {
+            left: -100,
+            top: 0,
+            right: 100,
+            bottom: 50,
+        };
+        assert_eq!((a.width(), a.height()), (200, 50));
+        assert_eq!(a.center(), POINT { x: 0, y: 25 });
+        assert!(a.contains(POINT { x: -100, y: 0 }));
+        assert!(!a.contains(POINT { x: 100, y: 0 }));
+
+        let b = WindowRect {
+            left: 50,
+            top: 25,
+            right: 300,
+            bottom: 300,
+        };
+        assert_eq!(
+            a.intersect(&b),
+            Some(WindowRect {
+                left: 50,
+                top: 25,
+                right: 100,
+                bottom: 50,
+            })
+        );
+        let beside = WindowRect { left: 100, ..a };
+        assert_eq!(a.intersect(&beside), None);
+    }
+}
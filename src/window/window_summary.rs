@@ -0,0 +1,55 @@
+use crate::window::WindowInfo;
+use crate::window::WindowRect;
+use facet::Facet;
+
+/// Plain-data view of a [`WindowInfo`] for facet output and roam services.
+#[derive(Facet, Debug, Clone, PartialEq, Eq)]
+pub struct WindowSummary {
+    /// The window handle as an integer, as accepted by `window focus`.
+    pub hwnd: u64,
+    pub title: String,
+    pub class_name: String,
+    pub exe_path: String,
+    pub rect: WindowRect,
+    pub process_id: u32,
+    pub thread_id: u32,
+    pub is_visible: bool,
+    pub is_on_taskbar: bool,
+}
+
+impl From<&WindowInfo> for WindowSummary {
+    fn from(window: &WindowInfo) -> Self {
+        Self {
+            hwnd: window.hwnd.0 as u64,
+            title: window.title.clone(),
+            class_name: window.class_name.clone(),
+            exe_path: window.exe_path.clone(),
+            rect: window.rect,
+            process_id: window.process_id,
+            thread_id: window.thread_id,
+            is_visible: window.is_visible,
+            is_on_taskbar: window.is_on_taskbar,
+        }
+    }
+}
+
+impl From<WindowInfo> for WindowSummary {
+    fn from(window: WindowInfo) -> Self {
+        Self::from(&window)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::WindowSummary;
+    use crate::window::enumerate_windows;
+
+    #[test]
+    fn it_works() -> eyre::Result<()> {
+        let windows = enumerate_windows()?;
+        let summaries: Vec<WindowSummary> = windows.iter().map(WindowSummary::from).collect();
+        let json = facet_json::to_string(&summaries)?;
+        assert!(json.starts_with('['));
+        Ok(())
+    }
+}
@@ -5,23 +5,26 @@ use clap::Parser;
 use teamy_windows::cli::Cli;
 use teamy_windows::cli::to_args::ToArgs;
 
+/// Deterministic xorshift byte source so each iteration sees different input without pulling in a rng crate.
+fn seeded_bytes(seed: u64, len: usize) -> Vec<u8> {
+    let mut state = seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1;
+    (0..len)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state as u8
+        })
+        .collect()
+}
+
 #[test]
 fn fuzz_cli_args_roundtrip() {
-    // Generate 100 arbitrary CLI instances and test roundtrip conversion
-    let mut data = vec![42u8; 1024]; // Create owned data
-    let mut rng = arbitrary::Unstructured::new(&data);
-
-    for i in 0..100 {
-        // Generate an arbitrary CLI instance
-        let cli = match Cli::arbitrary(&mut rng) {
-            Ok(cli) => cli,
-            Err(_) => {
-                // If we run out of data, refresh with new seed
-                data = vec![i as u8; 1024];
-                rng = arbitrary::Unstructured::new(&data);
-                Cli::arbitrary(&mut rng).expect("Failed to generate CLI instance")
-            }
-        };
+    // Every iteration gets fresh bytes so all subcommands and field values get exercised
+    for i in 0..2000u64 {
+        let data = seeded_bytes(i, 1024);
+        let mut rng = arbitrary::Unstructured::new(&data);
+        let cli = Cli::arbitrary(&mut rng).expect("Failed to generate CLI instance");
 
         // Convert CLI to args
         let args = cli.to_args();